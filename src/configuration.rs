@@ -1,4 +1,25 @@
-use crate::{arguments_parser::ArgumentsParser, system, file_utilities};
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use hyper::header::HeaderName;
+use ipnet::IpNet;
+
+use crate::{
+    arguments_parser::ArgumentsParser,
+    system,
+    file_utilities,
+    unix_socket_utilities,
+    forwarding_utilities::{self, TrustedProxyHeader},
+    proxy_protocol_utilities,
+    request_limit_utilities,
+    rate_limiter::{self, RateLimitRule},
+    access_logger::{self, AccessLogFormat},
+    diagnostic_logger::{self, LogFormat, LogLevels},
+    tracer::{self, TraceExporter},
+    cors_utilities::{self, CorsPolicy},
+    default_headers_utilities::SecurityHeaderPolicy,
+    cookie_utilities::{self, CookieSealer},
+    url_encoded_utilities::{self, UrlEncodedParser},
+};
 
 const DEFAULT_WORKER_THREAD_COUNT: &str = "16";
 const DEFAULT_MAXIMUM_BLOCKING_THREAD_COUNT: &str = "1024";
@@ -6,17 +27,91 @@ const DEFAULT_BLOCKING_THREAD_KEEP_ALIVE_TIMEOUT_IN_MILLISECONDS: &str = "10000"
 const DEFAULT_HOST: &str = "127.0.0.1";
 const DEFAULT_PORT: &str = "61798";
 const DEFAULT_POWERED_BY: &str = "Volcano";
+const DEFAULT_SERVER_NAME: &str = "volcano-http-server";
 const IS_WEB_SOCKET_SERVER_ENABLED_BY_DEFAULT: &str = "false";
 const IS_TLS_ENABLED_BY_DEFAULT: &str = "false";
 const DEFAULT_TLS_CERTIFICATE_PATH: &str = "tls_certificate.pem";
 const DEFAULT_TLS_PRIVATE_KEY_PATH: &str = "tls_private_key.key";
 const IS_HTTP2_ENABLED_BY_DEFAULT: &str = "false";
+const IS_HTTP3_ENABLED_BY_DEFAULT: &str = "false";
+const DEFAULT_HTTP3_ALTERNATIVE_SERVICE_MAX_AGE_IN_SECONDS: &str = "86400";
+const DEFAULT_UNIX_SOCKET_PATH: &str = "";
+const DEFAULT_UNIX_SOCKET_MODE: &str = "660";
+const DEFAULT_UNIX_SOCKET_OWNER_ID: &str = "";
+const DEFAULT_UNIX_SOCKET_GROUP_ID: &str = "";
+const DEFAULT_LISTEN_FILE_DESCRIPTOR: &str = "";
+const DEFAULT_TRUSTED_PROXIES: &str = "";
+const DEFAULT_TRUSTED_PROXY_HEADER: &str = "x-forwarded-for";
+const DEFAULT_PROXY_PROTOCOL_LISTENERS: &str = "";
+const DEFAULT_PROXY_PROTOCOL_TIMEOUT_IN_MILLISECONDS: &str = "5000";
+const DEFAULT_MAXIMUM_BODY_SIZE_IN_BYTES: &str = "10485760";
+const DEFAULT_MAXIMUM_BODY_SIZES_PER_CONTENT_TYPE: &str = "";
+const DEFAULT_MAXIMUM_HEADER_COUNT: &str = "100";
+const DEFAULT_MAXIMUM_HEADER_SIZE_IN_BYTES: &str = "16384";
+const DEFAULT_MAXIMUM_URI_LENGTH: &str = "8192";
+const DEFAULT_MAXIMUM_CONNECTION_COUNT: &str = "10000";
+const DEFAULT_MAXIMUM_CONNECTION_COUNT_PER_IP_ADDRESS: &str = "0";
+const DEFAULT_CONNECTION_QUEUE_TIMEOUT_IN_MILLISECONDS: &str = "0";
+const DEFAULT_CONNECTION_STATISTICS_PATH: &str = "";
+const DEFAULT_RATE_LIMITS: &str = "";
+const DEFAULT_RATE_LIMIT_KEY_HEADER_NAME: &str = "";
+const DEFAULT_TLS_HANDSHAKE_TIMEOUT_IN_MILLISECONDS: &str = "10000";
+const DEFAULT_HEADER_READ_TIMEOUT_IN_MILLISECONDS: &str = "10000";
+const DEFAULT_BODY_READ_TIMEOUT_IN_MILLISECONDS: &str = "30000";
+const DEFAULT_KEEP_ALIVE_TIMEOUT_IN_MILLISECONDS: &str = "60000";
+const DEFAULT_HTTP2_KEEP_ALIVE_INTERVAL_IN_MILLISECONDS: &str = "0";
+const DEFAULT_HTTP2_KEEP_ALIVE_TIMEOUT_IN_MILLISECONDS: &str = "20000";
+const DEFAULT_ACCESS_LOG_PATH: &str = "";
+const DEFAULT_ACCESS_LOG_FORMAT: &str = "combined";
+const DEFAULT_ACCESS_LOG_MAXIMUM_SIZE_IN_BYTES: &str = "0";
+const DEFAULT_ACCESS_LOG_ROTATION_INTERVAL_IN_SECONDS: &str = "0";
+const DEFAULT_ACCESS_LOG_MAXIMUM_FILE_COUNT: &str = "7";
+const DEFAULT_LOG_LEVEL: &str = "info";
+const DEFAULT_LOG_FORMAT: &str = "human";
+const DEFAULT_LOG_OUTPUT: &str = "stderr";
+const DEFAULT_METRICS_PATH: &str = "";
+const DEFAULT_METRICS_ADDRESS: &str = "";
+const DEFAULT_ADMIN_LISTENER_METRICS_PATH: &str = "/metrics";
+const DEFAULT_LIVENESS_PATH: &str = "";
+const DEFAULT_READINESS_PATH: &str = "";
+const DEFAULT_READINESS_PING_INTERVAL_IN_MILLISECONDS: &str = "5000";
+const DEFAULT_READINESS_PING_TIMEOUT_IN_MILLISECONDS: &str = "1000";
+const DEFAULT_SHUTDOWN_DRAIN_TIMEOUT_IN_MILLISECONDS: &str = "0";
+const DEFAULT_REQUEST_ID_HEADER_NAME: &str = "x-request-id";
+const DEFAULT_TRACE_EXPORTER: &str = "none";
+const DEFAULT_TRACE_OTLP_ENDPOINT: &str = "http://127.0.0.1:4318/v1/traces";
+const DEFAULT_TRACE_FILE_PATH: &str = "traces.json";
+const DEFAULT_TRACE_SAMPLE_RATIO: &str = "1";
+const DEFAULT_CORS_ALLOWED_ORIGINS: &str = "";
+const DEFAULT_CORS_ALLOWED_METHODS: &str = "GET,HEAD,PUT,PATCH,POST,DELETE";
+const DEFAULT_CORS_ALLOWED_HEADERS: &str = "";
+const DEFAULT_CORS_EXPOSED_HEADERS: &str = "";
+const ARE_CORS_CREDENTIALS_ALLOWED_BY_DEFAULT: &str = "false";
+const DEFAULT_CORS_MAX_AGE_IN_SECONDS: &str = "0";
+const ARE_SECURITY_HEADERS_ENABLED_BY_DEFAULT: &str = "false";
+const DEFAULT_STRICT_TRANSPORT_SECURITY: &str = "max-age=31536000; includeSubDomains";
+const DEFAULT_REFERRER_POLICY: &str = "strict-origin-when-cross-origin";
+const DEFAULT_FRAME_OPTIONS: &str = "DENY";
+const DEFAULT_CONTENT_SECURITY_POLICY: &str = "";
+const DEFAULT_COOKIE_SECRET_PATH: &str = "";
+const DEFAULT_URL_ENCODED_PARSER: &str = "simple";
+const ARE_RAW_HEADERS_PRESERVED_BY_DEFAULT: &str = "false";
+const DEFAULT_ROUTES_PATH: &str = "";
+const DEFAULT_UPSTREAM_CONNECT_TIMEOUT_IN_MILLISECONDS: &str = "5000";
+const DEFAULT_UPSTREAM_RESPONSE_TIMEOUT_IN_MILLISECONDS: &str = "60000";
+const DEFAULT_UPSTREAM_RETRY_COUNT: &str = "1";
+const DEFAULT_UPSTREAM_HEALTH_CHECK_INTERVAL_IN_MILLISECONDS: &str = "10000";
+// empty means the trust store of the system is used...
+const DEFAULT_UPSTREAM_CA_PATH: &str = "";
+const DEFAULT_RESPONSE_CACHE_SIZE_IN_BYTES: &str = "0";
+const ARE_ETAGS_ENABLED_BY_DEFAULT: &str = "false";
 
 #[derive(Clone)]
 pub struct Configuration {
     pub host: String,
     pub port: u16,
     pub powered_by: String,
+    pub server_name: String,
     pub worker_thread_count: usize,
     pub maximum_blocking_thread_count: usize,
     pub blocking_thread_keep_alive_timeout_in_milliseconds: u64,
@@ -25,6 +120,86 @@ pub struct Configuration {
     pub tls_certificate_path: String,
     pub tls_private_key_path: String,
     pub is_http2_enabled: bool,
+    pub is_http3_enabled: bool,
+    pub http3_port: u16,
+    pub http3_alternative_service_max_age_in_seconds: u64,
+    pub unix_socket_path: String,
+    pub unix_socket_mode: Option<u32>,
+    pub unix_socket_owner_id: Option<u32>,
+    pub unix_socket_group_id: Option<u32>,
+    pub listen_file_descriptor: Option<u32>,
+    pub trusted_proxies: Vec<IpNet>,
+    pub trusted_proxy_header: TrustedProxyHeader,
+    pub proxy_protocol_listeners: Vec<String>,
+    pub proxy_protocol_timeout_in_milliseconds: u64,
+    pub maximum_body_size_in_bytes: u64,
+    pub maximum_body_sizes_per_content_type: Vec<(String, u64)>,
+    pub maximum_header_count: usize,
+    pub maximum_header_size_in_bytes: usize,
+    pub maximum_uri_length: usize,
+    pub maximum_connection_count: usize,
+    pub maximum_connection_count_per_ip_address: usize,
+    pub connection_queue_timeout_in_milliseconds: u64,
+    pub connection_statistics_path: String,
+    pub rate_limit_rules: Vec<RateLimitRule>,
+    pub rate_limit_key_header_name: String,
+    pub tls_handshake_timeout_in_milliseconds: u64,
+    pub header_read_timeout_in_milliseconds: u64,
+    pub body_read_timeout_in_milliseconds: u64,
+    pub keep_alive_timeout_in_milliseconds: u64,
+    pub http2_keep_alive_interval_in_milliseconds: u64,
+    pub http2_keep_alive_timeout_in_milliseconds: u64,
+    pub access_log_path: String,
+    pub access_log_format: AccessLogFormat,
+    pub access_log_maximum_size_in_bytes: u64,
+    pub access_log_rotation_interval_in_seconds: u64,
+    pub access_log_maximum_file_count: usize,
+    pub log_levels: LogLevels,
+    pub log_format: LogFormat,
+    pub log_output: String,
+    pub metrics_path: String,
+    pub metrics_address: String,
+    pub liveness_path: String,
+    pub readiness_path: String,
+    pub readiness_ping_interval_in_milliseconds: u64,
+    pub readiness_ping_timeout_in_milliseconds: u64,
+    pub shutdown_drain_timeout_in_milliseconds: u64,
+    pub request_id_header_name: String,
+    pub trace_exporter: TraceExporter,
+    pub trace_sample_ratio: f64,
+    pub cors_policy: CorsPolicy,
+    pub security_header_policy: SecurityHeaderPolicy,
+    pub cookie_secret_path: String,
+    pub cookie_sealer: CookieSealer,
+    pub url_encoded_parser: UrlEncodedParser,
+    pub are_raw_headers_preserved: bool,
+    pub routes_path: String,
+    pub upstream_connect_timeout_in_milliseconds: u64,
+    pub upstream_response_timeout_in_milliseconds: u64,
+    pub upstream_retry_count: usize,
+    pub upstream_health_check_interval_in_milliseconds: u64,
+    pub upstream_ca_path: String,
+    pub response_cache_size_in_bytes: usize,
+    pub are_etags_enabled: bool,
+}
+
+fn parse_optional_u32(argument_name: &str, argument_value: String) -> Option<u32> {
+    // empty value means the value is not specified...
+    if argument_value.len() == 0 {
+        return None;
+    }
+
+    let parse_result = argument_value.parse::<u32>();
+
+    if parse_result.is_err() {
+        let error = parse_result.unwrap_err();
+
+        eprintln!("An error occurred while parsing {} ('{}') as u32: {}", argument_name, argument_value, error);
+
+        return None;
+    }
+
+    return Some(parse_result.unwrap());
 }
 
 impl Configuration {
@@ -32,6 +207,7 @@ impl Configuration {
         let host = arguments_parser.get_as_string("host", DEFAULT_HOST);
         let port = arguments_parser.get_as_u16("port", DEFAULT_PORT);
         let powered_by = arguments_parser.get_as_string("poweredBy", DEFAULT_POWERED_BY);
+        let server_name = arguments_parser.get_as_string("serverHeader", DEFAULT_SERVER_NAME);
         let worker_thread_count = arguments_parser.get_as_usize("workerThreadCount", DEFAULT_WORKER_THREAD_COUNT);
         let maximum_blocking_thread_count = arguments_parser.get_as_usize(
             "maximumBlockingThreadCount",
@@ -49,7 +225,16 @@ impl Configuration {
         let mut is_tls_enabled = arguments_parser.get_as_boolean("enableTls", IS_TLS_ENABLED_BY_DEFAULT);
         let mut tls_certificate_path = String::from("");
         let mut tls_private_key_path = String::from("");
-        let mut is_http2_enabled = false;
+        let mut is_http3_enabled = false;
+        // HTTP/3 listens on the same port number (over UDP) by default...
+        let http3_port = arguments_parser.get_as_u16("http3Port", port.to_string().as_str());
+        let http3_alternative_service_max_age_in_seconds = arguments_parser.get_as_u64(
+            "http3AltSvcMaxAge",
+            DEFAULT_HTTP3_ALTERNATIVE_SERVICE_MAX_AGE_IN_SECONDS,
+        );
+        // HTTP/2 is negotiated via ALPN with TLS. without TLS, clients may either speak
+        // HTTP/2 from the start (prior knowledge) or upgrade via "Upgrade: h2c"...
+        let is_http2_enabled = arguments_parser.get_as_boolean("enableHttp2", IS_HTTP2_ENABLED_BY_DEFAULT);
 
         // if user has requested to enable TLS...
         if is_tls_enabled {
@@ -68,16 +253,214 @@ impl Configuration {
                 // we shall assign the certificate and the key paths...
                 tls_certificate_path = cloned_temporary_tls_certificate_path;
                 tls_private_key_path = cloned_temporary_tls_private_key_path;
-                // we shall also check if HTTP/2 shall be enabled...
-                is_http2_enabled = arguments_parser.get_as_boolean("enableHttp2", IS_HTTP2_ENABLED_BY_DEFAULT);
+                // QUIC always requires TLS, so we shall check for HTTP/3 here...
+                is_http3_enabled = arguments_parser.get_as_boolean("enableHttp3", IS_HTTP3_ENABLED_BY_DEFAULT);
             }
         }
 
+        // listens on a Unix domain socket instead of TCP if the path is specified...
+        let unix_socket_path = arguments_parser.get_as_string("unixSocketPath", DEFAULT_UNIX_SOCKET_PATH);
+        let unix_socket_mode = unix_socket_utilities::parse_file_mode(
+            arguments_parser.get_as_string("unixSocketMode", DEFAULT_UNIX_SOCKET_MODE).as_str());
+        let unix_socket_owner_id = parse_optional_u32("unixSocketOwnerId",
+            arguments_parser.get_as_string("unixSocketOwnerId", DEFAULT_UNIX_SOCKET_OWNER_ID));
+        let unix_socket_group_id = parse_optional_u32("unixSocketGroupId",
+            arguments_parser.get_as_string("unixSocketGroupId", DEFAULT_UNIX_SOCKET_GROUP_ID));
+        // uses an already opened listening socket instead of binding one...
+        let listen_file_descriptor = parse_optional_u32("listenFd",
+            arguments_parser.get_as_string("listenFd", DEFAULT_LISTEN_FILE_DESCRIPTOR));
+        // forwarding headers are only honoured when set by these proxies...
+        let trusted_proxies = forwarding_utilities::parse_trusted_proxies(
+            arguments_parser.get_as_string("trustedProxies", DEFAULT_TRUSTED_PROXIES).as_str());
+        let trusted_proxy_header = forwarding_utilities::parse_trusted_proxy_header(
+            arguments_parser.get_as_string("trustedProxyHeader", DEFAULT_TRUSTED_PROXY_HEADER).as_str());
+        // listeners that expect a PROXY protocol header before anything else...
+        let proxy_protocol_listeners = proxy_protocol_utilities::parse_listener_selectors(
+            arguments_parser.get_as_string("proxyProtocolListeners", DEFAULT_PROXY_PROTOCOL_LISTENERS).as_str());
+        let proxy_protocol_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "proxyProtocolTimeout",
+            DEFAULT_PROXY_PROTOCOL_TIMEOUT_IN_MILLISECONDS,
+        );
+        // requests exceeding these limits are rejected with 413, 414 or 431...
+        let maximum_body_size_in_bytes = arguments_parser.get_as_u64("maximumBodySize", DEFAULT_MAXIMUM_BODY_SIZE_IN_BYTES);
+        let maximum_body_sizes_per_content_type = request_limit_utilities::parse_maximum_body_sizes_per_content_type(
+            arguments_parser.get_as_string("maximumBodySizePerContentType", DEFAULT_MAXIMUM_BODY_SIZES_PER_CONTENT_TYPE).as_str());
+        let maximum_header_count = arguments_parser.get_as_usize("maximumHeaderCount", DEFAULT_MAXIMUM_HEADER_COUNT);
+        let maximum_header_size_in_bytes = arguments_parser.get_as_usize("maximumHeaderSize", DEFAULT_MAXIMUM_HEADER_SIZE_IN_BYTES);
+        let maximum_uri_length = arguments_parser.get_as_usize("maximumUriLength", DEFAULT_MAXIMUM_URI_LENGTH);
+        // zero means unlimited...
+        let maximum_connection_count = arguments_parser.get_as_usize(
+            "maximumConnectionCount",
+            DEFAULT_MAXIMUM_CONNECTION_COUNT,
+        );
+        let maximum_connection_count_per_ip_address = arguments_parser.get_as_usize(
+            "maximumConnectionCountPerIp",
+            DEFAULT_MAXIMUM_CONNECTION_COUNT_PER_IP_ADDRESS,
+        );
+        // excess connections wait this long for a slot before they are closed...
+        let connection_queue_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "connectionQueueTimeout",
+            DEFAULT_CONNECTION_QUEUE_TIMEOUT_IN_MILLISECONDS,
+        );
+        let connection_statistics_path = arguments_parser.get_as_string(
+            "connectionStatisticsPath",
+            DEFAULT_CONNECTION_STATISTICS_PATH,
+        );
+        // requests are limited per client and path prefix (e.g. "/api=100/60")...
+        let rate_limit_rules = rate_limiter::parse_rules(
+            arguments_parser.get_as_string("rateLimits", DEFAULT_RATE_LIMITS).as_str());
+        // if specified, clients are identified by this header (e.g. an API key) instead of IP address...
+        let rate_limit_key_header_name = arguments_parser.get_as_string(
+            "rateLimitKeyHeader",
+            DEFAULT_RATE_LIMIT_KEY_HEADER_NAME,
+        ).to_lowercase();
+        // timeouts against slow or idle clients (zero disables the timeout)...
+        let tls_handshake_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "tlsHandshakeTimeout",
+            DEFAULT_TLS_HANDSHAKE_TIMEOUT_IN_MILLISECONDS,
+        );
+        let header_read_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "headerReadTimeout",
+            DEFAULT_HEADER_READ_TIMEOUT_IN_MILLISECONDS,
+        );
+        let body_read_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "bodyReadTimeout",
+            DEFAULT_BODY_READ_TIMEOUT_IN_MILLISECONDS,
+        );
+        let keep_alive_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "keepAliveTimeout",
+            DEFAULT_KEEP_ALIVE_TIMEOUT_IN_MILLISECONDS,
+        );
+        let http2_keep_alive_interval_in_milliseconds = arguments_parser.get_as_u64(
+            "http2KeepAliveInterval",
+            DEFAULT_HTTP2_KEEP_ALIVE_INTERVAL_IN_MILLISECONDS,
+        );
+        let http2_keep_alive_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "http2KeepAliveTimeout",
+            DEFAULT_HTTP2_KEEP_ALIVE_TIMEOUT_IN_MILLISECONDS,
+        );
+        // empty path disables access logging, "stderr" writes to the standard error...
+        let access_log_path = arguments_parser.get_as_string("accessLog", DEFAULT_ACCESS_LOG_PATH);
+        let access_log_format = access_logger::parse_format(
+            arguments_parser.get_as_string("accessLogFormat", DEFAULT_ACCESS_LOG_FORMAT).as_str());
+        // the access log file is rotated when it grows too large and/or periodically (zero disables)...
+        let access_log_maximum_size_in_bytes = arguments_parser.get_as_u64(
+            "accessLogMaximumSize",
+            DEFAULT_ACCESS_LOG_MAXIMUM_SIZE_IN_BYTES,
+        );
+        let access_log_rotation_interval_in_seconds = arguments_parser.get_as_u64(
+            "accessLogRotationInterval",
+            DEFAULT_ACCESS_LOG_ROTATION_INTERVAL_IN_SECONDS,
+        );
+        let access_log_maximum_file_count = arguments_parser.get_as_usize(
+            "accessLogMaximumFileCount",
+            DEFAULT_ACCESS_LOG_MAXIMUM_FILE_COUNT,
+        );
+        // a default level optionally followed by levels per module (e.g. "info,http_server=debug")...
+        let log_levels = diagnostic_logger::parse_levels(
+            arguments_parser.get_as_string("logLevel", DEFAULT_LOG_LEVEL).as_str());
+        let log_format = diagnostic_logger::parse_format(
+            arguments_parser.get_as_string("logFormat", DEFAULT_LOG_FORMAT).as_str());
+        // "stderr", "journald" or a file path...
+        let log_output = arguments_parser.get_as_string("logOutput", DEFAULT_LOG_OUTPUT);
+        // metrics are served on the regular listeners unless a separate admin address is specified...
+        let mut metrics_path = arguments_parser.get_as_string("metricsPath", DEFAULT_METRICS_PATH);
+        let metrics_address = arguments_parser.get_as_string("metricsAddress", DEFAULT_METRICS_ADDRESS);
+
+        // the admin listener serves nothing but metrics, so it needs no explicit path...
+        if metrics_address.len() > 0 && metrics_path.len() == 0 {
+            metrics_path = String::from(DEFAULT_ADMIN_LISTENER_METRICS_PATH);
+        }
+
+        let liveness_path = arguments_parser.get_as_string("livenessPath", DEFAULT_LIVENESS_PATH);
+        let readiness_path = arguments_parser.get_as_string("readinessPath", DEFAULT_READINESS_PATH);
+        // the parent process is pinged periodically to tell whether it is still responsive...
+        let readiness_ping_interval_in_milliseconds = arguments_parser.get_as_u64(
+            "readinessPingInterval",
+            DEFAULT_READINESS_PING_INTERVAL_IN_MILLISECONDS,
+        );
+        let readiness_ping_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "readinessPingTimeout",
+            DEFAULT_READINESS_PING_TIMEOUT_IN_MILLISECONDS,
+        );
+        let shutdown_drain_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "shutdownDrainTimeout",
+            DEFAULT_SHUTDOWN_DRAIN_TIMEOUT_IN_MILLISECONDS,
+        );
+        // the request ID is read from and echoed in this header...
+        let mut request_id_header_name = arguments_parser
+            .get_as_string("requestIdHeader", DEFAULT_REQUEST_ID_HEADER_NAME)
+            .trim()
+            .to_lowercase();
+
+        if HeaderName::from_bytes(request_id_header_name.as_bytes()).is_err() {
+            eprintln!("Invalid request ID header name '{}'. Falling back to {}.", request_id_header_name, DEFAULT_REQUEST_ID_HEADER_NAME);
+
+            request_id_header_name = String::from(DEFAULT_REQUEST_ID_HEADER_NAME);
+        }
+
+        // spans are exported to an OTLP collector or to a file...
+        let trace_exporter = tracer::parse_exporter(
+            arguments_parser.get_as_string("traceExporter", DEFAULT_TRACE_EXPORTER).as_str(),
+            arguments_parser.get_as_string("traceOtlpEndpoint", DEFAULT_TRACE_OTLP_ENDPOINT).as_str(),
+            arguments_parser.get_as_string("traceFile", DEFAULT_TRACE_FILE_PATH).as_str(),
+        );
+        let trace_sample_ratio = tracer::parse_sample_ratio(
+            arguments_parser.get_as_string("traceSampleRatio", DEFAULT_TRACE_SAMPLE_RATIO).as_str());
+        // CORS is handled by the server as soon as any origin is allowed...
+        let cors_policy = cors_utilities::create_policy(
+            arguments_parser.get_as_string("corsAllowedOrigins", DEFAULT_CORS_ALLOWED_ORIGINS).as_str(),
+            arguments_parser.get_as_string("corsAllowedMethods", DEFAULT_CORS_ALLOWED_METHODS).as_str(),
+            arguments_parser.get_as_string("corsAllowedHeaders", DEFAULT_CORS_ALLOWED_HEADERS).as_str(),
+            arguments_parser.get_as_string("corsExposedHeaders", DEFAULT_CORS_EXPOSED_HEADERS).as_str(),
+            arguments_parser.get_as_boolean("corsAllowCredentials", ARE_CORS_CREDENTIALS_ALLOWED_BY_DEFAULT),
+            arguments_parser.get_as_u64("corsMaxAge", DEFAULT_CORS_MAX_AGE_IN_SECONDS),
+        );
+        // security headers are added to every response the parent process has not set them on...
+        let security_header_policy = SecurityHeaderPolicy {
+            is_enabled: arguments_parser.get_as_boolean("enableSecurityHeaders", ARE_SECURITY_HEADERS_ENABLED_BY_DEFAULT),
+            strict_transport_security: arguments_parser
+                .get_as_string("strictTransportSecurity", DEFAULT_STRICT_TRANSPORT_SECURITY),
+            referrer_policy: arguments_parser.get_as_string("referrerPolicy", DEFAULT_REFERRER_POLICY),
+            frame_options: arguments_parser.get_as_string("frameOptions", DEFAULT_FRAME_OPTIONS),
+            content_security_policy: arguments_parser
+                .get_as_string("contentSecurityPolicy", DEFAULT_CONTENT_SECURITY_POLICY),
+        };
+        // the secret is read from a file so that it does not show up in the process list...
+        let cookie_secret_path = arguments_parser.get_as_string("cookieSecretPath", DEFAULT_COOKIE_SECRET_PATH);
+        let cookie_sealer = CookieSealer::new(cookie_utilities::load_secret(cookie_secret_path.as_str()));
+        let url_encoded_parser = url_encoded_utilities::parse_parser(
+            arguments_parser.get_as_string("urlEncodedParser", DEFAULT_URL_ENCODED_PARSER).as_str());
+        let are_raw_headers_preserved = arguments_parser.get_as_boolean("preserveRawHeaders", ARE_RAW_HEADERS_PRESERVED_BY_DEFAULT);
+        // requests no route matches are forwarded to the parent process...
+        // the routes themselves are loaded once the diagnostic logger is initialized...
+        let routes_path = arguments_parser.get_as_string("routesPath", DEFAULT_ROUTES_PATH);
+        // applies to the upstreams of proxy routes (zero disables the timeout)...
+        let upstream_connect_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "upstreamConnectTimeout",
+            DEFAULT_UPSTREAM_CONNECT_TIMEOUT_IN_MILLISECONDS,
+        );
+        let upstream_response_timeout_in_milliseconds = arguments_parser.get_as_u64(
+            "upstreamResponseTimeout",
+            DEFAULT_UPSTREAM_RESPONSE_TIMEOUT_IN_MILLISECONDS,
+        );
+        let upstream_retry_count = arguments_parser.get_as_usize("upstreamRetryCount", DEFAULT_UPSTREAM_RETRY_COUNT);
+        // zero disables health checking...
+        let upstream_health_check_interval_in_milliseconds = arguments_parser.get_as_u64(
+            "upstreamHealthCheckInterval",
+            DEFAULT_UPSTREAM_HEALTH_CHECK_INTERVAL_IN_MILLISECONDS,
+        );
+        let upstream_ca_path = arguments_parser.get_as_string("upstreamCaPath", DEFAULT_UPSTREAM_CA_PATH);
+        // zero disables the response cache...
+        let response_cache_size_in_bytes = arguments_parser.get_as_usize("responseCacheSize", DEFAULT_RESPONSE_CACHE_SIZE_IN_BYTES);
+        let are_etags_enabled = arguments_parser.get_as_boolean("enableEtags", ARE_ETAGS_ENABLED_BY_DEFAULT);
+
         // prepares the configuration...
         let configuration = Configuration {
             host,
             port,
             powered_by,
+            server_name,
             worker_thread_count,
             maximum_blocking_thread_count,
             blocking_thread_keep_alive_timeout_in_milliseconds,
@@ -86,6 +469,67 @@ impl Configuration {
             tls_certificate_path,
             tls_private_key_path,
             is_http2_enabled,
+            is_http3_enabled,
+            http3_port,
+            http3_alternative_service_max_age_in_seconds,
+            unix_socket_path,
+            unix_socket_mode,
+            unix_socket_owner_id,
+            unix_socket_group_id,
+            listen_file_descriptor,
+            trusted_proxies,
+            trusted_proxy_header,
+            proxy_protocol_listeners,
+            proxy_protocol_timeout_in_milliseconds,
+            maximum_body_size_in_bytes,
+            maximum_body_sizes_per_content_type,
+            maximum_header_count,
+            maximum_header_size_in_bytes,
+            maximum_uri_length,
+            maximum_connection_count,
+            maximum_connection_count_per_ip_address,
+            connection_queue_timeout_in_milliseconds,
+            connection_statistics_path,
+            rate_limit_rules,
+            rate_limit_key_header_name,
+            tls_handshake_timeout_in_milliseconds,
+            header_read_timeout_in_milliseconds,
+            body_read_timeout_in_milliseconds,
+            keep_alive_timeout_in_milliseconds,
+            http2_keep_alive_interval_in_milliseconds,
+            http2_keep_alive_timeout_in_milliseconds,
+            access_log_path,
+            access_log_format,
+            access_log_maximum_size_in_bytes,
+            access_log_rotation_interval_in_seconds,
+            access_log_maximum_file_count,
+            log_levels,
+            log_format,
+            log_output,
+            metrics_path,
+            metrics_address,
+            liveness_path,
+            readiness_path,
+            readiness_ping_interval_in_milliseconds,
+            readiness_ping_timeout_in_milliseconds,
+            shutdown_drain_timeout_in_milliseconds,
+            request_id_header_name,
+            trace_exporter,
+            trace_sample_ratio,
+            cors_policy,
+            security_header_policy,
+            cookie_secret_path,
+            cookie_sealer,
+            url_encoded_parser,
+            are_raw_headers_preserved,
+            routes_path,
+            upstream_connect_timeout_in_milliseconds,
+            upstream_response_timeout_in_milliseconds,
+            upstream_retry_count,
+            upstream_health_check_interval_in_milliseconds,
+            upstream_ca_path,
+            response_cache_size_in_bytes,
+            are_etags_enabled,
         };
 
         return configuration;
@@ -95,9 +539,177 @@ impl Configuration {
         system::print_version_information();
 
         println!();
-        println!("Host: {}", self.host);
-        println!("Port: {}", self.port);
+
+        if self.unix_socket_path.len() == 0 {
+            println!("Host: {}", self.host);
+            println!("Port: {}", self.port);
+        } else {
+            println!("Unix socket path: {}", self.unix_socket_path);
+            println!("Unix socket mode: {}", self.unix_socket_mode.map_or(String::from("unchanged"), |mode| format!("{:o}", mode)));
+            println!("Unix socket owner ID: {}", self.unix_socket_owner_id.map_or(String::from("unchanged"), |id| id.to_string()));
+            println!("Unix socket group ID: {}", self.unix_socket_group_id.map_or(String::from("unchanged"), |id| id.to_string()));
+        }
+
+        if self.listen_file_descriptor.is_some() {
+            println!("Listener file descriptor: {}", self.listen_file_descriptor.unwrap());
+        }
+
+        println!("Trusted proxies: {}", self.trusted_proxies.iter()
+            .map(|network| network.to_string()).collect::<Vec<String>>().join(", "));
+        println!("Trusted proxy header: {:?}", self.trusted_proxy_header);
+
+        if self.proxy_protocol_listeners.len() > 0 {
+            println!("PROXY protocol listeners: {}", self.proxy_protocol_listeners.join(", "));
+            println!("PROXY protocol timeout: {} ms", self.proxy_protocol_timeout_in_milliseconds);
+        }
+
+        println!("Maximum body size: {} bytes", self.maximum_body_size_in_bytes);
+
+        for (content_type, maximum_body_size) in self.maximum_body_sizes_per_content_type.iter() {
+            println!("Maximum body size of {}: {} bytes", content_type, maximum_body_size);
+        }
+
+        println!("Maximum header count: {}", self.maximum_header_count);
+        println!("Maximum header size: {} bytes", self.maximum_header_size_in_bytes);
+        println!("Maximum URI length: {}", self.maximum_uri_length);
+        println!("Maximum connection count: {}", self.maximum_connection_count);
+        println!("Maximum connection count per IP: {}", self.maximum_connection_count_per_ip_address);
+        println!("Connection queue timeout: {} ms", self.connection_queue_timeout_in_milliseconds);
+
+        if self.metrics_path.len() > 0 {
+            println!("Metrics path: {}", self.metrics_path);
+        }
+
+        if self.metrics_address.len() > 0 {
+            println!("Metrics address: {}", self.metrics_address);
+        }
+
+        if self.connection_statistics_path.len() > 0 {
+            println!("Connection statistics path: {}", self.connection_statistics_path);
+        }
+
+        println!("Request ID header: {}", self.request_id_header_name);
+
+        match &self.trace_exporter {
+            TraceExporter::Otlp(endpoint) => println!("Trace exporter: OTLP ({})", endpoint),
+            TraceExporter::File(file_path) => println!("Trace exporter: file ({})", file_path),
+            TraceExporter::None => {},
+        }
+
+        if self.trace_exporter != TraceExporter::None {
+            println!("Trace sample ratio: {}", self.trace_sample_ratio);
+        }
+
+        if self.cors_policy.is_enabled() {
+            println!("CORS allowed origins: {}", self.cors_policy.allowed_origins.join(", "));
+            println!("CORS allowed methods: {}", self.cors_policy.allowed_methods.join(", "));
+            println!("CORS allowed headers: {}", if self.cors_policy.allowed_headers.len() == 0 {
+                String::from("as requested")
+            } else {
+                self.cors_policy.allowed_headers.join(", ")
+            });
+
+            if self.cors_policy.exposed_headers.len() > 0 {
+                println!("CORS exposed headers: {}", self.cors_policy.exposed_headers.join(", "));
+            }
+
+            println!("CORS credentials allowed: {}", self.cors_policy.are_credentials_allowed);
+
+            if self.cors_policy.max_age_in_seconds > 0 {
+                println!("CORS max age: {} seconds", self.cors_policy.max_age_in_seconds);
+            }
+        }
+
+        if self.security_header_policy.is_enabled {
+            println!("Security headers: enabled");
+
+            if self.is_tls_enabled && self.security_header_policy.strict_transport_security.len() > 0 {
+                println!("Strict transport security: {}", self.security_header_policy.strict_transport_security);
+            }
+
+            if self.security_header_policy.referrer_policy.len() > 0 {
+                println!("Referrer policy: {}", self.security_header_policy.referrer_policy);
+            }
+
+            if self.security_header_policy.frame_options.len() > 0 {
+                println!("Frame options: {}", self.security_header_policy.frame_options);
+            }
+
+            if self.security_header_policy.content_security_policy.len() > 0 {
+                println!("Content security policy: {}", self.security_header_policy.content_security_policy);
+            }
+        }
+
+        if self.cookie_sealer.is_enabled() {
+            println!("Cookie secret path: {}", self.cookie_secret_path);
+        }
+
+        println!("URL encoded parser: {:?}", self.url_encoded_parser);
+        println!("Raw headers preserved: {}", self.are_raw_headers_preserved);
+
+        if self.routes_path.len() > 0 {
+            println!("Routes path: {}", self.routes_path);
+            println!("Upstream connect timeout: {} milliseconds", self.upstream_connect_timeout_in_milliseconds);
+            println!("Upstream response timeout: {} milliseconds", self.upstream_response_timeout_in_milliseconds);
+            println!("Upstream retry count: {}", self.upstream_retry_count);
+            println!("Upstream health check interval: {} milliseconds", self.upstream_health_check_interval_in_milliseconds);
+
+            if self.upstream_ca_path.len() > 0 {
+                println!("Upstream CA path: {}", self.upstream_ca_path);
+            }
+        }
+
+        if self.response_cache_size_in_bytes > 0 {
+            println!("Response cache size: {} bytes", self.response_cache_size_in_bytes);
+        }
+
+        println!("ETags enabled: {}", self.are_etags_enabled);
+
+        if self.liveness_path.len() > 0 {
+            println!("Liveness path: {}", self.liveness_path);
+        }
+
+        if self.readiness_path.len() > 0 {
+            println!("Readiness path: {}", self.readiness_path);
+            println!("Readiness ping interval: {} ms", self.readiness_ping_interval_in_milliseconds);
+            println!("Readiness ping timeout: {} ms", self.readiness_ping_timeout_in_milliseconds);
+        }
+
+        if self.shutdown_drain_timeout_in_milliseconds > 0 {
+            println!("Shutdown drain timeout: {} ms", self.shutdown_drain_timeout_in_milliseconds);
+        }
+
+        for rate_limit_rule in self.rate_limit_rules.iter() {
+            println!("Rate limit of {}: {} requests per {} seconds",
+                rate_limit_rule.path_prefix, rate_limit_rule.limit, rate_limit_rule.window_in_seconds);
+        }
+
+        if self.rate_limit_key_header_name.len() > 0 {
+            println!("Rate limit key header: {}", self.rate_limit_key_header_name);
+        }
+
+        println!("Header read timeout: {} ms", self.header_read_timeout_in_milliseconds);
+        println!("Body read timeout: {} ms", self.body_read_timeout_in_milliseconds);
+        println!("Keep alive timeout: {} ms", self.keep_alive_timeout_in_milliseconds);
+
+        if self.access_log_path.len() > 0 {
+            println!("Access log: {}", self.access_log_path);
+            println!("Access log format: {:?}", self.access_log_format);
+            println!("Access log maximum size: {} bytes", self.access_log_maximum_size_in_bytes);
+            println!("Access log rotation interval: {} seconds", self.access_log_rotation_interval_in_seconds);
+            println!("Access log maximum file count: {}", self.access_log_maximum_file_count);
+        }
+
+        println!("Log level: {}", self.log_levels.default_level);
+
+        for (module, level) in self.log_levels.module_levels.iter() {
+            println!("Log level of {}: {}", module, level);
+        }
+
+        println!("Log format: {:?}", self.log_format);
+        println!("Log output: {}", self.log_output);
         println!("Powered by: {}", self.powered_by);
+        println!("Server header: {}", self.server_name);
         println!("Worker thread count: {}", self.worker_thread_count);
         println!(
             "Maximum blocking thread count: {}",
@@ -111,6 +723,13 @@ impl Configuration {
             "WebSocket server enabled: {}",
             self.is_web_socket_server_enabled
         );
+        println!("HTTP/2 enabled: {}", self.is_http2_enabled);
+
+        if self.is_http2_enabled && self.http2_keep_alive_interval_in_milliseconds > 0 {
+            println!("HTTP/2 keep alive interval: {} ms", self.http2_keep_alive_interval_in_milliseconds);
+            println!("HTTP/2 keep alive timeout: {} ms", self.http2_keep_alive_timeout_in_milliseconds);
+        }
+
         println!("TLS enabled: {}", self.is_tls_enabled);
 
         if !self.is_tls_enabled {
@@ -119,6 +738,14 @@ impl Configuration {
 
         println!("TLS certificate path: {}", self.tls_certificate_path);
         println!("TLS private key path: {}", self.tls_private_key_path);
-        println!("HTTP/2 enabled: {}", self.is_http2_enabled);
+        println!("TLS handshake timeout: {} ms", self.tls_handshake_timeout_in_milliseconds);
+        println!("HTTP/3 enabled: {}", self.is_http3_enabled);
+
+        if !self.is_http3_enabled {
+            return;
+        }
+
+        println!("HTTP/3 port: {}", self.http3_port);
+        println!("HTTP/3 Alt-Svc max age: {} seconds", self.http3_alternative_service_max_age_in_seconds);
     }
 }
@@ -0,0 +1,359 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap)]
+
+use bytes::{Buf, Bytes};
+use hyper::{body::HttpBody, Body, Method, Request, Response, StatusCode, Version};
+use quic_rustls::{
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs1KeyDer},
+    version::TLS13,
+    ProtocolVersion,
+};
+use quinn::{crypto::rustls::QuicServerConfig, Endpoint, IdleTimeout, ServerConfig, TransportConfig};
+use std::{io, sync::Arc};
+use log::{error, warn};
+
+use crate::{
+    http_server,
+    http_server_configuration::HttpServerConfiguration,
+    http_server_context::{HttpServerContext, TlsInformation},
+    http_utilities,
+    remote_address::RemoteAddress,
+};
+
+type RequestStream<S> = h3::server::RequestStream<S, Bytes>;
+
+fn create_server_configuration(configuration: &HttpServerConfiguration) -> io::Result<ServerConfig> {
+    // the credentials are loaded the same way as for the TCP listener and only
+    // converted to the types of the newer rustls used by QUIC...
+    let (tls_certificate_chain, tls_private_key) = http_utilities::load_tls_credentials(configuration)?;
+    let tls_certificate_chain = tls_certificate_chain.into_iter()
+        .map(|certificate| CertificateDer::from(certificate.0))
+        .collect();
+    let tls_private_key = PrivateKeyDer::Pkcs1(PrivatePkcs1KeyDer::from(tls_private_key.0));
+    let mut tls_server_configuration = quic_rustls::ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_protocol_versions(&[&TLS13])
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(tls_certificate_chain, tls_private_key))
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+    // HTTP/3 is the only protocol spoken over QUIC...
+    tls_server_configuration.alpn_protocols = vec![b"h3".to_vec()];
+
+    let quic_server_configuration = QuicServerConfig::try_from(tls_server_configuration)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+    let mut server_configuration = ServerConfig::with_crypto(Arc::new(quic_server_configuration));
+    let keep_alive_timeout_option = http_utilities::to_timeout(configuration.keep_alive_timeout_in_milliseconds);
+
+    // QUIC closes idle connections on its own, we only need to tell how long to wait...
+    if keep_alive_timeout_option.is_some() {
+        let idle_timeout = IdleTimeout::try_from(keep_alive_timeout_option.unwrap())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let mut transport_configuration = TransportConfig::default();
+
+        transport_configuration.max_idle_timeout(Some(idle_timeout));
+        server_configuration.transport_config(Arc::new(transport_configuration));
+    }
+
+    return Ok(server_configuration);
+}
+
+fn to_request(request: http::Request<()>, body: Body) -> Option<Request<Body>> {
+    let (parts, _) = request.into_parts();
+    let mut request_builder = Request::builder()
+        .method(parts.method.as_str())
+        .uri(parts.uri.to_string())
+        .version(Version::HTTP_3);
+
+    for (header_name, header_value) in parts.headers.iter() {
+        request_builder = request_builder.header(header_name.as_str(), header_value.as_bytes());
+    }
+
+    let request_result = request_builder.body(body);
+
+    if request_result.is_err() {
+        let error = request_result.unwrap_err();
+
+        error!("An error occurred while converting HTTP/3 request: {}", error);
+
+        return None;
+    }
+
+    return Some(request_result.unwrap());
+}
+
+fn to_response_head(response: &Response<Body>) -> http::Response<()> {
+    let mut response_builder = http::Response::builder().status(response.status().as_u16());
+
+    for (header_name, header_value) in response.headers().iter() {
+        // connection specific headers are not allowed in HTTP/3...
+        if http_utilities::CONNECTION_SPECIFIC_HEADER_NAMES.contains(&header_name.as_str()) {
+            continue;
+        }
+
+        response_builder = response_builder.header(header_name.as_str(), header_value.as_bytes());
+    }
+
+    return response_builder.body(()).unwrap();
+}
+
+async fn forward_request_body_async<S>(mut receive_stream: RequestStream<S>, mut body_sender: hyper::body::Sender)
+where
+    S: h3::quic::RecvStream,
+{
+    loop {
+        let receive_result = receive_stream.recv_data().await;
+        // the types returned by h3 do not implement Debug, so we
+        // cannot use unwrap_err() here...
+        let chunk_option = match receive_result {
+            Ok(chunk_option) => chunk_option,
+            Err(error) => {
+                warn!("An error occurred while reading HTTP/3 request body: {}", error);
+
+                body_sender.abort();
+
+                return;
+            },
+        };
+
+        // no more data means the request body is complete...
+        if chunk_option.is_none() {
+            return;
+        }
+
+        let mut chunk = chunk_option.unwrap();
+        let bytes = chunk.copy_to_bytes(chunk.remaining());
+
+        // if the request handler is no longer interested in the body...
+        if body_sender.send_data(bytes).await.is_err() {
+            return;
+        }
+    }
+}
+
+fn is_body_allowed(method: &Method, status_code: StatusCode) -> bool {
+    // responses to HEAD requests, informational, 204 and 304 responses never have a body...
+    return method != Method::HEAD
+        && !status_code.is_informational()
+        && status_code != StatusCode::NO_CONTENT
+        && status_code != StatusCode::NOT_MODIFIED;
+}
+
+async fn send_response_async<S>(
+    mut send_stream: RequestStream<S>,
+    method: &Method,
+    response: Response<Body>,
+) -> Result<(), h3::error::StreamError>
+where
+    S: h3::quic::SendStream<Bytes>,
+{
+    send_stream.send_response(to_response_head(&response)).await?;
+
+    // if the response must not have a body, we shall only finish the stream...
+    if !is_body_allowed(method, response.status()) {
+        return send_stream.finish().await;
+    }
+
+    let mut body = response.into_body();
+
+    while let Some(chunk) = HttpBody::data(&mut body).await {
+        if chunk.is_err() {
+            let error = chunk.unwrap_err();
+
+            error!("An error occurred while reading response body for HTTP/3: {}", error);
+
+            break;
+        }
+
+        send_stream.send_data(chunk.unwrap()).await?;
+    }
+
+    return send_stream.finish().await;
+}
+
+fn record_transferred_bytes(connection: &quinn::Connection, context: &HttpServerContext) {
+    let connection_statistics = connection.stats();
+
+    // QUIC connections are not streams, hence we rely on the statistics kept by QUIC...
+    context.metrics.record_transferred_bytes(connection_statistics.udp_rx.bytes, connection_statistics.udp_tx.bytes);
+}
+
+async fn handle_connection_async(
+    incoming: quinn::Incoming,
+    context: HttpServerContext,
+) {
+    let remote_address = RemoteAddress::Ip(incoming.remote_address());
+    let connection_permit_option = context.connection_limiter.acquire_async(&remote_address).await;
+
+    // if the connection limit is reached, we shall refuse the connection before the handshake...
+    if connection_permit_option.is_none() {
+        warn!("QUIC connection from {} is refused because the connection limit is reached.", remote_address);
+
+        incoming.refuse();
+
+        return;
+    }
+
+    let _connection_permit = connection_permit_option.unwrap();
+    let connection_result = incoming.await;
+
+    if connection_result.is_err() {
+        let error = connection_result.unwrap_err();
+
+        warn!("An error occurred while accepting QUIC connection: {}", error);
+        // QUIC connections fail to be established mostly due to the TLS handshake...
+        context.metrics.record_tls_handshake_failure();
+
+        return;
+    }
+
+    let connection = connection_result.unwrap();
+    let quic_connection = connection.clone();
+    let mut context = context.for_connection();
+
+    // QUIC always uses TLS 1.3...
+    context.tls_information = Some(TlsInformation {
+        protocol_version: format!("{:?}", ProtocolVersion::TLSv1_3),
+        cipher_suite: None,
+    });
+
+    let h3_connection_result = h3::server::Connection::new(h3_quinn::Connection::new(connection)).await;
+    let mut h3_connection: h3::server::Connection<h3_quinn::Connection, Bytes> = match h3_connection_result {
+        Ok(h3_connection) => h3_connection,
+        Err(error) => {
+            warn!("An error occurred while establishing HTTP/3 connection: {}", error);
+            record_transferred_bytes(&quic_connection, &context);
+
+            return;
+        },
+    };
+
+    loop {
+        let accept_result = h3_connection.accept().await;
+        let request_resolver_option = match accept_result {
+            Ok(request_resolver_option) => request_resolver_option,
+            Err(error) => {
+                // closing the connection without an error is not worth reporting...
+                if !error.is_h3_no_error() {
+                    error!("An unexpected HTTP/3 connection error occurred: {}", error);
+                }
+
+                break;
+            },
+        };
+
+        // the client has gracefully closed the connection...
+        if request_resolver_option.is_none() {
+            break;
+        }
+
+        let request_resolver = request_resolver_option.unwrap();
+        let context = context.clone();
+
+        tokio::spawn(async move {
+            let resolve_result = request_resolver.resolve_request().await;
+            let (request, request_stream) = match resolve_result {
+                Ok(resolved_request) => resolved_request,
+                Err(error) => {
+                    warn!("An error occurred while reading HTTP/3 request: {}", error);
+
+                    return;
+                },
+            };
+            let (send_stream, receive_stream) = request_stream.split();
+            let (body_sender, body) = Body::channel();
+            let request_option = to_request(request, body);
+
+            if request_option.is_none() {
+                return;
+            }
+
+            // the request body is streamed to the handler while it is being read...
+            tokio::spawn(forward_request_body_async(receive_stream, body_sender));
+
+            let request = request_option.unwrap();
+            let method = request.method().clone();
+            let request_id = context.id_generator.generate();
+            let response = http_server::handle_request_async(
+                request_id,
+                remote_address,
+                request,
+                &context,
+            ).await;
+            let send_result = send_response_async(send_stream, &method, response).await;
+
+            if send_result.is_err() {
+                let error = send_result.unwrap_err();
+
+                error!("An error occurred while sending HTTP/3 response: {}", error);
+            }
+        });
+    }
+
+    record_transferred_bytes(&quic_connection, &context);
+}
+
+pub async fn start_async(context: HttpServerContext) {
+    let configuration = &context.configuration;
+    let server_configuration_result = create_server_configuration(configuration);
+
+    if server_configuration_result.is_err() {
+        let error = server_configuration_result.unwrap_err();
+
+        error!("An error occurred while creating QUIC server configuration: {}", error);
+
+        return;
+    }
+
+    let socket_address_result =
+        http_utilities::create_socket_address(configuration.host.clone(), configuration.http3_port);
+
+    if socket_address_result.is_err() {
+        let error = socket_address_result.unwrap_err();
+
+        error!(
+            "An error occurred while creating socket address using host {} and port {}: {}",
+            configuration.host, configuration.http3_port, error
+        );
+
+        return;
+    }
+
+    let socket_address = socket_address_result.unwrap();
+    let endpoint_result = Endpoint::server(server_configuration_result.unwrap(), socket_address);
+
+    if endpoint_result.is_err() {
+        let error = endpoint_result.unwrap_err();
+
+        error!("An error occurred while creating QUIC endpoint: {}", error);
+
+        return;
+    }
+
+    let endpoint = endpoint_result.unwrap();
+
+    println!("HTTP/3 server listening on https://{} (QUIC)", socket_address);
+
+    while let Some(incoming) = endpoint.accept().await {
+        tokio::spawn(handle_connection_async(incoming, context.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omits_body_of_head_responses() {
+        assert!(!is_body_allowed(&Method::HEAD, StatusCode::OK));
+        assert!(!is_body_allowed(&Method::HEAD, StatusCode::NOT_FOUND));
+        assert!(is_body_allowed(&Method::GET, StatusCode::OK));
+    }
+
+    #[test]
+    fn omits_body_of_responses_without_content() {
+        assert!(!is_body_allowed(&Method::GET, StatusCode::NO_CONTENT));
+        assert!(!is_body_allowed(&Method::GET, StatusCode::NOT_MODIFIED));
+        assert!(!is_body_allowed(&Method::GET, StatusCode::CONTINUE));
+        assert!(is_body_allowed(&Method::POST, StatusCode::CREATED));
+    }
+}
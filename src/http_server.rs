@@ -1,37 +1,109 @@
-use hyper::{service::service_fn, Body, Request, Response, upgrade::Upgraded};
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero, clippy::assign_op_pattern)]
+
+use chrono::Utc;
+use hyper::{body::HttpBody, service::{service_fn, Service}, http::HeaderValue, Body, Method, Request, Response, StatusCode, upgrade::Upgraded};
+use serde::Deserialize;
 use serde_json::json;
 use tokio_tungstenite::WebSocketStream;
 use tungstenite::{Message, protocol::WebSocketConfig};
 use std::{
     convert::Infallible,
-    net::SocketAddr,
     borrow::BorrowMut,
+    future::Future,
+    pin::Pin,
+    sync::atomic::Ordering,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+    signal::unix::{signal, SignalKind},
+    sync::{mpsc::{self, UnboundedReceiver, UnboundedSender}, watch::Receiver},
+    time::{timeout, Duration, Instant},
 };
-use tokio::{net::TcpListener, sync::watch::Receiver};
+use tokio_rustls::{rustls::ServerConnection, TlsAcceptor};
+use hyper::server::conn::Http;
 use futures::{stream::StreamExt, SinkExt};
+use log::{debug, error, info, warn};
 
 use crate::{
+    access_logger::{AccessLogEntry, AccessLogger},
+    diagnostic_logger,
     http_server_configuration::HttpServerConfiguration,
     http_utilities::{self, SerializableHttpRequest, SerializableHttpResponse},
     web_socket_utilities,
+    h2c_utilities::{self, PrefixedStream},
+    unix_socket_utilities,
+    socket_activation_utilities::InheritedFileDescriptor,
+    proxy_protocol_utilities,
+    request_limit_utilities,
+    request_id_utilities,
+    cors_utilities,
+    default_headers_utilities,
+    cookie_utilities::CookieSealer,
+    connection_limiter::{ConnectionLimiter, PermittedStream},
+    connection_activity::IdleTimeoutStream,
+    raw_header_recorder::RawHeaderRecordingStream,
+    router::{self, PathParameters, RouteAction, RouteMatch},
+    static_file_utilities,
+    reverse_proxy::{self, UpstreamGroup},
+    response_cache::{CacheLookup, CacheRequest, PurgeRequest},
+    metrics::{self, MeteredStream},
+    rate_limiter::RateLimitDecision,
+    forwarding_utilities,
     ipc_handler,
-    id_generator::IdGenerator,
+    listener::Listener,
+    http_server_context::{HttpServerContext, TlsInformation},
+    remote_address::RemoteAddress,
+    http3_server,
+    tracer::{self, Span, SpanContext, SpanKind},
 };
 
+// identifies the listener bound by us when selecting PROXY protocol listeners...
+const DEFAULT_LISTENER_IDENTIFIER: &str = "default";
+const CONTENT_LENGTH_HEADER_NAME: &str = "content-length";
+const USER_AGENT_HEADER_NAME: &str = "user-agent";
+const REFERER_HEADER_NAME: &str = "referer";
+
+type ResponseFuture = Pin<Box<dyn Future<Output = Result<Response<Body>, Infallible>> + Send>>;
+
 async fn get_parent_process_response_async(
     http_request: &SerializableHttpRequest,
-    receiver: &Receiver<(u64, String)>,
+    span_context_option: Option<&SpanContext>,
+    context: &HttpServerContext,
 ) -> Response<Body> {
+    let tracer = &context.tracer;
     // converts http request to JSON...
     let http_request_as_json = http_request.to_string();
     let request_id = http_request.request_id;
+    let started_at = Instant::now();
+    // the reply is pending until the guard is dropped...
+    let waiting_for_reply_guard = context.metrics.begin_waiting_for_reply();
+    let ipc_write_span_option = tracer.start_span("ipc write", SpanKind::Internal, span_context_option);
 
     // writes the http request data to the standard output as JSON...
     ipc_handler::write_line(&http_request_as_json);
+    tracer.end_span(ipc_write_span_option);
+
+    let receiver = context.receiver.clone();
+    let mut wait_span_option = tracer.start_span("wait for parent", SpanKind::Internal, span_context_option);
+
+    if let Some(wait_span) = wait_span_option.as_mut() {
+        wait_span.set_attribute("volcano.ipc.request_id", request_id);
+    }
 
-    let receiver = receiver.clone();
     // reads the specified line...
     let line_read = ipc_handler::read_line_async(request_id, receiver).await;
+
+    tracer.end_span(wait_span_option);
+    drop(waiting_for_reply_guard);
+    context.metrics.record_ipc_round_trip(started_at.elapsed());
+
+    let response = create_parent_process_response(line_read, &context.configuration.cookie_sealer);
+
+    return response;
+}
+
+fn create_parent_process_response(line_read: String, cookie_sealer: &CookieSealer) -> Response<Body> {
     let serializable_http_response_option = SerializableHttpResponse::from(line_read);
 
     if serializable_http_response_option.is_none() {
@@ -42,18 +114,18 @@ async fn get_parent_process_response_async(
     }
 
     // preparing the response...
-    let response = serializable_http_response_option.unwrap().to_response();
+    let response = serializable_http_response_option.unwrap().to_response(cookie_sealer);
 
     return response;
 }
 
 async fn handle_web_socket_stream_async(
     request_id: u64,
-    remote_address: SocketAddr,
+    remote_address: RemoteAddress,
     _receiver: &Receiver<(u64, String)>,
     mut web_socket_stream: WebSocketStream<Upgraded>) {
     let mut message_count = 1_u64;
-    println!("Web socket client connected from {}:{} with ID, '{}'.", remote_address.ip(), remote_address.port(), request_id);
+    info!("Web socket client connected from {} with ID, '{}'.", remote_address, request_id);
 
     loop {
         let message_result_option = web_socket_stream.next().await;
@@ -65,7 +137,7 @@ async fn handle_web_socket_stream_async(
         if message_result.is_err() {
             let error = message_result.unwrap_err();
 
-            eprintln!("An error occurred while handling WebSocket Stream data: {error}");
+            warn!("An error occurred while handling WebSocket Stream data: {error}");
 
             continue;
         }
@@ -83,36 +155,37 @@ async fn handle_web_socket_stream_async(
 
         match message {
             Message::Text(message) => {
-                println!("[{}:{}:{}:{}] message: {}", message_id, request_id, remote_address.ip(), remote_address.port(), message);
+                debug!("[{}:{}:{}] message: {}", message_id, request_id, remote_address, message);
 
                 _ = web_socket_stream.send(Message::text(json!({
                     "requestId": request_id,
                     "messageId": message_id,
-                    "remoteIpAddress": remote_address.ip(),
-                    "remotePort": remote_address.port(),
+                    "remoteIpAddress": remote_address.get_ip_address(),
+                    "remotePort": remote_address.get_port(),
+                    "peerCredentials": remote_address.get_peer_credentials(),
                     "content": message,
                 }).to_string())).await;
             },
             Message::Binary(message) => {
-                println!("Received binary message: {:02X?}", message);
+                debug!("Received binary message: {:02X?}", message);
                 // websocket.send(Message::binary(b"Thank you, come again.".to_vec())).await?;
             },
             Message::Ping(message) => {
                 // No need to send a reply: tungstenite takes care of this for you.
-                println!("Received ping message: {:02X?}", message);
+                debug!("Received ping message: {:02X?}", message);
             },
             Message::Pong(message) => {
-                println!("Received pong message: {:02X?}", message);
+                debug!("Received pong message: {:02X?}", message);
             }
             Message::Close(close_frame_option) => {
                 _ = web_socket_stream.close(close_frame_option.clone()).await;
 
                 if close_frame_option.is_none() {
-                    println!("Web socket client disconnected from {}:{} with ID, '{}'.", remote_address.ip(), remote_address.port(), request_id);
+                    info!("Web socket client disconnected from {} with ID, '{}'.", remote_address, request_id);
                 } else {
                     let close_frame = close_frame_option.unwrap();
 
-                    println!("Web socket client disconnected from {}:{} with ID, '{}'; status code, '{}' and reason, '{}'.", remote_address.ip(), remote_address.port(), request_id, close_frame.code, close_frame.reason);
+                    info!("Web socket client disconnected from {} with ID, '{}'; status code, '{}' and reason, '{}'.", remote_address, request_id, close_frame.code, close_frame.reason);
                 }
 
                 break;
@@ -124,18 +197,36 @@ async fn handle_web_socket_stream_async(
     }
 }
 
-async fn handle_request_async(
+async fn forward_request_async(
     request_id: u64,
-    remote_address: SocketAddr,
+    remote_address: RemoteAddress,
     mut request: impl BorrowMut<Request<Body>>,
-    receiver: &Receiver<(u64, String)>,
-    configuration: &HttpServerConfiguration,
+    context: &HttpServerContext,
 ) -> Response<Body> {
-    let receiver = receiver.clone();
-    let configuration = configuration.clone();
+    let receiver = context.receiver.clone();
+    let configuration = context.configuration.clone();
     let borrowed_request: &mut Request<Body> = request.borrow_mut();
-    let http_request =
-        http_utilities::serialize_http_request_async(request_id, remote_address, borrowed_request).await;
+    let span_context_option = tracer::get_span_context(borrowed_request);
+    // taken before the request is consumed...
+    let cache_request_option = context.response_cache.create_request(borrowed_request);
+    let conditional_request_option = context.conditional_request_handler.create_request(borrowed_request);
+
+    // reading and parsing the body is usually what takes the longest on our side...
+    let parse_span_option = context.tracer.start_span("parse request", SpanKind::Internal, span_context_option.as_ref());
+    let serialization_result =
+        http_utilities::serialize_http_request_async(request_id, remote_address, borrowed_request, &configuration).await;
+
+    context.tracer.end_span(parse_span_option);
+
+    // if the request exceeds any of the configured limits...
+    if serialization_result.is_err() {
+        let status_code = serialization_result.unwrap_err();
+
+        // we shall respond with the corresponding status (413, 414 or 431)...
+        return request_limit_utilities::create_error_response(status_code);
+    }
+
+    let http_request = serialization_result.unwrap();
     let is_upgrade_to_web_socket_request = web_socket_utilities::
         is_upgrade_request(&http_request.headers);
 
@@ -156,7 +247,7 @@ async fn handle_request_async(
         if upgrade_result.is_err() {
             let error = upgrade_result.unwrap_err();
 
-            eprintln!("An error occurred while upgrading connection to WebSocket: {}", error);
+            warn!("An error occurred while upgrading connection to WebSocket: {}", error);
 
             // we shall return erroneous response...
             return Response::builder()
@@ -166,10 +257,16 @@ async fn handle_request_async(
         }
 
         let (response, on_upgrade) = upgrade_result.unwrap();
+
+        // the upgraded connection shall not be closed by the keep-alive timeout...
+        context.connection_activity.mark_upgraded();
         let web_socket_configuration: Option<WebSocketConfig> = None;
+        let metrics = context.metrics.clone();
 
         // spawns a task...
         _ = tokio::spawn(async move {
+            // the session is counted as long as the task is alive...
+            let _web_socket_session_guard = metrics.begin_web_socket_session();
             // retrieves the upgraded connection...
             let upgrade_option =
                 web_socket_utilities::get_upgraded_connection(on_upgrade).await;
@@ -186,30 +283,998 @@ async fn handle_request_async(
         return response;
     }
 
-    let response = get_parent_process_response_async(&http_request, &receiver).await;
+    let response = if cache_request_option.is_some() {
+        get_cached_parent_process_response_async(
+            cache_request_option.unwrap(), http_request, span_context_option.as_ref(), context).await
+    } else {
+        get_parent_process_response_async(&http_request, span_context_option.as_ref(), context).await
+    };
+
+    // validators are added after caching so that a cached response is validated the same way...
+    if conditional_request_option.is_some() {
+        return context.conditional_request_handler.handle_response_async(&conditional_request_option.unwrap(), response).await;
+    }
 
     return response;
 }
 
-pub async fn start_async(
+// the response cache sits in front of the parent process...
+async fn get_cached_parent_process_response_async(
+    cache_request: CacheRequest,
+    http_request: SerializableHttpRequest,
+    span_context_option: Option<&SpanContext>,
+    context: &HttpServerContext,
+) -> Response<Body> {
+    let response_cache = &context.response_cache;
+
+    if !cache_request.is_cacheable() {
+        let response = get_parent_process_response_async(&http_request, span_context_option, context).await;
+
+        // e.g. a successful PUT makes the cached response of the same path outdated...
+        if cache_request.is_invalidating() && (response.status().is_success() || response.status().is_redirection()) {
+            response_cache.invalidate(&cache_request);
+        }
+
+        return response;
+    }
+
+    match response_cache.lookup(&cache_request) {
+        CacheLookup::Fresh(response) => return response,
+        CacheLookup::StaleWhileRevalidate(response) => {
+            let context = context.clone();
+
+            // the client does not wait for the revalidation...
+            tokio::spawn(async move {
+                let response = get_parent_process_response_async(&http_request, None, &context).await;
+
+                context.response_cache.store_async(&cache_request, response, "stale").await;
+            });
+
+            return response;
+        },
+        CacheLookup::StaleIfError(stale_response) => {
+            let response = get_parent_process_response_async(&http_request, span_context_option, context).await;
+
+            if response.status().is_server_error() {
+                debug!("Serving stale response of '{}' as the parent process responded with {}.", http_request.path, response.status());
+
+                return stale_response;
+            }
+
+            return response_cache.store_async(&cache_request, response, "stale").await;
+        },
+        CacheLookup::Miss => {
+            let response = get_parent_process_response_async(&http_request, span_context_option, context).await;
+
+            return response_cache.store_async(&cache_request, response, "miss").await;
+        },
+    }
+}
+
+async fn get_rate_limit_keys_async(
+    remote_address: &RemoteAddress,
+    request: &Request<Body>,
     configuration: &HttpServerConfiguration,
-    receiver: &Receiver<(u64, String)>,
+) -> Vec<String> {
+    // the client is always identified by its address...
+    let mut rate_limit_keys = vec![get_client_ip_address_async(remote_address, request, configuration).await];
+
+    // if clients identify themselves via header (e.g. an API key), the header is limited as
+    // well. the address stays limited, as a client may send a different value every time...
+    if configuration.rate_limit_key_header_name.len() > 0 {
+        let key_header_value_option = request.headers()
+            .get(configuration.rate_limit_key_header_name.as_str())
+            .and_then(|header_value| header_value.to_str().ok());
+
+        if key_header_value_option.is_some() {
+            rate_limit_keys.push(format!("{}:{}", configuration.rate_limit_key_header_name, key_header_value_option.unwrap()));
+        }
+    }
+
+    return rate_limit_keys;
+}
+
+async fn get_client_ip_address_async(
+    remote_address: &RemoteAddress,
+    request: &Request<Body>,
+    configuration: &HttpServerConfiguration,
+) -> String {
+    let headers = http_utilities::to_serializable_header_map(request.headers()).await;
+    // the client address is resolved through trusted proxies...
+    let forwarded_information = forwarding_utilities::resolve(
+        remote_address, &headers, &configuration.trusted_proxies, configuration.trusted_proxy_header);
+
+    return forwarded_information.client_ip_address.unwrap_or(remote_address.to_string());
+}
+
+fn get_header_value(request: &Request<Body>, header_name: &str) -> Option<String> {
+    return request.headers()
+        .get(header_name)
+        .and_then(|header_value| header_value.to_str().ok())
+        .map(String::from);
+}
+
+fn get_response_size_in_bytes(method: &Method, response: &Response<Body>) -> Option<u64> {
+    // responses to HEAD requests never carry a body...
+    if method == Method::HEAD {
+        return Some(0);
+    }
+
+    let response_size_option = response.body().size_hint().exact();
+
+    if response_size_option.is_some() {
+        return response_size_option;
+    }
+
+    // streamed bodies may still announce their size...
+    return response.headers()
+        .get(CONTENT_LENGTH_HEADER_NAME)
+        .and_then(|header_value| header_value.to_str().ok())
+        .and_then(|header_value| header_value.trim().parse::<u64>().ok());
+}
+
+// the request becomes the root of our spans, continuing the trace of the caller (if any).
+// the context to propagate is attached to the request. if tracing is disabled, the
+// context of the caller is passed on to the parent process unchanged...
+fn start_server_span(
+    request: &mut Request<Body>,
+    remote_address: &RemoteAddress,
+    correlation_id: &str,
+    context: &HttpServerContext,
+) -> Option<Span> {
+    let parent_option = tracer::extract(request.headers());
+    let mut server_span_option = context.tracer.start_span(
+        request.method().as_str(), SpanKind::Server, parent_option.as_ref());
+    let span_context_option = server_span_option.as_ref()
+        .map(|server_span| server_span.context.clone())
+        .or(parent_option);
+
+    if span_context_option.is_some() {
+        request.extensions_mut().insert(span_context_option.unwrap());
+    }
+
+    if let Some(server_span) = server_span_option.as_mut() {
+        server_span.set_attribute("http.request.method", request.method().as_str());
+        server_span.set_attribute("url.path", request.uri().path());
+        server_span.set_attribute("network.protocol.version", format!("{:?}", request.version()));
+        server_span.set_attribute("network.peer.address", remote_address.to_string());
+        server_span.set_attribute("volcano.request.id", correlation_id);
+    }
+
+    return server_span_option;
+}
+
+pub async fn handle_request_async(
+    request_id: u64,
+    remote_address: RemoteAddress,
+    mut request: impl BorrowMut<Request<Body>>,
+    context: &HttpServerContext,
+) -> Response<Body> {
+    let started_at = Instant::now();
+    let method = request.borrow_mut().method().clone();
+    let request_id_header_name = context.configuration.request_id_header_name.as_str();
+    let correlation_id = request_id_utilities::resolve(request.borrow_mut(), request_id_header_name);
+
+    // every request takes its recorded head, even if it is not forwarded to the parent process...
+    if let Some(raw_header_recorder) = context.raw_header_recorder.as_ref() {
+        raw_header_recorder.attach(request.borrow_mut());
+    }
+
+    let mut server_span_option = start_server_span(
+        request.borrow_mut(), &remote_address, correlation_id.as_str(), context);
+    let origin_option = cors_utilities::get_origin(request.borrow_mut());
+    let is_preflight_request = cors_utilities::is_preflight_request(request.borrow_mut());
+    // the request is in flight until its response is ready...
+    let _in_flight_request_guard = context.metrics.begin_request();
+    let mut response = if context.access_logger.is_enabled() {
+        process_and_log_request_async(request_id, remote_address, request, context).await
+    } else {
+        process_request_async(request_id, remote_address, request, context).await
+    };
+
+    context.metrics.record_request(&method, response.status(), started_at.elapsed());
+    request_id_utilities::add_header(&mut response, request_id_header_name, correlation_id.as_str());
+
+    default_headers_utilities::add_default_headers(
+        &mut response,
+        context.configuration.powered_by.as_str(),
+        context.configuration.server_name.as_str(),
+        &context.configuration.security_header_policy,
+        context.configuration.is_tls_enabled,
+    );
+
+    // preflight responses already carry their CORS headers...
+    if context.configuration.cors_policy.is_enabled() && origin_option.is_some() && !is_preflight_request {
+        context.configuration.cors_policy.add_response_headers(&mut response, origin_option.unwrap().as_str());
+    }
+
+    if let Some(server_span) = server_span_option.as_mut() {
+        server_span.set_attribute("http.response.status_code", response.status().as_u16());
+
+        // client errors are not errors of the server...
+        if response.status().is_server_error() {
+            server_span.set_error();
+        }
+    }
+
+    // the body itself is written to the client by hyper once we return...
+    let write_response_span_option = context.tracer.start_span(
+        "write response", SpanKind::Internal, server_span_option.as_ref().map(|server_span| &server_span.context));
+    let response = context.tracer.end_span_after_body(response, write_response_span_option);
+
+    context.tracer.end_span(server_span_option);
+
+    return response;
+}
+
+async fn process_and_log_request_async(
+    request_id: u64,
+    remote_address: RemoteAddress,
+    mut request: impl BorrowMut<Request<Body>>,
+    context: &HttpServerContext,
+) -> Response<Body> {
+    let timestamp = Utc::now();
+    let started_at = Instant::now();
+    let borrowed_request: &mut Request<Body> = request.borrow_mut();
+    // the request is consumed while it is processed, so we
+    // shall capture everything we log beforehand...
+    let method = borrowed_request.method().clone();
+    let target = borrowed_request.uri()
+        .path_and_query()
+        .map_or(borrowed_request.uri().to_string(), |path_and_query| path_and_query.to_string());
+    let protocol = format!("{:?}", borrowed_request.version());
+    let user_agent = get_header_value(borrowed_request, USER_AGENT_HEADER_NAME);
+    let referer = get_header_value(borrowed_request, REFERER_HEADER_NAME);
+    let correlation_id = request_id_utilities::get(borrowed_request).unwrap_or_default();
+    let client_ip_address = get_client_ip_address_async(
+        &remote_address, borrowed_request, &context.configuration).await;
+    let response = process_request_async(request_id, remote_address, request, context).await;
+    let tls_information_option = context.tls_information.as_ref();
+
+    context.access_logger.log(&AccessLogEntry {
+        timestamp,
+        request_id: correlation_id,
+        client_ip_address,
+        method: method.to_string(),
+        target,
+        protocol,
+        status_code: response.status().as_u16(),
+        response_size_in_bytes: get_response_size_in_bytes(&method, &response),
+        duration: started_at.elapsed(),
+        user_agent,
+        referer,
+        tls_protocol_version: tls_information_option.map(|tls_information| tls_information.protocol_version.clone()),
+        tls_cipher_suite: tls_information_option.and_then(|tls_information| tls_information.cipher_suite.clone()),
+    });
+
+    return response;
+}
+
+async fn process_request_async(
+    request_id: u64,
+    remote_address: RemoteAddress,
+    mut request: impl BorrowMut<Request<Body>>,
+    context: &HttpServerContext,
+) -> Response<Body> {
+    let configuration = &context.configuration;
+    let borrowed_request: &mut Request<Body> = request.borrow_mut();
+    // hyper already bounds the head while reading it (see http_utilities::create_http).
+    // the exact limits apply to every request, whoever answers it...
+    let check_result = request_limit_utilities::check_request_head(borrowed_request, configuration);
+
+    if check_result.is_err() {
+        return request_limit_utilities::create_error_response(check_result.unwrap_err());
+    }
+
+    // if connection statistics are requested, we shall answer
+    // ourselves without involving the parent process...
+    if configuration.connection_statistics_path.len() > 0
+        && configuration.connection_statistics_path.eq(borrowed_request.uri().path()) {
+        return create_connection_statistics_response(&context.connection_limiter);
+    }
+
+    // orchestrators probe liveness and readiness directly, without involving the parent process...
+    if configuration.liveness_path.len() > 0
+        && configuration.liveness_path.eq(borrowed_request.uri().path()) {
+        return create_liveness_response();
+    }
+
+    if configuration.readiness_path.len() > 0
+        && configuration.readiness_path.eq(borrowed_request.uri().path()) {
+        return create_readiness_response(context);
+    }
+
+    // CORS preflights do not need the parent process either...
+    if configuration.cors_policy.is_enabled() && cors_utilities::is_preflight_request(borrowed_request) {
+        return configuration.cors_policy.create_preflight_response(borrowed_request);
+    }
+
+    // metrics are answered the same way, unless a separate admin listener serves them...
+    if configuration.metrics_path.len() > 0
+        && configuration.metrics_address.len() == 0
+        && configuration.metrics_path.eq(borrowed_request.uri().path()) {
+        return create_metrics_response(context);
+    }
+
+    // if rate limiting is not enabled, the request is forwarded right away...
+    if !context.rate_limiter.is_enabled() {
+        return route_request_async(request_id, remote_address, request, context).await;
+    }
+
+    let rate_limit_keys = get_rate_limit_keys_async(&remote_address, borrowed_request, configuration).await;
+    let rate_limit_decision_option = context.rate_limiter
+        .check(borrowed_request.uri().path(), &rate_limit_keys);
+
+    // if no rate limit applies to the path...
+    if rate_limit_decision_option.is_none() {
+        return route_request_async(request_id, remote_address, request, context).await;
+    }
+
+    let rate_limit_decision = rate_limit_decision_option.unwrap();
+
+    // the request is rejected before it ever reaches the parent process...
+    if !rate_limit_decision.is_allowed {
+        return create_too_many_requests_response(&rate_limit_decision);
+    }
+
+    let mut response = route_request_async(request_id, remote_address, request, context).await;
+
+    add_rate_limit_headers(&mut response, &rate_limit_decision);
+
+    return response;
+}
+
+// requests no route matches are forwarded to the parent process...
+async fn route_request_async(
+    request_id: u64,
+    remote_address: RemoteAddress,
+    mut request: impl BorrowMut<Request<Body>>,
+    context: &HttpServerContext,
+) -> Response<Body> {
+    let router = &context.configuration.router;
+
+    if !router.is_enabled() {
+        return forward_request_async(request_id, remote_address, request, context).await;
+    }
+
+    let borrowed_request: &mut Request<Body> = request.borrow_mut();
+    let route_match_option = router.find(borrowed_request);
+
+    if route_match_option.is_none() {
+        return forward_request_async(request_id, remote_address, request, context).await;
+    }
+
+    let RouteMatch { action, path_parameters, glob_path_option, glob_name_option } = route_match_option.unwrap();
+
+    match action {
+        RouteAction::Parent => {
+            // the parent process receives the path parameters along with the request...
+            borrowed_request.extensions_mut().insert(PathParameters(path_parameters));
+
+            return forward_request_async(request_id, remote_address, request, context).await;
+        },
+        RouteAction::Static { directory, index_file_name } => {
+            // the glob (if any) of the route locates the file within the directory...
+            let relative_path = glob_path_option
+                .unwrap_or(router::decode_path_segment(borrowed_request.uri().path()));
+
+            return static_file_utilities::serve_file_async(
+                borrowed_request, directory.as_str(), relative_path.as_str(), index_file_name.as_str()).await;
+        },
+        RouteAction::Redirect { location, status_code, preserve_query } => {
+            return router::create_redirect_response(
+                borrowed_request, location.as_str(), *status_code, *preserve_query, &path_parameters, glob_name_option);
+        },
+        RouteAction::Respond { status_code, headers, body } => {
+            return router::create_fixed_response(*status_code, headers, body.as_str());
+        },
+        RouteAction::Proxy(upstream_group) => {
+            // the body limit applies to proxied requests as well...
+            let check_result = request_limit_utilities::check_content_length(
+                borrowed_request, context.configuration.maximum_body_size_in_bytes);
+
+            if check_result.is_err() {
+                return request_limit_utilities::create_error_response(check_result.unwrap_err());
+            }
+
+            // a chunked body announces no length, hence it is counted while being streamed...
+            let (limited_body, is_limit_exceeded_arc) = request_limit_utilities::limit_body(
+                std::mem::take(borrowed_request.body_mut()), context.configuration.maximum_body_size_in_bytes);
+
+            *borrowed_request.body_mut() = limited_body;
+
+            let response = context.reverse_proxy.forward_async(
+                borrowed_request, upstream_group, &remote_address, &context.configuration).await;
+
+            // the upstream request failed because the body was too large...
+            if is_limit_exceeded_arc.load(Ordering::Relaxed) {
+                return request_limit_utilities::create_error_response(StatusCode::PAYLOAD_TOO_LARGE);
+            }
+
+            return response;
+        },
+    }
+}
+
+fn add_rate_limit_headers(response: &mut Response<Body>, rate_limit_decision: &RateLimitDecision) {
+    let headers = response.headers_mut();
+
+    headers.insert("ratelimit-limit", rate_limit_decision.limit.into());
+    headers.insert("ratelimit-remaining", rate_limit_decision.remaining.into());
+    headers.insert("ratelimit-reset", rate_limit_decision.reset_in_seconds.into());
+    headers.insert("ratelimit-policy", HeaderValue::from_str(format!("{};w={}",
+        rate_limit_decision.limit, rate_limit_decision.window_in_seconds).as_str()).unwrap());
+}
+
+fn create_too_many_requests_response(rate_limit_decision: &RateLimitDecision) -> Response<Body> {
+    let mut response = Response::builder()
+        .status(StatusCode::TOO_MANY_REQUESTS)
+        .header("retry-after", rate_limit_decision.retry_after_in_seconds)
+        .body(Body::from(StatusCode::TOO_MANY_REQUESTS.canonical_reason().unwrap_or("")))
+        .unwrap();
+
+    add_rate_limit_headers(&mut response, rate_limit_decision);
+
+    return response;
+}
+
+fn create_connection_statistics_response(connection_limiter: &ConnectionLimiter) -> Response<Body> {
+    let connection_statistics = connection_limiter.get_statistics();
+
+    return Response::builder()
+        .status(200)
+        .header("content-type", "application/json")
+        .header("cache-control", "no-store")
+        .body(Body::from(json!(connection_statistics).to_string()))
+        .unwrap();
+}
+
+fn create_liveness_response() -> Response<Body> {
+    // if we are able to answer, we are alive...
+    return Response::builder()
+        .status(200)
+        .header("content-type", "application/json")
+        .header("cache-control", "no-store")
+        .body(Body::from(json!({ "status": "alive" }).to_string()))
+        .unwrap();
+}
+
+fn create_readiness_response(context: &HttpServerContext) -> Response<Body> {
+    let readiness = context.health_monitor.get_readiness(&context.receiver);
+    let status_code = if readiness.is_ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    return Response::builder()
+        .status(status_code)
+        .header("content-type", "application/json")
+        .header("cache-control", "no-store")
+        .body(Body::from(json!(readiness).to_string()))
+        .unwrap();
+}
+
+fn create_metrics_response(context: &HttpServerContext) -> Response<Body> {
+    let metrics = context.metrics.render(&context.connection_limiter.get_statistics());
+
+    return Response::builder()
+        .status(200)
+        .header("content-type", metrics::CONTENT_TYPE)
+        .header("cache-control", "no-store")
+        .body(Body::from(metrics))
+        .unwrap();
+}
+
+fn upgrade_to_h2c(
+    remote_address: RemoteAddress,
+    mut request: Request<Body>,
+    context: &HttpServerContext,
+) -> Response<Body> {
+    // the upgrade request becomes stream 1 of the HTTP/2 connection. it is handed
+    // to the HTTP/2 server as if the client had sent it after the connection preface...
+    let request_frames = h2c_utilities::encode_request_frames(&request);
+    let on_upgrade = hyper::upgrade::on(&mut request);
+    let mut http = http_utilities::create_http(&context.configuration);
+    let service = create_service(remote_address, context.clone());
+
+    http.http2_only(true);
+
+    // spawns a task...
+    _ = tokio::spawn(async move {
+        let upgrade_result = on_upgrade.await;
+
+        if upgrade_result.is_err() {
+            let error = upgrade_result.unwrap_err();
+
+            error!("An error occurred while upgrading connection to h2c: {}", error);
+
+            return;
+        }
+
+        let mut upgraded = upgrade_result.unwrap();
+        let client_preface_result = h2c_utilities::read_client_preface_async(&mut upgraded).await;
+
+        if client_preface_result.is_err() {
+            let error = client_preface_result.unwrap_err();
+
+            warn!("An error occurred while reading HTTP/2 connection preface from {}: {}", remote_address, error);
+
+            return;
+        }
+
+        let mut prefix = client_preface_result.unwrap();
+
+        prefix.extend_from_slice(&request_frames);
+
+        let upgraded_stream = PrefixedStream::new(upgraded, prefix);
+        // the rest of the connection is served as HTTP/2...
+        let connection_result = http.serve_connection(upgraded_stream, service).await;
+
+        if connection_result.is_err() {
+            let error = connection_result.unwrap_err();
+
+            error!("An unexpected connection error occurred: {}", error);
+        }
+    });
+
+    return h2c_utilities::create_switching_protocols_response();
+}
+
+fn create_service(
+    remote_address: RemoteAddress,
+    context: HttpServerContext,
+) -> impl Service<Request<Body>, Response = Response<Body>, Error = Infallible, Future = ResponseFuture> + Send {
+    return service_fn(move |request: Request<Body>| {
+        let cloned_context = context.clone();
+        let request_id = context.id_generator.generate();
+        // the connection is not idle while the request is being handled...
+        let request_activity_guard = context.connection_activity.begin_request();
+
+        // the future is boxed because an h2c upgrade creates a service from within...
+        let response_future: ResponseFuture = Box::pin(async move {
+            let _request_activity_guard = request_activity_guard;
+            let is_upgrade_to_h2c_request = cloned_context.configuration.is_http2_enabled
+                && !cloned_context.configuration.is_tls_enabled
+                && h2c_utilities::is_upgrade_request(&request);
+
+            // if HTTP/2 over cleartext is requested via upgrade...
+            if is_upgrade_to_h2c_request {
+                return Ok(upgrade_to_h2c(remote_address, request, &cloned_context));
+            }
+
+            let mut response = handle_request_async(
+                request_id,
+                remote_address,
+                request,
+                &cloned_context,
+            )
+            .await;
+
+            // if HTTP/3 is enabled, we shall advertise it...
+            if cloned_context.configuration.is_http3_enabled {
+                http_utilities::add_alternative_service_header(&mut response, &cloned_context.configuration);
+            }
+
+            Ok::<_, Infallible>(response)
+        });
+
+        response_future
+    });
+}
+
+async fn read_proxy_protocol_header_async<S>(
+    stream: &mut S,
+    remote_address: RemoteAddress,
+    configuration: &HttpServerConfiguration,
+) -> Option<RemoteAddress>
+where
+    S: AsyncRead + Unpin,
+{
+    let proxy_protocol_timeout = Duration::from_millis(configuration.proxy_protocol_timeout_in_milliseconds);
+    let timeout_result = timeout(proxy_protocol_timeout,
+        proxy_protocol_utilities::read_header_async(stream)).await;
+
+    if timeout_result.is_err() {
+        warn!("Timed out while waiting for PROXY protocol header from {}.", remote_address);
+
+        return None;
+    }
+
+    let read_result = timeout_result.unwrap();
+
+    if read_result.is_err() {
+        let error = read_result.unwrap_err();
+
+        warn!("An error occurred while reading PROXY protocol header from {}: {}", remote_address, error);
+
+        return None;
+    }
+
+    let source_address_option = read_result.unwrap();
+
+    // if the proxy did not convey a client address (e.g. health checks),
+    // we shall keep the address of the proxy itself...
+    if source_address_option.is_none() {
+        return Some(remote_address);
+    }
+
+    return Some(RemoteAddress::Ip(source_address_option.unwrap()));
+}
+
+fn get_tls_information(server_connection: &ServerConnection) -> TlsInformation {
+    let protocol_version = server_connection.protocol_version()
+        .map_or(String::from("-"), |protocol_version| format!("{:?}", protocol_version));
+    let cipher_suite = server_connection.negotiated_cipher_suite()
+        .map(|cipher_suite| format!("{:?}", cipher_suite.suite()));
+
+    return TlsInformation { protocol_version, cipher_suite };
+}
+
+async fn serve_connection_async<S>(
+    mut stream: S,
+    mut remote_address: RemoteAddress,
+    is_proxy_protocol_enabled: bool,
+    http: Http,
+    tls_acceptor_option: Option<TlsAcceptor>,
+    context: HttpServerContext,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    // the permit is held until the connection is closed. a connection that has yet to
+    // send its PROXY protocol header counts as well, but only towards the global limit...
+    let connection_permit_option = if is_proxy_protocol_enabled {
+        context.connection_limiter.acquire_for_unknown_client_async().await
+    } else {
+        context.connection_limiter.acquire_async(&remote_address).await
+    };
+
+    if connection_permit_option.is_none() {
+        warn!("Connection from {} is closed because the connection limit is reached.", remote_address);
+
+        return;
+    }
+
+    let mut connection_permit = connection_permit_option.unwrap();
+
+    // the PROXY protocol header precedes everything else, including the TLS handshake.
+    // connections without a valid header (in time) are rejected...
+    if is_proxy_protocol_enabled {
+        let remote_address_option = read_proxy_protocol_header_async(
+            &mut stream, remote_address, &context.configuration).await;
+
+        if remote_address_option.is_none() {
+            return;
+        }
+
+        remote_address = remote_address_option.unwrap();
+
+        // the limit per IP address applies to the actual client...
+        if !connection_permit.assign_async(&remote_address).await {
+            warn!("Connection from {} is closed because the connection limit is reached.", remote_address);
+
+            return;
+        }
+    }
+
+    let stream = PermittedStream::new(stream, connection_permit);
+    let stream = MeteredStream::new(stream, context.metrics.clone());
+    // every connection keeps track of its own activity...
+    let context = context.for_connection();
+    let keep_alive_timeout_option = http_utilities::to_timeout(context.configuration.keep_alive_timeout_in_milliseconds);
+    let stream = IdleTimeoutStream::new(stream, context.connection_activity.clone(), keep_alive_timeout_option);
+    let tls_handshake_timeout_option = http_utilities::to_timeout(context.configuration.tls_handshake_timeout_in_milliseconds);
+
+    // if TLS acceptor is none...
+    if tls_acceptor_option.is_none() {
+        let stream = RawHeaderRecordingStream::new(stream, context.raw_header_recorder.clone());
+        let service_function = create_service(remote_address, context);
+        // we shall serve the connection without TLS...
+        let connection = http
+            .serve_connection(stream, service_function)
+            .with_upgrades();
+        let connection_result = connection.await;
+
+        if connection_result.is_err() {
+            let error = connection_result.unwrap_err();
+
+            error!("An unexpected connection error occurred: {}", error);
+        }
+
+        return;
+    }
+
+    // otherwise, we shall serve the connection with TLS...
+    let tls_acceptor = tls_acceptor_option.unwrap();
+    let accept_future = tls_acceptor.accept(stream);
+    let accept_result = if tls_handshake_timeout_option.is_none() {
+        accept_future.await
+    } else {
+        // the stream type is generic and may not implement Debug, so we
+        // cannot use unwrap() here...
+        match timeout(tls_handshake_timeout_option.unwrap(), accept_future).await {
+            Ok(accept_result) => accept_result,
+            Err(_) => {
+                warn!("Timed out while performing TLS handshake with {}.", remote_address);
+                context.metrics.record_tls_handshake_failure();
+
+                return;
+            },
+        }
+    };
+    // the stream type is generic and may not implement Debug, so we
+    // cannot use unwrap_err() here...
+    let tls_stream = match accept_result {
+        Ok(tls_stream) => tls_stream,
+        Err(error) => {
+            warn!(
+                "An error occurred while accepting TLS connection: {}",
+                error
+            );
+            context.metrics.record_tls_handshake_failure();
+
+            return;
+        },
+    };
+    let mut context = context;
+
+    // the negotiated parameters are recorded in the access log...
+    context.tls_information = Some(get_tls_information(tls_stream.get_ref().1));
+
+    // the decrypted stream is recorded...
+    let tls_stream = RawHeaderRecordingStream::new(tls_stream, context.raw_header_recorder.clone());
+    let service_function = create_service(remote_address, context);
+    let connection = http
+        .serve_connection(tls_stream, service_function)
+        .with_upgrades();
+    let connection_result = connection.await;
+
+    if connection_result.is_err() {
+        let error = connection_result.unwrap_err();
+
+        error!("An unexpected connection error occurred: {}", error);
+    }
+}
+
+async fn wait_for_shutdown_signal_async() {
+    let signal_result = signal(SignalKind::terminate());
+
+    if signal_result.is_err() {
+        let error = signal_result.unwrap_err();
+
+        error!("An error occurred while listening for termination signal: {}", error);
+
+        // we shall still be able to shut down on interrupt signal...
+        _ = tokio::signal::ctrl_c().await;
+
+        return;
+    }
+
+    let mut terminate_signal = signal_result.unwrap();
+
+    tokio::select! {
+        _ = terminate_signal.recv() => {},
+        _ = tokio::signal::ctrl_c() => {},
+    }
+}
+
+async fn serve_metrics_async(context: HttpServerContext) {
+    let metrics_address = context.configuration.metrics_address.clone();
+    let tcp_listener_result = TcpListener::bind(metrics_address.as_str()).await;
+
+    if tcp_listener_result.is_err() {
+        let error = tcp_listener_result.unwrap_err();
+
+        error!("An error occurred while creating metrics listener on {}: {}", metrics_address, error);
+
+        return;
+    }
+
+    let tcp_listener = tcp_listener_result.unwrap();
+
+    println!("Metrics server listening on http://{}{}", metrics_address, context.configuration.metrics_path);
+
+    loop {
+        let accept_result = tcp_listener.accept().await;
+
+        if accept_result.is_err() {
+            let error = accept_result.unwrap_err();
+
+            error!("An error occurred while accepting metrics connection: {}", error);
+
+            continue;
+        }
+
+        let (stream, _) = accept_result.unwrap();
+        let context = context.clone();
+        // the admin listener serves nothing but metrics...
+        let service = service_fn(move |request: Request<Body>| {
+            let response = if context.configuration.metrics_path.eq(request.uri().path()) {
+                create_metrics_response(&context)
+            } else {
+                request_limit_utilities::create_error_response(StatusCode::NOT_FOUND)
+            };
+
+            async move { Ok::<_, Infallible>(response) }
+        });
+
+        tokio::spawn(async move {
+            let connection_result = Http::new().serve_connection(stream, service).await;
+
+            if connection_result.is_err() {
+                let error = connection_result.unwrap_err();
+
+                warn!("An unexpected metrics connection error occurred: {}", error);
+            }
+        });
+    }
+}
+
+async fn reopen_log_files_on_signal_async(access_logger: AccessLogger) {
+    let signal_result = signal(SignalKind::user_defined1());
+
+    if signal_result.is_err() {
+        let error = signal_result.unwrap_err();
+
+        error!("An error occurred while listening for SIGUSR1 signal: {}", error);
+
+        return;
+    }
+
+    let mut user_defined_signal = signal_result.unwrap();
+
+    // log rotation tools move the files away and signal us to continue with new ones...
+    while user_defined_signal.recv().await.is_some() {
+        access_logger.reopen();
+        diagnostic_logger::reopen();
+    }
+}
+
+// messages the parent process sends on its own, i.e. "0#<message>"...
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ControlMessage {
+    // e.g. 0#{"type":"purgeCache","keys":["GET /a"]}...
+    PurgeCache(PurgeRequest),
+    // the reply to {"type":"ping","pingId":42} is 0#{"type":"pong","pingId":42}...
+    Pong {
+        #[serde(rename = "pingId")]
+        ping_id: u64,
+    },
+}
+
+async fn handle_control_messages_async(
+    context: HttpServerContext,
+    mut control_message_receiver: UnboundedReceiver<String>,
+    pong_sender: UnboundedSender<u64>,
 ) {
-    let cloned_configuration = configuration.clone();
-    let host = String::from(cloned_configuration.host.as_str());
-    let port = cloned_configuration.port;
+    // the loop ends once the IPC channel has been closed...
+    while let Some(line) = control_message_receiver.recv().await {
+        let control_message_result: Result<ControlMessage, serde_json::Error> = serde_json::from_str(line.as_str());
+
+        if control_message_result.is_err() {
+            let error = control_message_result.unwrap_err();
+
+            warn!("Invalid control message '{}': {}", line, error);
+
+            continue;
+        }
+
+        match control_message_result.unwrap() {
+            ControlMessage::PurgeCache(purge_request) => _ = context.response_cache.purge(&purge_request),
+            // if pings are not enabled, nobody is waiting for the reply...
+            ControlMessage::Pong { ping_id } => _ = pong_sender.send(ping_id),
+        }
+    }
+}
+
+async fn wait_for_pong_async(pong_receiver: &mut UnboundedReceiver<u64>, ping_id: u64) -> bool {
+    // replies to earlier pings that arrived too late are skipped...
+    while let Some(received_ping_id) = pong_receiver.recv().await {
+        if received_ping_id == ping_id {
+            return true;
+        }
+    }
+
+    // the IPC channel has been closed...
+    return false;
+}
+
+async fn ping_parent_process_async(context: HttpServerContext, mut pong_receiver: UnboundedReceiver<u64>) {
+    let health_monitor = &context.health_monitor;
+
+    loop {
+        let ping_id = context.id_generator.generate();
+        // the parent process answers with a control message, i.e. 0#{"type":"pong","pingId":<ping-id>}.
+        // pings never wait on the replies to requests, so they cannot be mixed up with them...
+        let ping = json!({ "type": "ping", "pingId": ping_id }).to_string();
+        let started_at = Instant::now();
+        let mut round_trip_option = None;
+
+        if ipc_handler::write_line(&ping) {
+            let reply_result = timeout(
+                health_monitor.get_ping_timeout(),
+                wait_for_pong_async(&mut pong_receiver, ping_id),
+            ).await;
+
+            if reply_result.is_ok() && reply_result.unwrap() {
+                round_trip_option = Some(started_at.elapsed());
+            }
+        }
+
+        if round_trip_option.is_none() {
+            warn!("The parent process did not answer ping '{}' in time.", ping_id);
+        }
+
+        health_monitor.record_ping(round_trip_option);
+        tokio::time::sleep(health_monitor.get_ping_interval()).await;
+    }
+}
+
+fn create_inherited_listeners(
+    configuration: &HttpServerConfiguration,
+    inherited_file_descriptors: Vec<InheritedFileDescriptor>,
+) -> Option<Vec<(Listener, bool)>> {
+    let mut listeners: Vec<(Listener, bool)> = Vec::with_capacity(inherited_file_descriptors.len());
+
+    for inherited_file_descriptor in inherited_file_descriptors {
+        let file_descriptor = inherited_file_descriptor.file_descriptor;
+        let listener_result = Listener::from_file_descriptor(file_descriptor);
+
+        if listener_result.is_err() {
+            let error = listener_result.unwrap_err();
+
+            error!("An error occurred while using inherited file descriptor {} as listener: {}", file_descriptor, error);
+
+            return None;
+        }
+
+        // inherited listeners are selected either by file descriptor or by name...
+        let is_proxy_protocol_enabled = proxy_protocol_utilities::is_enabled_for(
+            &configuration.proxy_protocol_listeners,
+            &[file_descriptor.to_string().as_str(), inherited_file_descriptor.name.as_str()],
+        );
+
+        listeners.push((listener_result.unwrap(), is_proxy_protocol_enabled));
+    }
+
+    return Some(listeners);
+}
+
+async fn create_listener_async(configuration: &HttpServerConfiguration) -> Option<Listener> {
+    let configuration = configuration.clone();
+
+    // if Unix domain socket path is specified...
+    if configuration.unix_socket_path.len() > 0 {
+        // we shall listen on the Unix domain socket...
+        let unix_listener_result = unix_socket_utilities::bind(
+            configuration.unix_socket_path.as_str(),
+            configuration.unix_socket_mode,
+            configuration.unix_socket_owner_id,
+            configuration.unix_socket_group_id,
+        );
+
+        if unix_listener_result.is_err() {
+            let error = unix_listener_result.unwrap_err();
+
+            error!("An error occurred while creating Unix domain socket listener: {}", error);
+
+            return None;
+        }
+
+        return Some(Listener::Unix(unix_listener_result.unwrap()));
+    }
+
+    let host = String::from(configuration.host.as_str());
+    let port = configuration.port;
     let socket_address_result =
-        http_utilities::create_socket_address(cloned_configuration.host, cloned_configuration.port);
+        http_utilities::create_socket_address(configuration.host, configuration.port);
 
     if socket_address_result.is_err() {
         let error = socket_address_result.unwrap_err();
 
-        eprintln!(
+        error!(
             "An error occurred while creating socket address using host {} and port {}: {}",
             host, port, error
         );
 
-        return;
+        return None;
     }
 
     let socket_address = socket_address_result.unwrap();
@@ -218,118 +1283,188 @@ pub async fn start_async(
     if tcp_listener_result.is_err() {
         let error = tcp_listener_result.unwrap_err();
 
-        eprintln!("An error occurred while creating TCP listener: {}", error);
+        error!("An error occurred while creating TCP listener: {}", error);
 
-        return;
+        return None;
     }
 
-    let tcp_listener = tcp_listener_result.unwrap();
-    let http = http_utilities::create_http(configuration);
-    let tls_acceptor_option = http_utilities::create_tls_acceptor(configuration);
-    let id_generator = IdGenerator::new();
+    return Some(Listener::Tcp(tcp_listener_result.unwrap()));
+}
+
+fn print_listening_addresses(listeners: &Vec<(Listener, bool)>, configuration: &HttpServerConfiguration, is_tls_enabled: bool) {
+    let (web_socket_scheme, http_scheme) = if is_tls_enabled { ("wss", "https") } else { ("ws", "http") };
 
     println!();
 
-    // checks if TLS is not enabled...
-    if tls_acceptor_option.is_none() {
-        if configuration.is_web_socket_server_enabled {
-            println!("WebSocket server listening on ws://{}:{}", host, port);
-        }
+    for (listener, is_proxy_protocol_enabled) in listeners {
+        let address = listener.get_local_address();
+        let suffix = if *is_proxy_protocol_enabled { " (PROXY protocol)" } else { "" };
 
-        println!("HTTP server listening on http://{}:{}", host, port);
-    } else {
         if configuration.is_web_socket_server_enabled {
-            println!("WebSocket server listening on wss://{}:{}", host, port);
+            println!("WebSocket server listening on {}://{}{}", web_socket_scheme, address, suffix);
         }
 
-        println!("HTTP server listening on https://{}:{}", host, port);
+        println!("HTTP server listening on {}://{}{}", http_scheme, address, suffix);
     }
+}
 
+async fn accept_connections_async(
+    listener: Listener,
+    is_proxy_protocol_enabled: bool,
+    http: Http,
+    tls_acceptor_option: Option<TlsAcceptor>,
+    context: HttpServerContext,
+) {
     loop {
-        let accept_result = tcp_listener.accept().await;
+        let accept_result = listener.accept_async().await;
 
         // in case of error...
         if accept_result.is_err() {
             let error = accept_result.unwrap_err();
 
-            eprintln!("An error occurred while accepting connection: {}", error);
+            error!("An error occurred while accepting connection: {}", error);
 
             // we shall skip this iteration...
             continue;
         }
 
-        let receiver = receiver.clone();
-        let http = http.clone();
-        let tls_acceptor_option = tls_acceptor_option.clone();
-        let cloned_configuration = configuration.clone();
-        let id_generator = id_generator.clone();
+        let (stream, remote_address) = accept_result.unwrap();
 
-        tokio::spawn(async move {
-            let http = http.clone();
-            let (tcp_stream, remote_address) = accept_result.unwrap();
-            let service_function = service_fn(move |request: Request<Body>| {
-                let receiver = receiver.clone();
-                let cloned_configuration = cloned_configuration.clone();
-                let id_generator = id_generator.clone();
-                let request_id = id_generator.generate();
-
-                async move {
-                    Ok::<_, Infallible>(
-                        handle_request_async(
-                            request_id,
-                            remote_address,
-                            request,
-                            &receiver,
-                            &cloned_configuration,
-                        )
-                        .await,
-                    )
-                }
-            });
+        tokio::spawn(serve_connection_async(
+            stream,
+            remote_address,
+            is_proxy_protocol_enabled,
+            http.clone(),
+            tls_acceptor_option.clone(),
+            context.clone(),
+        ));
+    }
+}
 
-            // if TLS acceptor is none...
-            if tls_acceptor_option.is_none() {
-                // we shall serve the connection without TLS...
-                let connection = http
-                    .serve_connection(tcp_stream, service_function)
-                    .with_upgrades();
-                let connection_result = connection.await;
+pub async fn start_async(
+    configuration: &HttpServerConfiguration,
+    receiver: &Receiver<(u64, String)>,
+    control_message_receiver: UnboundedReceiver<String>,
+    inherited_file_descriptors: Vec<InheritedFileDescriptor>,
+) {
+    let inherited_listeners_option = create_inherited_listeners(configuration, inherited_file_descriptors);
 
-                if connection_result.is_err() {
-                    let error = connection_result.unwrap_err();
+    if inherited_listeners_option.is_none() {
+        return;
+    }
 
-                    eprintln!("An unexpected connection error occurred: {}", error);
-                }
+    let mut listeners = inherited_listeners_option.unwrap();
+    let are_listeners_inherited = listeners.len() > 0;
 
-                return;
-            }
+    // if no listener is inherited, we shall bind one ourselves...
+    if !are_listeners_inherited {
+        let listener_option = create_listener_async(configuration).await;
 
-            // otherwise, we shall serve the connection with TLS...
-            let tls_acceptor = tls_acceptor_option.unwrap();
-            let accept_result = tls_acceptor.accept(tcp_stream).await;
+        if listener_option.is_none() {
+            return;
+        }
 
-            if accept_result.is_err() {
-                let error = accept_result.unwrap_err();
+        let is_proxy_protocol_enabled = proxy_protocol_utilities::is_enabled_for(
+            &configuration.proxy_protocol_listeners, &[DEFAULT_LISTENER_IDENTIFIER]);
 
-                eprintln!(
-                    "An error occurred while accepting TLS connection: {}",
-                    error
-                );
+        listeners.push((listener_option.unwrap(), is_proxy_protocol_enabled));
+    }
 
-                return;
-            }
+    let http = http_utilities::create_http(configuration);
+    let tls_acceptor_option = http_utilities::create_tls_acceptor(configuration);
+    let context = HttpServerContext::new(configuration, receiver);
+    let mut join_handles = Vec::with_capacity(listeners.len());
 
-            let tls_stream = accept_result.unwrap();
-            let connection = http
-                .serve_connection(tls_stream, service_function)
-                .with_upgrades();
-            let connection_result = connection.await;
+    print_listening_addresses(&listeners, configuration, tls_acceptor_option.is_some());
 
-            if connection_result.is_err() {
-                let error = connection_result.unwrap_err();
+    let join_handle = tokio::spawn(reopen_log_files_on_signal_async(context.access_logger.clone()));
 
-                eprintln!("An unexpected connection error occurred: {}", error);
-            }
-        });
+    join_handles.push(join_handle);
+
+    // finished spans are exported in the background...
+    if context.tracer.is_enabled() {
+        let join_handle = tokio::spawn(tracer::export_spans_async(context.tracer.clone()));
+
+        join_handles.push(join_handle);
+    }
+
+    // if a separate admin address is specified, metrics are served there...
+    if configuration.metrics_address.len() > 0 {
+        let join_handle = tokio::spawn(serve_metrics_async(context.clone()));
+
+        join_handles.push(join_handle);
+    }
+
+    // replies to pings arrive as control messages...
+    let (pong_sender, pong_receiver) = mpsc::unbounded_channel();
+
+    // readiness reflects whether the parent process answers our pings...
+    if configuration.readiness_path.len() > 0 && context.health_monitor.is_ping_enabled() {
+        let join_handle = tokio::spawn(ping_parent_process_async(context.clone(), pong_receiver));
+
+        join_handles.push(join_handle);
+    }
+
+    let join_handle = tokio::spawn(handle_control_messages_async(
+        context.clone(), control_message_receiver, pong_sender));
+
+    join_handles.push(join_handle);
+
+    let upstream_groups = configuration.router.get_upstream_groups();
+
+    // upstreams of proxy routes with a health check path are probed in the background...
+    if context.reverse_proxy.is_health_checking_enabled() && upstream_groups.iter().any(UpstreamGroup::is_health_checked) {
+        let join_handle = tokio::spawn(reverse_proxy::check_upstream_health_async(
+            context.reverse_proxy.clone(), upstream_groups));
+
+        join_handles.push(join_handle);
+    }
+
+    // if HTTP/3 is enabled, we shall also listen for QUIC connections...
+    if configuration.is_http3_enabled {
+        let join_handle = tokio::spawn(http3_server::start_async(context.clone()));
+
+        join_handles.push(join_handle);
+    }
+
+    for (listener, is_proxy_protocol_enabled) in listeners {
+        let join_handle = tokio::spawn(accept_connections_async(
+            listener,
+            is_proxy_protocol_enabled,
+            http.clone(),
+            tls_acceptor_option.clone(),
+            context.clone(),
+        ));
+
+        join_handles.push(join_handle);
+    }
+
+    wait_for_shutdown_signal_async().await;
+
+    // while draining, we keep serving requests but report that we are not ready,
+    // so that load balancers stop sending new requests...
+    if configuration.shutdown_drain_timeout_in_milliseconds > 0 {
+        context.health_monitor.begin_draining();
+
+        info!("Draining for {} ms before shutting down.", configuration.shutdown_drain_timeout_in_milliseconds);
+
+        // another signal cuts the draining short...
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(configuration.shutdown_drain_timeout_in_milliseconds)) => {},
+            _ = wait_for_shutdown_signal_async() => {},
+        }
+    }
+
+    info!("Shutting down HTTP server.");
+
+    // stops accepting new connections...
+    for join_handle in join_handles {
+        join_handle.abort();
+    }
+
+    // if we have bound the Unix domain socket ourselves...
+    if !are_listeners_inherited && configuration.unix_socket_path.len() > 0 {
+        // we shall remove the socket file...
+        unix_socket_utilities::remove(configuration.unix_socket_path.as_str());
     }
 }
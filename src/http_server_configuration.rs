@@ -1,30 +1,164 @@
-use crate::configuration::Configuration;
+#![allow(clippy::needless_return)]
+
+use ipnet::IpNet;
+
+use crate::{configuration::Configuration, rate_limiter::RateLimitRule, access_logger::AccessLogFormat, tracer::TraceExporter, cors_utilities::CorsPolicy,
+    default_headers_utilities::SecurityHeaderPolicy,
+    cookie_utilities::CookieSealer,
+    url_encoded_utilities::UrlEncodedParser,
+    router::{self, Router},
+    forwarding_utilities::TrustedProxyHeader,
+};
 
 #[derive(Clone)]
 pub struct HttpServerConfiguration {
     pub host: String,
     pub port: u16,
     pub powered_by: String,
+    pub server_name: String,
     pub is_web_socket_server_enabled: bool,
     pub is_tls_enabled: bool,
     pub tls_certificate_path: String,
     pub tls_private_key_path: String,
     pub is_http2_enabled: bool,
+    pub is_http3_enabled: bool,
+    pub http3_port: u16,
+    pub http3_alternative_service_max_age_in_seconds: u64,
+    pub unix_socket_path: String,
+    pub unix_socket_mode: Option<u32>,
+    pub unix_socket_owner_id: Option<u32>,
+    pub unix_socket_group_id: Option<u32>,
+    pub trusted_proxies: Vec<IpNet>,
+    pub trusted_proxy_header: TrustedProxyHeader,
+    pub proxy_protocol_listeners: Vec<String>,
+    pub proxy_protocol_timeout_in_milliseconds: u64,
+    pub maximum_body_size_in_bytes: u64,
+    pub maximum_body_sizes_per_content_type: Vec<(String, u64)>,
+    pub maximum_header_count: usize,
+    pub maximum_header_size_in_bytes: usize,
+    pub maximum_uri_length: usize,
+    pub maximum_connection_count: usize,
+    pub maximum_connection_count_per_ip_address: usize,
+    pub connection_queue_timeout_in_milliseconds: u64,
+    pub connection_statistics_path: String,
+    pub rate_limit_rules: Vec<RateLimitRule>,
+    pub rate_limit_key_header_name: String,
+    pub tls_handshake_timeout_in_milliseconds: u64,
+    pub header_read_timeout_in_milliseconds: u64,
+    pub body_read_timeout_in_milliseconds: u64,
+    pub keep_alive_timeout_in_milliseconds: u64,
+    pub http2_keep_alive_interval_in_milliseconds: u64,
+    pub http2_keep_alive_timeout_in_milliseconds: u64,
+    pub access_log_path: String,
+    pub access_log_format: AccessLogFormat,
+    pub access_log_maximum_size_in_bytes: u64,
+    pub access_log_rotation_interval_in_seconds: u64,
+    pub access_log_maximum_file_count: usize,
+    pub metrics_path: String,
+    pub metrics_address: String,
+    pub liveness_path: String,
+    pub readiness_path: String,
+    pub readiness_ping_interval_in_milliseconds: u64,
+    pub readiness_ping_timeout_in_milliseconds: u64,
+    pub shutdown_drain_timeout_in_milliseconds: u64,
+    pub request_id_header_name: String,
+    pub trace_exporter: TraceExporter,
+    pub trace_sample_ratio: f64,
+    pub cors_policy: CorsPolicy,
+    pub security_header_policy: SecurityHeaderPolicy,
+    pub cookie_sealer: CookieSealer,
+    pub url_encoded_parser: UrlEncodedParser,
+    pub are_raw_headers_preserved: bool,
+    pub router: Router,
+    pub upstream_connect_timeout_in_milliseconds: u64,
+    pub upstream_response_timeout_in_milliseconds: u64,
+    pub upstream_retry_count: usize,
+    pub upstream_health_check_interval_in_milliseconds: u64,
+    pub upstream_ca_path: String,
+    pub response_cache_size_in_bytes: usize,
+    pub are_etags_enabled: bool,
 }
 
 impl HttpServerConfiguration {
     pub fn from(configuration: Configuration) -> Self {
+        // mistakes in the routes file are logged, hence the routes cannot be loaded any earlier...
+        let router = router::load(configuration.routes_path.as_str());
         let http_server_configuration = HttpServerConfiguration {
             host: configuration.host,
             port: configuration.port,
             powered_by: configuration.powered_by,
+            server_name: configuration.server_name,
             is_web_socket_server_enabled: configuration.is_web_socket_server_enabled,
             is_tls_enabled: configuration.is_tls_enabled,
             tls_certificate_path: configuration.tls_certificate_path,
             tls_private_key_path: configuration.tls_private_key_path,
             is_http2_enabled: configuration.is_http2_enabled,
+            is_http3_enabled: configuration.is_http3_enabled,
+            http3_port: configuration.http3_port,
+            http3_alternative_service_max_age_in_seconds: configuration.http3_alternative_service_max_age_in_seconds,
+            unix_socket_path: configuration.unix_socket_path,
+            unix_socket_mode: configuration.unix_socket_mode,
+            unix_socket_owner_id: configuration.unix_socket_owner_id,
+            unix_socket_group_id: configuration.unix_socket_group_id,
+            trusted_proxies: configuration.trusted_proxies,
+            trusted_proxy_header: configuration.trusted_proxy_header,
+            proxy_protocol_listeners: configuration.proxy_protocol_listeners,
+            proxy_protocol_timeout_in_milliseconds: configuration.proxy_protocol_timeout_in_milliseconds,
+            maximum_body_size_in_bytes: configuration.maximum_body_size_in_bytes,
+            maximum_body_sizes_per_content_type: configuration.maximum_body_sizes_per_content_type,
+            maximum_header_count: configuration.maximum_header_count,
+            maximum_header_size_in_bytes: configuration.maximum_header_size_in_bytes,
+            maximum_uri_length: configuration.maximum_uri_length,
+            maximum_connection_count: configuration.maximum_connection_count,
+            maximum_connection_count_per_ip_address: configuration.maximum_connection_count_per_ip_address,
+            connection_queue_timeout_in_milliseconds: configuration.connection_queue_timeout_in_milliseconds,
+            connection_statistics_path: configuration.connection_statistics_path,
+            rate_limit_rules: configuration.rate_limit_rules,
+            rate_limit_key_header_name: configuration.rate_limit_key_header_name,
+            tls_handshake_timeout_in_milliseconds: configuration.tls_handshake_timeout_in_milliseconds,
+            header_read_timeout_in_milliseconds: configuration.header_read_timeout_in_milliseconds,
+            body_read_timeout_in_milliseconds: configuration.body_read_timeout_in_milliseconds,
+            keep_alive_timeout_in_milliseconds: configuration.keep_alive_timeout_in_milliseconds,
+            http2_keep_alive_interval_in_milliseconds: configuration.http2_keep_alive_interval_in_milliseconds,
+            http2_keep_alive_timeout_in_milliseconds: configuration.http2_keep_alive_timeout_in_milliseconds,
+            access_log_path: configuration.access_log_path,
+            access_log_format: configuration.access_log_format,
+            access_log_maximum_size_in_bytes: configuration.access_log_maximum_size_in_bytes,
+            access_log_rotation_interval_in_seconds: configuration.access_log_rotation_interval_in_seconds,
+            access_log_maximum_file_count: configuration.access_log_maximum_file_count,
+            metrics_path: configuration.metrics_path,
+            metrics_address: configuration.metrics_address,
+            liveness_path: configuration.liveness_path,
+            readiness_path: configuration.readiness_path,
+            readiness_ping_interval_in_milliseconds: configuration.readiness_ping_interval_in_milliseconds,
+            readiness_ping_timeout_in_milliseconds: configuration.readiness_ping_timeout_in_milliseconds,
+            shutdown_drain_timeout_in_milliseconds: configuration.shutdown_drain_timeout_in_milliseconds,
+            request_id_header_name: configuration.request_id_header_name,
+            trace_exporter: configuration.trace_exporter,
+            trace_sample_ratio: configuration.trace_sample_ratio,
+            cors_policy: configuration.cors_policy,
+            security_header_policy: configuration.security_header_policy,
+            cookie_sealer: configuration.cookie_sealer,
+            url_encoded_parser: configuration.url_encoded_parser,
+            are_raw_headers_preserved: configuration.are_raw_headers_preserved,
+            router,
+            upstream_connect_timeout_in_milliseconds: configuration.upstream_connect_timeout_in_milliseconds,
+            upstream_response_timeout_in_milliseconds: configuration.upstream_response_timeout_in_milliseconds,
+            upstream_retry_count: configuration.upstream_retry_count,
+            upstream_health_check_interval_in_milliseconds: configuration.upstream_health_check_interval_in_milliseconds,
+            upstream_ca_path: configuration.upstream_ca_path,
+            response_cache_size_in_bytes: configuration.response_cache_size_in_bytes,
+            are_etags_enabled: configuration.are_etags_enabled,
         };
 
         return http_server_configuration;
     }
+    // a configuration as if the server was started with the given arguments...
+    #[cfg(test)]
+    pub fn from_arguments(arguments: &[&str]) -> Self {
+        let name_only_arguments = crate::system::get_name_only_arguments();
+        let arguments_parser = crate::arguments_parser::ArgumentsParser::from_arguments(arguments, &name_only_arguments);
+
+        return HttpServerConfiguration::from(Configuration::from(&arguments_parser));
+    }
 }
@@ -0,0 +1,98 @@
+#![allow(clippy::needless_return)]
+
+use tokio::sync::watch::Receiver;
+
+use crate::{
+    access_logger::AccessLogger,
+    connection_limiter::ConnectionLimiter,
+    connection_activity::ConnectionActivity,
+    health_monitor::HealthMonitor,
+    http_server_configuration::HttpServerConfiguration,
+    id_generator::IdGenerator,
+    metrics::Metrics,
+    raw_header_recorder::RawHeaderRecorder,
+    reverse_proxy::ReverseProxy,
+    response_cache::ResponseCache,
+    conditional_request_handler::ConditionalRequestHandler,
+    rate_limiter::RateLimiter,
+    tracer::Tracer,
+};
+
+// negotiated parameters of a TLS connection...
+#[derive(Clone)]
+pub struct TlsInformation {
+    pub protocol_version: String,
+    // QUIC does not expose the cipher suite...
+    pub cipher_suite: Option<String>,
+}
+
+// everything a connection or a request handler needs, shared by all listeners...
+#[derive(Clone)]
+pub struct HttpServerContext {
+    pub receiver: Receiver<(u64, String)>,
+    pub configuration: HttpServerConfiguration,
+    pub id_generator: IdGenerator,
+    pub connection_limiter: ConnectionLimiter,
+    pub rate_limiter: RateLimiter,
+    pub access_logger: AccessLogger,
+    pub metrics: Metrics,
+    pub health_monitor: HealthMonitor,
+    pub tracer: Tracer,
+    pub reverse_proxy: ReverseProxy,
+    pub response_cache: ResponseCache,
+    pub conditional_request_handler: ConditionalRequestHandler,
+    // the following belong to a single connection and are replaced for every accepted connection...
+    pub connection_activity: ConnectionActivity,
+    pub tls_information: Option<TlsInformation>,
+    // none means raw headers are not preserved...
+    pub raw_header_recorder: Option<RawHeaderRecorder>,
+}
+
+impl HttpServerContext {
+    pub fn new(configuration: &HttpServerConfiguration, receiver: &Receiver<(u64, String)>) -> Self {
+        let connection_limiter = ConnectionLimiter::new(
+            configuration.maximum_connection_count,
+            configuration.maximum_connection_count_per_ip_address,
+            configuration.connection_queue_timeout_in_milliseconds,
+        );
+        let rate_limiter = RateLimiter::new(configuration.rate_limit_rules.clone());
+        let access_logger = AccessLogger::new(
+            configuration.access_log_path.as_str(),
+            configuration.access_log_format,
+            configuration.access_log_maximum_size_in_bytes,
+            configuration.access_log_rotation_interval_in_seconds,
+            configuration.access_log_maximum_file_count,
+        );
+
+        return HttpServerContext {
+            receiver: receiver.clone(),
+            configuration: configuration.clone(),
+            id_generator: IdGenerator::new(),
+            connection_limiter,
+            rate_limiter,
+            access_logger,
+            metrics: Metrics::new(),
+            health_monitor: HealthMonitor::new(
+                configuration.readiness_ping_interval_in_milliseconds,
+                configuration.readiness_ping_timeout_in_milliseconds,
+            ),
+            tracer: Tracer::new(&configuration.trace_exporter, configuration.trace_sample_ratio),
+            reverse_proxy: ReverseProxy::new(configuration),
+            response_cache: ResponseCache::new(configuration.response_cache_size_in_bytes),
+            conditional_request_handler: ConditionalRequestHandler::new(configuration.are_etags_enabled),
+            connection_activity: ConnectionActivity::new(),
+            tls_information: None,
+            raw_header_recorder: None,
+        };
+    }
+
+    pub fn for_connection(&self) -> Self {
+        let mut context = self.clone();
+
+        context.connection_activity = ConnectionActivity::new();
+        context.tls_information = None;
+        context.raw_header_recorder = if self.configuration.are_raw_headers_preserved { Some(RawHeaderRecorder::new()) } else { None };
+
+        return context;
+    }
+}
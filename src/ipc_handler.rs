@@ -1,14 +1,19 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap)]
+
 use std::io::{stdin, stdout, BufRead, Write};
 use std::thread;
 use std::thread::JoinHandle;
-use tokio::sync::watch;
+use tokio::sync::{mpsc::UnboundedSender, watch};
 use watch::Receiver;
 use watch::Sender;
+use log::{error, warn};
 
 const KEY_SEPARATOR: &str = "#";
 const KEY_SEPARATOR_LENGTH: usize = KEY_SEPARATOR.len();
+// request IDs start at one, so lines with this key are control messages of the parent process...
+pub const CONTROL_MESSAGE_KEY: u64 = 0;
 
-fn listen(sender: &Sender<(u64, String)>) {
+fn listen(sender: &Sender<(u64, String)>, control_message_sender: &UnboundedSender<String>) {
     let sender = sender.clone();
     let mut line_buffer = String::from("");
     let standard_input = stdin();
@@ -23,18 +28,27 @@ fn listen(sender: &Sender<(u64, String)>) {
         if result.is_err() {
             let error = result.unwrap_err();
 
-            eprintln!("An error occurred while reading line from the standard input: {}", error);
+            error!("An error occurred while reading line from the standard input: {}", error);
 
             continue;
         }
 
+        // if the parent process has closed the standard input, no reply will ever
+        // arrive. we shall stop listening so that the sender gets dropped and the
+        // receivers stop waiting...
+        if result.unwrap() == 0 {
+            error!("The standard input has been closed by the parent process.");
+
+            break;
+        }
+
         let line = line_buffer.trim().to_string();
         let index_of_key_separator_option = line.find(KEY_SEPARATOR);
 
         // if key separator is not found...
         if index_of_key_separator_option.is_none() {
             // we shall print an error message and continue to the next iteration...
-            eprintln!("Invalid line read from the standard input: {}", line);
+            warn!("Invalid line read from the standard input: {}", line);
 
             continue;
         }
@@ -47,7 +61,7 @@ fn listen(sender: &Sender<(u64, String)>) {
             let error = key_extraction_result.unwrap_err();
 
             // we shall print an error message and continue to the next iteration...
-            eprintln!("An error occurred during key extraction: {}", error);
+            error!("An error occurred during key extraction: {}", error);
 
             continue;
         }
@@ -55,13 +69,23 @@ fn listen(sender: &Sender<(u64, String)>) {
         let key = key_extraction_result.unwrap();
         let line_without_key = String::from(&line[index_of_key_separator + KEY_SEPARATOR_LENGTH..]);
 
+        // the watch channel only keeps the latest line, so control messages take a channel
+        // of their own where none of them can be overwritten by the next line...
+        if key == CONTROL_MESSAGE_KEY {
+            if control_message_sender.send(line_without_key).is_err() {
+                warn!("A control message has been received but no one is handling control messages.");
+            }
+
+            continue;
+        }
+
         let send_result = sender.send((key, line_without_key));
 
         if send_result.is_err() {
             let error = send_result.unwrap_err();
 
             // we shall print an error message...
-            eprintln!("An error occurred while sending the received line: {}", error);
+            error!("An error occurred while sending the received line: {}", error);
         }
     }
 }
@@ -82,9 +106,9 @@ pub async fn read_line_async(key: u64, receiver: Receiver<(u64, String)>) -> Str
     return String::from("");
 }
 
-pub fn write_line(text: &String) -> bool {
+pub fn write_line(text: &str) -> bool {
     let mut text_to_write = String::from("");
-    text_to_write.push_str(text.as_str());
+    text_to_write.push_str(text);
     text_to_write.push('\n');
     let bytes = text_to_write.as_bytes();
 
@@ -100,9 +124,9 @@ pub fn write_line(text: &String) -> bool {
     return true;
 }
 
-pub fn start(sender: Sender<(u64, String)>) -> JoinHandle<()> {
+pub fn start(sender: Sender<(u64, String)>, control_message_sender: UnboundedSender<String>) -> JoinHandle<()> {
     let join_handle = thread::spawn(move || {
-        listen(&sender);
+        listen(&sender, &control_message_sender);
     });
 
     return join_handle;
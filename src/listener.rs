@@ -0,0 +1,110 @@
+#![allow(clippy::needless_return)]
+
+use std::{
+    io,
+    os::unix::io::{FromRawFd, IntoRawFd, RawFd},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream, UnixListener, UnixStream},
+};
+
+use crate::{remote_address::RemoteAddress, unix_socket_utilities};
+
+#[derive(Debug)]
+pub enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+#[derive(Debug)]
+pub enum Stream {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+}
+
+impl Listener {
+    pub fn from_file_descriptor(file_descriptor: RawFd) -> io::Result<Self> {
+        // we shall first assume that the inherited socket is a TCP socket...
+        let tcp_listener = unsafe { std::net::TcpListener::from_raw_fd(file_descriptor) };
+
+        // local address can only be retrieved if the socket is of internet family...
+        if tcp_listener.local_addr().is_ok() {
+            tcp_listener.set_nonblocking(true)?;
+
+            return Ok(Listener::Tcp(TcpListener::from_std(tcp_listener)?));
+        }
+
+        // otherwise, we shall take the file descriptor back and try Unix domain socket...
+        let file_descriptor = tcp_listener.into_raw_fd();
+        let unix_listener = unsafe { std::os::unix::net::UnixListener::from_raw_fd(file_descriptor) };
+
+        unix_listener.local_addr()?;
+        unix_listener.set_nonblocking(true)?;
+
+        return Ok(Listener::Unix(UnixListener::from_std(unix_listener)?));
+    }
+
+    pub async fn accept_async(&self) -> io::Result<(Stream, RemoteAddress)> {
+        return match self {
+            Listener::Tcp(tcp_listener) => {
+                let (tcp_stream, socket_address) = tcp_listener.accept().await?;
+
+                Ok((Stream::Tcp(tcp_stream), RemoteAddress::Ip(socket_address)))
+            },
+            Listener::Unix(unix_listener) => {
+                let (unix_stream, _) = unix_listener.accept().await?;
+                let peer_credentials_option = unix_socket_utilities::get_peer_credentials(&unix_stream);
+
+                Ok((Stream::Unix(unix_stream), RemoteAddress::Unix(peer_credentials_option)))
+            },
+        };
+    }
+
+    pub fn get_local_address(&self) -> String {
+        return match self {
+            Listener::Tcp(tcp_listener) => tcp_listener.local_addr()
+                .map_or(String::from("unknown"), |socket_address| socket_address.to_string()),
+            Listener::Unix(unix_listener) => {
+                let path = unix_listener.local_addr().ok()
+                    .and_then(|socket_address| socket_address.as_pathname().map(|path| path.display().to_string()));
+
+                format!("unix:{}", path.unwrap_or(String::from("unnamed")))
+            },
+        };
+    }
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        return match self.get_mut() {
+            Stream::Tcp(tcp_stream) => Pin::new(tcp_stream).poll_read(context, buffer),
+            Stream::Unix(unix_stream) => Pin::new(unix_stream).poll_read(context, buffer),
+        };
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &[u8]) -> Poll<io::Result<usize>> {
+        return match self.get_mut() {
+            Stream::Tcp(tcp_stream) => Pin::new(tcp_stream).poll_write(context, buffer),
+            Stream::Unix(unix_stream) => Pin::new(unix_stream).poll_write(context, buffer),
+        };
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return match self.get_mut() {
+            Stream::Tcp(tcp_stream) => Pin::new(tcp_stream).poll_flush(context),
+            Stream::Unix(unix_stream) => Pin::new(unix_stream).poll_flush(context),
+        };
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return match self.get_mut() {
+            Stream::Tcp(tcp_stream) => Pin::new(tcp_stream).poll_shutdown(context),
+            Stream::Unix(unix_stream) => Pin::new(unix_stream).poll_shutdown(context),
+        };
+    }
+}
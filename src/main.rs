@@ -8,36 +8,103 @@ mod file_utilities;
 mod http_utilities;
 #[path = "utilities/web_socket_utilities.rs"]
 mod web_socket_utilities;
+#[path = "utilities/h2c_utilities.rs"]
+mod h2c_utilities;
+#[path = "utilities/forwarding_utilities.rs"]
+mod forwarding_utilities;
+#[path = "utilities/unix_socket_utilities.rs"]
+mod unix_socket_utilities;
+#[path = "utilities/socket_activation_utilities.rs"]
+mod socket_activation_utilities;
+#[path = "utilities/proxy_protocol_utilities.rs"]
+mod proxy_protocol_utilities;
+#[path = "utilities/request_limit_utilities.rs"]
+mod request_limit_utilities;
+#[path = "utilities/connection_limiter.rs"]
+mod connection_limiter;
+#[path = "utilities/rate_limiter.rs"]
+mod rate_limiter;
+#[path = "utilities/connection_activity.rs"]
+mod connection_activity;
+#[path = "utilities/access_logger.rs"]
+mod access_logger;
+#[path = "utilities/diagnostic_logger.rs"]
+mod diagnostic_logger;
+#[path = "utilities/metrics.rs"]
+mod metrics;
+#[path = "utilities/health_monitor.rs"]
+mod health_monitor;
+#[path = "utilities/request_id_utilities.rs"]
+mod request_id_utilities;
+#[path = "utilities/tracer.rs"]
+mod tracer;
+#[path = "utilities/cors_utilities.rs"]
+mod cors_utilities;
+#[path = "utilities/default_headers_utilities.rs"]
+mod default_headers_utilities;
+#[path = "utilities/cookie_utilities.rs"]
+mod cookie_utilities;
+#[path = "utilities/url_encoded_utilities.rs"]
+mod url_encoded_utilities;
+#[path = "utilities/raw_header_recorder.rs"]
+mod raw_header_recorder;
+#[path = "utilities/router.rs"]
+mod router;
+#[path = "utilities/static_file_utilities.rs"]
+mod static_file_utilities;
+#[path = "utilities/reverse_proxy.rs"]
+mod reverse_proxy;
+#[path = "utilities/response_cache.rs"]
+mod response_cache;
+#[path = "utilities/conditional_request_handler.rs"]
+mod conditional_request_handler;
 #[path = "configuration.rs"]
 mod configuration;
 #[path = "system.rs"]
 mod system;
 #[path = "http_server_configuration.rs"]
 mod http_server_configuration;
+#[path = "http_server_context.rs"]
+mod http_server_context;
 #[path = "http_server.rs"]
 mod http_server;
+#[path = "http3_server.rs"]
+mod http3_server;
 #[path = "ipc_handler.rs"]
 mod ipc_handler;
+#[path = "remote_address.rs"]
+mod remote_address;
+#[path = "listener.rs"]
+mod listener;
 
 use std::time::Duration;
-use tokio::sync::watch;
+use tokio::sync::{mpsc, watch};
 
 use crate::{
     arguments_parser::ArgumentsParser,
     configuration::Configuration,
     http_server_configuration::HttpServerConfiguration,
+    socket_activation_utilities::InheritedFileDescriptor,
 };
 
-async fn main_async(configuration: &Configuration) {
+async fn main_async(configuration: &Configuration, inherited_file_descriptors: Vec<InheritedFileDescriptor>) {
     let configuration = configuration.clone();
     // let line_delimiter = arguments_parser::get_argument("lineDelimiter", &arguments);
     let (sender, receiver) = watch::channel((0u64, String::from("")));
-    let join_handle = ipc_handler::start(sender);
+    let (control_message_sender, control_message_receiver) = mpsc::unbounded_channel();
+    // the IPC thread blocks on the standard input until the parent process closes it.
+    // the HTTP server stops on its own (e.g. if binding fails or after draining on
+    // shutdown) while the standard input is still open, so waiting for the thread
+    // would keep the process alive forever. the thread ends with the process instead...
+    _ = ipc_handler::start(sender, control_message_sender);
     let http_server_configuration = HttpServerConfiguration::from(configuration);
 
-    http_server::start_async(&http_server_configuration, &receiver).await;
-
-    _ = join_handle.join();
+    http_server::start_async(
+        &http_server_configuration,
+        &receiver,
+        control_message_receiver,
+        inherited_file_descriptors,
+    ).await;
 }
 
 fn main() {
@@ -57,8 +124,19 @@ fn main() {
     }
 
     let configuration = Configuration::from(&arguments_parser);
+
+    // anything reported before this point (e.g. invalid arguments) is written to the standard error...
+    diagnostic_logger::initialize(
+        &configuration.log_levels,
+        configuration.log_format,
+        configuration.log_output.as_str(),
+    );
     configuration.print();
 
+    // socket activation variables are read (and removed) before any other thread is started...
+    let inherited_file_descriptors = socket_activation_utilities::
+        take_inherited_file_descriptors(configuration.listen_file_descriptor);
+
     tokio::runtime::Builder::new_multi_thread()
         .enable_io()
         .enable_time()
@@ -70,6 +148,6 @@ fn main() {
         .build()
         .unwrap()
         .block_on(async {
-            main_async(&configuration).await;
+            main_async(&configuration, inherited_file_descriptors).await;
         });
 }
@@ -0,0 +1,59 @@
+#![allow(clippy::needless_return)]
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, net::SocketAddr};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerCredentials {
+    pub process_id: Option<i32>,
+    pub user_id: u32,
+    pub group_id: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum RemoteAddress {
+    // remote address of a peer connected over IP (TCP or QUIC)...
+    Ip(SocketAddr),
+    // credentials of a peer connected via Unix domain socket (none if unavailable)...
+    Unix(Option<PeerCredentials>),
+}
+
+impl RemoteAddress {
+    pub fn get_ip_address(&self) -> Option<String> {
+        return match self {
+            RemoteAddress::Ip(socket_address) => Some(socket_address.ip().to_string()),
+            RemoteAddress::Unix(_) => None,
+        };
+    }
+
+    pub fn get_port(&self) -> Option<u16> {
+        return match self {
+            RemoteAddress::Ip(socket_address) => Some(socket_address.port()),
+            RemoteAddress::Unix(_) => None,
+        };
+    }
+
+    pub fn get_peer_credentials(&self) -> Option<PeerCredentials> {
+        return match self {
+            RemoteAddress::Ip(_) => None,
+            RemoteAddress::Unix(peer_credentials_option) => *peer_credentials_option,
+        };
+    }
+}
+
+impl fmt::Display for RemoteAddress {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            RemoteAddress::Ip(socket_address) => write!(formatter, "{}:{}", socket_address.ip(), socket_address.port()),
+            RemoteAddress::Unix(None) => write!(formatter, "unix:unknown"),
+            RemoteAddress::Unix(Some(peer_credentials)) => write!(
+                formatter,
+                "unix:pid={},uid={},gid={}",
+                peer_credentials.process_id.map_or(String::from("?"), |process_id| process_id.to_string()),
+                peer_credentials.user_id,
+                peer_credentials.group_id,
+            ),
+        };
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 const VERSION: &str = "0.0.1";
 
 pub fn get_name_only_arguments() -> Vec<String> {
@@ -8,6 +10,10 @@ pub fn get_name_only_arguments() -> Vec<String> {
         "enableWebSocketServer".to_string(),
         "enableTls".to_string(),
         "enableHttp2".to_string(),
+        "enableHttp3".to_string(),
+        "enableSecurityHeaders".to_string(),
+        "preserveRawHeaders".to_string(),
+        "enableEtags".to_string(),
     ];
 }
 
@@ -22,12 +28,151 @@ pub fn print_help() {
     println!("--host                             Sets the address on which the server shall bind to.   Example: volcano-http-server --host 127.0.0.1");
     println!("--port                             Sets the port on which the server shall listen.       Example: volcano-http-server --port 61798");
     println!("--poweredBy                        Sets default X-Powered-By header.                     Example: volcano-http-server --poweredBy Volcano");
+    println!("--serverHeader                     Sets default Server header (empty disables it).       Example: volcano-http-server --serverHeader volcano-http-server");
     println!("--workerThreadCount                Sets the number of worker threads to use.             Example: volcano-http-server --workerThreadCount 16");
     println!("--enableWebSocketServer            Enables the WebSocket server.                         Example: volcano-http-server --enableWebSocketServer");
     println!("--enableTls                        Enables HTTPS.                                        Example: volcano-http-server --enableTls");
     println!("--tlsCertificatePath               Sets the TLS certificate path.                        Example: volcano-http-server --tlsCertificatePath tls_certificate.pem");
     println!("--tlsPrivateKeyPath                Sets the TLS private key path.                        Example: volcano-http-server --tlsPrivateKeyPath tls_private_key.key");
-    println!("--enableHttp2                      Enables HTTP/2 (TLS must be enabled).                 Example: volcano-http-server --enableTls --enableHttp2");
+    println!("--enableHttp2                      Enables HTTP/2 (h2c when TLS is not enabled).         Example: volcano-http-server --enableTls --enableHttp2");
+    println!("--enableHttp3                      Enables HTTP/3 over QUIC (TLS must be enabled).       Example: volcano-http-server --enableTls --enableHttp3");
+    println!("--http3Port                        Sets the UDP port for HTTP/3 (defaults to --port).    Example: volcano-http-server --http3Port 443");
+    println!("--http3AltSvcMaxAge                Sets max age in seconds of the Alt-Svc header.        Example: volcano-http-server --http3AltSvcMaxAge 86400");
+    println!("--unixSocketPath                   Listens on a Unix domain socket instead of TCP.       Example: volcano-http-server --unixSocketPath /run/volcano.sock");
+    println!("--unixSocketMode                   Sets the file mode (octal) of the Unix domain socket. Example: volcano-http-server --unixSocketMode 660");
+    println!("--unixSocketOwnerId                Sets the owner (user ID) of the Unix domain socket.   Example: volcano-http-server --unixSocketOwnerId 1000");
+    println!("--unixSocketGroupId                Sets the group ID of the Unix domain socket.          Example: volcano-http-server --unixSocketGroupId 33");
+    println!("--listenFd                         Uses an inherited listening socket instead of binding. Example: volcano-http-server --listenFd 3");
+    println!("                                   LISTEN_FDS/LISTEN_PID (systemd) are honoured as well.");
+    println!("--trustedProxies                   Sets comma separated proxy addresses/CIDRs whose      Example: volcano-http-server --trustedProxies 10.0.0.0/8,127.0.0.1");
+    println!("                                   Forwarded/X-Forwarded-* headers shall be honoured.");
+    println!("--trustedProxyHeader               Sets the header the trusted proxies set, either       Example: volcano-http-server --trustedProxyHeader forwarded");
+    println!("                                   x-forwarded-for (default) or forwarded. The other one");
+    println!("                                   is ignored as it may have been sent by the client.");
+    println!("--proxyProtocolListeners           Sets comma separated listeners that require a PROXY   Example: volcano-http-server --proxyProtocolListeners default");
+    println!("                                   protocol (v1/v2) header. A listener is 'all', 'default'");
+    println!("                                   (the one bound by us), an inherited file descriptor or");
+    println!("                                   a LISTEN_FDNAMES name.");
+    println!("--proxyProtocolTimeout             Sets timeout in milliseconds to receive the PROXY     Example: volcano-http-server --proxyProtocolTimeout 5000");
+    println!("                                   protocol header.");
+    println!("--maximumBodySize                  Sets the maximum request body size in bytes.          Example: volcano-http-server --maximumBodySize 10485760");
+    println!("                                   Defaults to 10485760 (10 MiB). Previously unlimited.");
+    println!("--maximumBodySizePerContentType    Sets comma separated body size limits per content     Example: volcano-http-server --maximumBodySizePerContentType application/json=1048576,text/*=65536");
+    println!("                                   type (overrides --maximumBodySize).");
+    println!("--maximumHeaderCount               Sets the maximum number of request headers.           Example: volcano-http-server --maximumHeaderCount 100");
+    println!("                                   Defaults to 100 (HTTP/1 never allows more).");
+    println!("--maximumHeaderSize                Sets the maximum total size of request headers.       Example: volcano-http-server --maximumHeaderSize 16384");
+    println!("                                   Defaults to 16384 bytes (previously ~400 KiB).");
+    println!("--maximumUriLength                 Sets the maximum length of the request URI.           Example: volcano-http-server --maximumUriLength 8192");
+    println!("                                   Defaults to 8192 (previously ~400 KiB).");
+    println!("--maximumConnectionCount           Sets the maximum number of concurrent connections     Example: volcano-http-server --maximumConnectionCount 10000");
+    println!("                                   Defaults to 10000 (previously unlimited), 0 means");
+    println!("                                   unlimited.");
+    println!("--maximumConnectionCountPerIp      Sets the maximum number of concurrent connections     Example: volcano-http-server --maximumConnectionCountPerIp 64");
+    println!("                                   per client IP address (0 means unlimited).");
+    println!("--connectionQueueTimeout           Sets how long in milliseconds an excess connection    Example: volcano-http-server --connectionQueueTimeout 1000");
+    println!("                                   waits for a free slot before it is closed.");
+    println!("--connectionStatisticsPath         Serves connection counts as JSON on this path.        Example: volcano-http-server --connectionStatisticsPath /_volcano/connections");
+    println!("--metricsPath                      Serves Prometheus metrics on this path.               Example: volcano-http-server --metricsPath /metrics");
+    println!("--metricsAddress                   Serves metrics on a separate admin listener at this   Example: volcano-http-server --metricsAddress 127.0.0.1:9100");
+    println!("                                   host:port instead of the regular listeners (the path");
+    println!("                                   defaults to /metrics).");
+    println!("--livenessPath                     Answers liveness probes with JSON on this path.       Example: volcano-http-server --livenessPath /_volcano/live");
+    println!("--readinessPath                    Answers readiness probes with JSON on this path (503  Example: volcano-http-server --readinessPath /_volcano/ready");
+    println!("                                   when the parent process is unresponsive or draining).");
+    println!("--readinessPingInterval            Sets interval in milliseconds of pings to the parent  Example: volcano-http-server --readinessPingInterval 5000");
+    println!("                                   process (0 disables pings). A ping is a JSON line with");
+    println!("                                   \"type\": \"ping\" and \"pingId\" that the parent process");
+    println!("                                   answers with 0#{{\"type\":\"pong\",\"pingId\":<pingId>}}.");
+    println!("--readinessPingTimeout             Sets timeout in milliseconds to receive a ping reply. Example: volcano-http-server --readinessPingTimeout 1000");
+    println!("--shutdownDrainTimeout             Sets how long in milliseconds requests are still      Example: volcano-http-server --shutdownDrainTimeout 10000");
+    println!("                                   served (while not ready) after a termination signal.");
+    println!("--requestIdHeader                  Honours (if valid) and echoes the request ID in this  Example: volcano-http-server --requestIdHeader X-Correlation-Id");
+    println!("                                   header. Otherwise a UUID v7 is generated.");
+    println!("--traceExporter                    Exports a span per request (continuing W3C trace      Example: volcano-http-server --traceExporter otlp");
+    println!("                                   context) to an OTLP collector or to a file. Expected");
+    println!("                                   none, otlp or file.");
+    println!("--traceOtlpEndpoint                Sets the OTLP/HTTP endpoint spans are posted to.      Example: volcano-http-server --traceOtlpEndpoint http://127.0.0.1:4318/v1/traces");
+    println!("--traceFile                        Sets the file spans are appended to (OTLP JSON).      Example: volcano-http-server --traceFile traces.json");
+    println!("--traceSampleRatio                 Sets the ratio of new traces that are recorded.       Example: volcano-http-server --traceSampleRatio 0.1");
+    println!("--corsAllowedOrigins               Handles CORS for these comma separated origins (may   Example: volcano-http-server --corsAllowedOrigins https://example.com,https://*.example.com");
+    println!("                                   contain * as wildcard, or be * for any origin).");
+    println!("--corsAllowedMethods               Sets comma separated methods allowed by preflights.   Example: volcano-http-server --corsAllowedMethods GET,POST");
+    println!("--corsAllowedHeaders               Sets comma separated request headers allowed by       Example: volcano-http-server --corsAllowedHeaders Content-Type,Authorization");
+    println!("                                   preflights (requested headers are allowed if empty).");
+    println!("--corsExposedHeaders               Sets comma separated response headers exposed to      Example: volcano-http-server --corsExposedHeaders X-Request-Id");
+    println!("                                   scripts.");
+    println!("--corsAllowCredentials             Allows requests with credentials (cookies etc.).      Example: volcano-http-server --corsAllowCredentials true");
+    println!("                                   Only applies to origins listed other than \"*\".");
+    println!("--corsMaxAge                       Sets how long in seconds preflights may be cached.    Example: volcano-http-server --corsMaxAge 600");
+    println!("--enableSecurityHeaders            Adds security headers to responses unless the parent  Example: volcano-http-server --enableSecurityHeaders");
+    println!("                                   process sets them (an empty value removes a header).");
+    println!("--strictTransportSecurity          Sets Strict-Transport-Security header (TLS only).     Example: volcano-http-server --strictTransportSecurity \"max-age=31536000\"");
+    println!("--referrerPolicy                   Sets Referrer-Policy header.                          Example: volcano-http-server --referrerPolicy no-referrer");
+    println!("--frameOptions                     Sets X-Frame-Options header.                          Example: volcano-http-server --frameOptions SAMEORIGIN");
+    println!("--contentSecurityPolicy            Sets Content-Security-Policy header (not sent if      Example: volcano-http-server --contentSecurityPolicy \"default-src 'self'\"");
+    println!("                                   empty).");
+    println!("--cookieSecretPath                 Sets the file containing the secret (at least 32      Example: volcano-http-server --cookieSecretPath cookie_secret.key");
+    println!("                                   bytes) used to sign and encrypt cookie values.");
+    println!("--urlEncodedParser                 Sets how query strings and URL encoded forms are      Example: volcano-http-server --urlEncodedParser extended");
+    println!("                                   parsed. simple yields flat lists of values, extended");
+    println!("                                   additionally yields nested JSON (e.g. a[b][]=1).");
+    println!("--preserveRawHeaders               Passes HTTP/1 request headers in original order and   Example: volcano-http-server --preserveRawHeaders");
+    println!("                                   casing (rawHeaders) to the parent process.");
+    println!("--routesPath                       Sets the JSON file of routes that are matched (in     Example: volcano-http-server --routesPath routes.json");
+    println!("                                   order) by method, host and path (e.g. /users/:id or");
+    println!("                                   /assets/*) before a request is forwarded to the parent");
+    println!("                                   process. Actions are parent, static, redirect, respond");
+    println!("                                   and proxy.");
+    println!("--upstreamConnectTimeout           Sets the time in milliseconds to connect to an        Example: volcano-http-server --upstreamConnectTimeout 5000");
+    println!("                                   upstream of a proxy route (0 disables it).");
+    println!("--upstreamResponseTimeout          Sets the time in milliseconds an upstream may take    Example: volcano-http-server --upstreamResponseTimeout 60000");
+    println!("                                   to respond (0 disables it).");
+    println!("--upstreamRetryCount               Sets how many times idempotent requests without body  Example: volcano-http-server --upstreamRetryCount 1");
+    println!("                                   are retried on the next upstream after a failure.");
+    println!("--upstreamHealthCheckInterval      Sets the interval in milliseconds of health checks    Example: volcano-http-server --upstreamHealthCheckInterval 10000");
+    println!("                                   (healthCheckPath) and how long failed upstreams are");
+    println!("                                   avoided (0 disables health checking).");
+    println!("--upstreamCaPath                   Sets the PEM file of CA certificates trusted for      Example: volcano-http-server --upstreamCaPath /etc/ssl/cert.pem");
+    println!("                                   https:// upstreams. The trust store of the system is");
+    println!("                                   used if not specified.");
+    println!("--responseCacheSize                Sets the memory in bytes of the cache of parent       Example: volcano-http-server --responseCacheSize 67108864");
+    println!("                                   process responses (0 disables it). Responses are");
+    println!("                                   cached as per Cache-Control, Expires and Vary and");
+    println!("                                   purged by the parent process via control message");
+    println!("                                   0#{{\"type\":\"purgeCache\",\"keys\":[],\"tags\":[]}}.");
+    println!("--enableEtags                      Adds strong ETags to parent process responses and     Example: volcano-http-server --enableEtags");
+    println!("                                   answers conditional requests (304 and 412).");
+    println!("--rateLimits                       Sets comma separated rate limits per path prefix as   Example: volcano-http-server --rateLimits /api=100/60,/login=5/60");
+    println!("                                   <path-prefix>=<limit>/<window-in-seconds>.");
+    println!("--rateLimitKeyHeader               Identifies clients by this header (e.g. an API key)   Example: volcano-http-server --rateLimitKeyHeader X-Api-Key");
+    println!("                                   in addition to the IP address when present.");
+    println!("--tlsHandshakeTimeout              Sets TLS handshake timeout in milliseconds.           Example: volcano-http-server --tlsHandshakeTimeout 10000");
+    println!("--headerReadTimeout                Sets timeout in milliseconds to receive the request   Example: volcano-http-server --headerReadTimeout 10000");
+    println!("                                   headers (HTTP/1).");
+    println!("--bodyReadTimeout                  Sets timeout in milliseconds to receive the request   Example: volcano-http-server --bodyReadTimeout 30000");
+    println!("                                   body.");
+    println!("--keepAliveTimeout                 Sets timeout in milliseconds after which an idle      Example: volcano-http-server --keepAliveTimeout 60000");
+    println!("                                   connection is closed.");
+    println!("--http2KeepAliveInterval           Sets interval in milliseconds of HTTP/2 keep alive    Example: volcano-http-server --http2KeepAliveInterval 30000");
+    println!("                                   pings (0 disables pings).");
+    println!("--http2KeepAliveTimeout            Sets timeout in milliseconds to receive HTTP/2 ping   Example: volcano-http-server --http2KeepAliveTimeout 20000");
+    println!("                                   acknowledgements.");
+    println!("                                   A timeout of 0 disables the respective timeout.");
+    println!("--accessLog                        Writes an access log entry per request to this file   Example: volcano-http-server --accessLog /var/log/volcano/access.log");
+    println!("                                   ('stderr' writes to the standard error). The file is");
+    println!("                                   reopened on SIGUSR1 (e.g. after logrotate).");
+    println!("--accessLogFormat                  Sets access log format (common, combined or json).    Example: volcano-http-server --accessLogFormat json");
+    println!("--accessLogMaximumSize             Rotates the access log file when it exceeds this size Example: volcano-http-server --accessLogMaximumSize 104857600");
+    println!("                                   in bytes (0 disables).");
+    println!("--accessLogRotationInterval        Rotates the access log file every this many seconds   Example: volcano-http-server --accessLogRotationInterval 86400");
+    println!("                                   (0 disables).");
+    println!("--accessLogMaximumFileCount        Sets the number of rotated access log files to keep.  Example: volcano-http-server --accessLogMaximumFileCount 7");
+    println!("--logLevel                         Sets the log level (off, error, warn, info, debug or  Example: volcano-http-server --logLevel info,http_server=debug,quinn=warn");
+    println!("                                   trace), optionally per module.");
+    println!("--logFormat                        Sets the log format (human or json).                  Example: volcano-http-server --logFormat json");
+    println!("--logOutput                        Writes logs to 'stderr', 'journald' or a file. The    Example: volcano-http-server --logOutput /var/log/volcano/volcano.log");
+    println!("                                   file is reopened on SIGUSR1 (e.g. after logrotate).");
     println!("--maximumBlockingThreadCount       Sets the maximum number of blocking threads to use.   Example: volcano-http-server --maximumBlockingThreadCount 1024");
     println!("--blockingThreadKeepAliveTimeout   Sets keep alive timeout in milliseconds for the       Example: volcano-http-server --blockingThreadKeepAliveTimeout 10000");
     println!("                                   blocking threads.");
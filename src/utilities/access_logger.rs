@@ -0,0 +1,350 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero, clippy::assign_op_pattern)]
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+use log::error;
+
+// writes to the standard error instead of a file. the standard
+// output is reserved for communication with the parent process...
+const STANDARD_ERROR_DESTINATION: &str = "stderr";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccessLogFormat {
+    // Common Log Format...
+    Common,
+    // Combined Log Format (Common Log Format with referer and user agent)...
+    Combined,
+    // one JSON object per line...
+    Json,
+}
+
+pub struct AccessLogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub request_id: String,
+    pub client_ip_address: String,
+    pub method: String,
+    pub target: String,
+    pub protocol: String,
+    pub status_code: u16,
+    pub response_size_in_bytes: Option<u64>,
+    pub duration: Duration,
+    pub user_agent: Option<String>,
+    pub referer: Option<String>,
+    pub tls_protocol_version: Option<String>,
+    pub tls_cipher_suite: Option<String>,
+}
+
+struct AccessLogFile {
+    file: File,
+    size_in_bytes: u64,
+    // none means the file is not rotated periodically...
+    rotate_at_option: Option<Instant>,
+}
+
+enum AccessLogCommand {
+    Write(String),
+    Reopen,
+}
+
+// owns the access log file. it runs on a dedicated thread so that
+// writing and rotating the file never blocks the request handlers...
+struct AccessLogWriter {
+    path: String,
+    // zero means the file is not rotated by size...
+    maximum_size_in_bytes: u64,
+    // zero means the file is not rotated periodically...
+    rotation_interval_in_seconds: u64,
+    // number of rotated files to keep...
+    maximum_file_count: usize,
+    // none means the access log is written to the standard error...
+    file_option: Option<AccessLogFile>,
+}
+
+#[derive(Clone)]
+pub struct AccessLogger {
+    format: AccessLogFormat,
+    // none means access logging is disabled...
+    sender_option: Option<Sender<AccessLogCommand>>,
+}
+
+pub fn parse_format(format: &str) -> AccessLogFormat {
+    return match format.trim().to_lowercase().as_str() {
+        "common" => AccessLogFormat::Common,
+        "combined" => AccessLogFormat::Combined,
+        "json" => AccessLogFormat::Json,
+        _ => {
+            eprintln!("Invalid access log format '{}'. Expected common, combined or json. Falling back to combined.", format);
+
+            AccessLogFormat::Combined
+        },
+    };
+}
+
+// quotes are escaped so that values cannot break out of quoted fields. control
+// characters are escaped so that a value cannot forge another log line...
+fn escape(value: &str) -> String {
+    let mut escaped_value = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        match character {
+            '"' => escaped_value.push_str("\\\""),
+            '\\' => escaped_value.push_str("\\\\"),
+            character if character.is_control() => escaped_value.push_str(format!("\\x{:02x}", character as u32).as_str()),
+            character => escaped_value.push(character),
+        }
+    }
+
+    return escaped_value;
+}
+
+fn format_common(entry: &AccessLogEntry) -> String {
+    return format!(
+        "{} - - [{}] \"{} {} {}\" {} {}",
+        escape(entry.client_ip_address.as_str()),
+        entry.timestamp.format("%d/%b/%Y:%H:%M:%S %z"),
+        escape(entry.method.as_str()),
+        escape(entry.target.as_str()),
+        entry.protocol,
+        entry.status_code,
+        entry.response_size_in_bytes.map_or(String::from("-"), |size| size.to_string()),
+    );
+}
+
+fn format_combined(entry: &AccessLogEntry) -> String {
+    return format!(
+        "{} \"{}\" \"{}\"",
+        format_common(entry),
+        escape(entry.referer.as_deref().unwrap_or("-")),
+        escape(entry.user_agent.as_deref().unwrap_or("-")),
+    );
+}
+
+fn format_json(entry: &AccessLogEntry) -> String {
+    // serde takes care of escaping...
+    return json!({
+        "timestamp": entry.timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        "requestId": entry.request_id,
+        "clientIpAddress": entry.client_ip_address,
+        "method": entry.method,
+        "target": entry.target,
+        "protocol": entry.protocol,
+        "statusCode": entry.status_code,
+        "responseSizeInBytes": entry.response_size_in_bytes,
+        "durationInMilliseconds": entry.duration.as_secs_f64() * 1000.0,
+        "userAgent": entry.user_agent,
+        "referer": entry.referer,
+        "tlsProtocolVersion": entry.tls_protocol_version,
+        "tlsCipherSuite": entry.tls_cipher_suite,
+    }).to_string();
+}
+
+fn get_rotated_file_path(path: &str, index: usize) -> String {
+    return format!("{}.{}", path, index);
+}
+
+impl AccessLogWriter {
+    fn is_written_to_standard_error(&self) -> bool {
+        return self.path.eq(STANDARD_ERROR_DESTINATION);
+    }
+
+    fn open_file(&self) -> Option<AccessLogFile> {
+        let file_result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path.as_str());
+
+        if file_result.is_err() {
+            let error = file_result.unwrap_err();
+
+            error!("An error occurred while opening access log file '{}': {}", self.path, error);
+
+            return None;
+        }
+
+        let file = file_result.unwrap();
+        // we continue where the existing file left off...
+        let size_in_bytes = file.metadata().map_or(0, |metadata| metadata.len());
+        let rotate_at_option = if self.rotation_interval_in_seconds == 0 {
+            None
+        } else {
+            Some(Instant::now() + Duration::from_secs(self.rotation_interval_in_seconds))
+        };
+
+        return Some(AccessLogFile { file, size_in_bytes, rotate_at_option });
+    }
+
+    fn shift_rotated_files(&self) -> io::Result<()> {
+        // if no rotated file shall be kept, the current file is simply discarded...
+        if self.maximum_file_count == 0 {
+            return fs::remove_file(self.path.as_str());
+        }
+
+        // "access.log.1" becomes "access.log.2" and so on. the oldest file is overwritten...
+        for index in (1..self.maximum_file_count).rev() {
+            let rotated_file_path = get_rotated_file_path(self.path.as_str(), index);
+
+            if fs::metadata(rotated_file_path.as_str()).is_ok() {
+                fs::rename(rotated_file_path, get_rotated_file_path(self.path.as_str(), index + 1))?;
+            }
+        }
+
+        return fs::rename(self.path.as_str(), get_rotated_file_path(self.path.as_str(), 1));
+    }
+
+    fn rotate(&mut self) {
+        // the current file must be closed before it is renamed...
+        self.file_option = None;
+
+        let shift_result = self.shift_rotated_files();
+
+        if shift_result.is_err() {
+            let error = shift_result.unwrap_err();
+
+            error!("An error occurred while rotating access log file '{}': {}", self.path, error);
+        }
+
+        self.file_option = self.open_file();
+    }
+
+    fn should_rotate(&self, access_log_file: &AccessLogFile, line_length: u64) -> bool {
+        if self.maximum_size_in_bytes > 0
+            && access_log_file.size_in_bytes > 0
+            && access_log_file.size_in_bytes + line_length > self.maximum_size_in_bytes {
+            return true;
+        }
+
+        return access_log_file.rotate_at_option.is_some()
+            && Instant::now() >= access_log_file.rotate_at_option.unwrap();
+    }
+
+    fn reopen(&mut self) {
+        if self.is_written_to_standard_error() {
+            return;
+        }
+
+        // the previous file is closed first...
+        self.file_option = None;
+        self.file_option = self.open_file();
+    }
+
+    fn write(&mut self, line: &str) {
+        if self.is_written_to_standard_error() {
+            eprint!("{}", line);
+
+            return;
+        }
+
+        let line_length = line.len() as u64;
+
+        if self.file_option.is_some() && self.should_rotate(self.file_option.as_ref().unwrap(), line_length) {
+            self.rotate();
+        }
+
+        // if the file could not be opened, the entry is lost...
+        if self.file_option.is_none() {
+            return;
+        }
+
+        let access_log_file = self.file_option.as_mut().unwrap();
+        let write_result = access_log_file.file.write_all(line.as_bytes());
+
+        if write_result.is_err() {
+            let error = write_result.unwrap_err();
+
+            error!("An error occurred while writing to access log file '{}': {}", self.path, error);
+
+            return;
+        }
+
+        access_log_file.size_in_bytes = access_log_file.size_in_bytes + line_length;
+    }
+
+    fn run(mut self, receiver: Receiver<AccessLogCommand>) {
+        // the thread ends once every logger is dropped...
+        for command in receiver {
+            match command {
+                AccessLogCommand::Write(line) => self.write(line.as_str()),
+                AccessLogCommand::Reopen => self.reopen(),
+            }
+        }
+    }
+}
+
+impl AccessLogger {
+    pub fn new(
+        path: &str,
+        format: AccessLogFormat,
+        maximum_size_in_bytes: u64,
+        rotation_interval_in_seconds: u64,
+        maximum_file_count: usize,
+    ) -> Self {
+        // empty path means access logging is disabled...
+        if path.len() == 0 {
+            return AccessLogger { format, sender_option: None };
+        }
+
+        let mut access_log_writer = AccessLogWriter {
+            path: path.to_string(),
+            maximum_size_in_bytes,
+            rotation_interval_in_seconds,
+            maximum_file_count,
+            file_option: None,
+        };
+
+        if !access_log_writer.is_written_to_standard_error() {
+            access_log_writer.file_option = access_log_writer.open_file();
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let spawn_result = thread::Builder::new()
+            .name(String::from("access-logger"))
+            .spawn(move || access_log_writer.run(receiver));
+
+        if spawn_result.is_err() {
+            let error = spawn_result.unwrap_err();
+
+            error!("An error occurred while starting access log writer: {}", error);
+
+            return AccessLogger { format, sender_option: None };
+        }
+
+        return AccessLogger { format, sender_option: Some(sender) };
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        return self.sender_option.is_some();
+    }
+
+    // reopens the file after it has been moved away by an external tool (e.g. logrotate)...
+    pub fn reopen(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        _ = self.sender_option.as_ref().unwrap().send(AccessLogCommand::Reopen);
+    }
+
+    pub fn log(&self, entry: &AccessLogEntry) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut line = match self.format {
+            AccessLogFormat::Common => format_common(entry),
+            AccessLogFormat::Combined => format_combined(entry),
+            AccessLogFormat::Json => format_json(entry),
+        };
+
+        line.push('\n');
+
+        // the line is written by the writer thread...
+        _ = self.sender_option.as_ref().unwrap().send(AccessLogCommand::Write(line));
+    }
+}
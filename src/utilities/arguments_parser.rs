@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::assign_op_pattern, clippy::needless_late_init)]
+
 use std::collections::HashMap;
 use std::env;
 
@@ -10,8 +12,9 @@ pub struct ArgumentsParser {
 }
 
 impl ArgumentsParser {
-    pub fn new(name_only_arguments: &Vec<String>) -> Self {
-        let arguments_map = populate_arguments_map(name_only_arguments);
+    pub fn new(name_only_arguments: &[String]) -> Self {
+        let arguments: Vec<String> = env::args().collect();
+        let arguments_map = populate_arguments_map(&arguments, name_only_arguments);
         let arguments_parser = ArgumentsParser {
             arguments_map,
         };
@@ -19,6 +22,20 @@ impl ArgumentsParser {
         return arguments_parser;
     }
 
+    // parses the given arguments instead of the ones of the process...
+    #[cfg(test)]
+    pub fn from_arguments(arguments: &[&str], name_only_arguments: &[String]) -> Self {
+        // the first argument is the program name...
+        let arguments: Vec<String> = std::iter::once("volcano-http-server")
+            .chain(arguments.iter().copied())
+            .map(String::from)
+            .collect();
+
+        return ArgumentsParser {
+            arguments_map: populate_arguments_map(&arguments, name_only_arguments),
+        };
+    }
+
     pub fn get_as_boolean(&self, argument_name: &str, default_value: &str) -> bool {
         let argument_value = self.get_as_string(argument_name, default_value);
 
@@ -83,9 +100,8 @@ impl ArgumentsParser {
     }
 }
 
-fn populate_arguments_map(name_only_arguments: &Vec<String>) -> HashMap<String, String> {
+fn populate_arguments_map(arguments: &[String], name_only_arguments: &[String]) -> HashMap<String, String> {
     let mut i = 1;
-    let arguments: Vec<String> = env::args().collect();
     let mut arguments_map: HashMap<String, String> = HashMap::with_capacity(arguments.len());
 
     while i < arguments.len() {
@@ -97,7 +113,7 @@ fn populate_arguments_map(name_only_arguments: &Vec<String>) -> HashMap<String,
             continue;
         }
 
-        let argument_name = (&argument[ARGUMENT_NAME_PREFIX_LENGTH..]).to_string();
+        let argument_name = argument[ARGUMENT_NAME_PREFIX_LENGTH..].to_string();
         let argument_value: String;
 
         if name_only_arguments.contains(&argument_name) {
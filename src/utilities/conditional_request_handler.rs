@@ -0,0 +1,393 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use hyper::{header::HeaderValue, Body, HeaderMap, Method, Request, Response, StatusCode};
+use ring::digest::{digest, SHA256};
+use log::warn;
+
+const ETAG_HEADER_NAME: &str = "etag";
+const WEAK_ETAG_PREFIX: &str = "W/";
+const ANY_ETAG: &str = "*";
+// 128 bits of the SHA-256 digest are plenty to tell representations apart...
+const ETAG_DIGEST_LENGTH_IN_BYTES: usize = 16;
+// describe the body of a 200 response and don't belong to a 304 response...
+const CONTENT_HEADER_NAMES: [&str; 3] = ["content-type", "content-length", "transfer-encoding"];
+
+// the conditional headers of a request, taken before the request is consumed...
+pub struct ConditionalRequest {
+    is_safe: bool,
+    if_match_option: Option<Vec<String>>,
+    if_none_match_option: Option<Vec<String>>,
+    if_modified_since_option: Option<DateTime<Utc>>,
+    if_unmodified_since_option: Option<DateTime<Utc>>,
+}
+
+// answers conditional requests (RFC 9110 section 13) on behalf of the parent process...
+#[derive(Clone)]
+pub struct ConditionalRequestHandler {
+    is_enabled: bool,
+}
+
+// a strong ETag derived from the body (e.g. "\"q1OfgeNaW0AE3rvf2ZUNrA\"")...
+pub fn create_etag(body: &[u8]) -> String {
+    let body_digest = digest(&SHA256, body);
+
+    return format!("\"{}\"", URL_SAFE_NO_PAD.encode(&body_digest.as_ref()[..ETAG_DIGEST_LENGTH_IN_BYTES]));
+}
+
+// e.g. "\"a\", W/\"b\"" yields "\"a\"" and "W/\"b\""...
+fn parse_etags(headers: &HeaderMap, header_name: &str) -> Option<Vec<String>> {
+    if !headers.contains_key(header_name) {
+        return None;
+    }
+
+    let etags = headers.get_all(header_name)
+        .iter()
+        .map(|header_value| String::from_utf8_lossy(header_value.as_bytes()).into_owned())
+        .flat_map(|header_value| header_value.split(',').map(|etag| etag.trim().to_string()).collect::<Vec<String>>())
+        .filter(|etag| etag.len() > 0)
+        .collect();
+
+    return Some(etags);
+}
+
+fn parse_http_date(headers: &HeaderMap, header_name: &str) -> Option<DateTime<Utc>> {
+    let header_value = headers.get(header_name)?.to_str().ok()?;
+
+    return DateTime::parse_from_rfc2822(header_value).ok().map(|date_time| date_time.with_timezone(&Utc));
+}
+
+// weak comparison ignores the weakness indicator (e.g. W/"a" matches "a")...
+fn is_weak_match(etags: &[String], etag: &str) -> bool {
+    let etag = etag.trim_start_matches(WEAK_ETAG_PREFIX);
+
+    return etags.iter().any(|other_etag| other_etag == ANY_ETAG || other_etag.trim_start_matches(WEAK_ETAG_PREFIX) == etag);
+}
+
+// strong comparison never matches weak ETags...
+fn is_strong_match(etags: &[String], etag: &str) -> bool {
+    if etag.starts_with(WEAK_ETAG_PREFIX) {
+        return false;
+    }
+
+    return etags.iter().any(|other_etag| other_etag == ANY_ETAG || other_etag == etag);
+}
+
+fn get_etag(response: &Response<Body>) -> Option<String> {
+    return response.headers()
+        .get(ETAG_HEADER_NAME)
+        .and_then(|header_value| header_value.to_str().ok())
+        .map(String::from);
+}
+
+// the headers of the 200 response are kept (e.g. ETag, Cache-Control and Vary)
+// except the ones describing its body...
+fn create_not_modified_response(response: Response<Body>) -> Response<Body> {
+    let (mut parts, _) = response.into_parts();
+
+    parts.status = StatusCode::NOT_MODIFIED;
+
+    for header_name in CONTENT_HEADER_NAMES {
+        parts.headers.remove(header_name);
+    }
+
+    return Response::from_parts(parts, Body::empty());
+}
+
+// the response of the parent process is discarded, including its body...
+fn create_precondition_failed_response() -> Response<Body> {
+    return Response::builder()
+        .status(StatusCode::PRECONDITION_FAILED)
+        .body(Body::empty())
+        .unwrap();
+}
+
+fn create_error_response(status_code: StatusCode) -> Response<Body> {
+    return Response::builder()
+        .status(status_code)
+        .body(Body::from(status_code.canonical_reason().unwrap_or("")))
+        .unwrap();
+}
+
+impl ConditionalRequestHandler {
+    pub fn new(is_enabled: bool) -> Self {
+        return ConditionalRequestHandler { is_enabled };
+    }
+
+    pub fn create_request(&self, request: &Request<Body>) -> Option<ConditionalRequest> {
+        if !self.is_enabled {
+            return None;
+        }
+
+        let headers = request.headers();
+
+        return Some(ConditionalRequest {
+            is_safe: request.method() == Method::GET || request.method() == Method::HEAD,
+            if_match_option: parse_etags(headers, "if-match"),
+            if_none_match_option: parse_etags(headers, "if-none-match"),
+            if_modified_since_option: parse_http_date(headers, "if-modified-since"),
+            if_unmodified_since_option: parse_http_date(headers, "if-unmodified-since"),
+        });
+    }
+
+    async fn add_etag_async(&self, response: Response<Body>) -> Response<Body> {
+        let (mut parts, body) = response.into_parts();
+        // the body of a parent process response is already in memory...
+        let body_result = hyper::body::to_bytes(body).await;
+
+        if body_result.is_err() {
+            let error = body_result.unwrap_err();
+
+            warn!("An error occurred while reading response body to create ETag: {}", error);
+
+            return create_error_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+
+        let body = body_result.unwrap();
+
+        parts.headers.insert(ETAG_HEADER_NAME, HeaderValue::from_str(create_etag(&body).as_str()).unwrap());
+
+        return Response::from_parts(parts, Body::from(body));
+    }
+
+    // adds an ETag to responses of GET and HEAD requests (unless the parent process supplied
+    // one), answers with 304 if the client already has the representation and with 412 if
+    // the client expects a representation it does not get (or, for unsafe requests, if the
+    // ETag or Last-Modified of the parent process does not meet the preconditions)...
+    pub async fn handle_response_async(&self, conditional_request: &ConditionalRequest, response: Response<Body>) -> Response<Body> {
+        if !conditional_request.is_safe {
+            return self.handle_unsafe_response(conditional_request, response);
+        }
+
+        if response.status() != StatusCode::OK {
+            return response;
+        }
+
+        let response = if response.headers().contains_key(ETAG_HEADER_NAME) {
+            response
+        } else {
+            self.add_etag_async(response).await
+        };
+
+        // the body could not be read...
+        if response.status() != StatusCode::OK {
+            return response;
+        }
+
+        let etag_option = get_etag(&response);
+        let last_modified_option = parse_http_date(response.headers(), "last-modified");
+
+        if !is_precondition_met(conditional_request, etag_option.as_deref(), last_modified_option) {
+            return create_precondition_failed_response();
+        }
+
+        // If-None-Match takes precedence over If-Modified-Since...
+        if conditional_request.if_none_match_option.is_some() {
+            if etag_option.is_some() && is_weak_match(conditional_request.if_none_match_option.as_ref().unwrap(), etag_option.unwrap().as_str()) {
+                return create_not_modified_response(response);
+            }
+
+            return response;
+        }
+
+        if conditional_request.if_modified_since_option.is_some()
+            && last_modified_option.is_some_and(|last_modified| last_modified <= conditional_request.if_modified_since_option.unwrap()) {
+            return create_not_modified_response(response);
+        }
+
+        return response;
+    }
+
+    // the body of a response to an unsafe request (e.g. PUT) is not a representation of the
+    // resource, so only the ETag and Last-Modified supplied by the parent process are used...
+    fn handle_unsafe_response(&self, conditional_request: &ConditionalRequest, response: Response<Body>) -> Response<Body> {
+        if !response.status().is_success() {
+            return response;
+        }
+
+        let etag_option = get_etag(&response);
+        let last_modified_option = parse_http_date(response.headers(), "last-modified");
+
+        if !is_precondition_met(conditional_request, etag_option.as_deref(), last_modified_option) {
+            return create_precondition_failed_response();
+        }
+
+        return response;
+    }
+}
+
+// If-Match takes precedence over If-Unmodified-Since (RFC 9110 section 13.2.2)...
+fn is_precondition_met(conditional_request: &ConditionalRequest, etag_option: Option<&str>, last_modified_option: Option<DateTime<Utc>>) -> bool {
+    if conditional_request.if_match_option.is_some() {
+        return etag_option.is_some() && is_strong_match(conditional_request.if_match_option.as_ref().unwrap(), etag_option.unwrap());
+    }
+
+    if conditional_request.if_unmodified_since_option.is_some() {
+        return last_modified_option.is_none_or(|last_modified| last_modified <= conditional_request.if_unmodified_since_option.unwrap());
+    }
+
+    return true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAST_MODIFIED: &str = "Mon, 19 Oct 2026 10:00:00 GMT";
+
+    fn create_conditional_request(method: Method, headers: &[(&str, &str)]) -> Option<ConditionalRequest> {
+        let mut request_builder = Request::builder().method(method).uri("/a");
+
+        for (header_name, header_value) in headers {
+            request_builder = request_builder.header(*header_name, *header_value);
+        }
+
+        return ConditionalRequestHandler::new(true).create_request(&request_builder.body(Body::empty()).unwrap());
+    }
+
+    fn create_response(headers: &[(&str, &str)]) -> Response<Body> {
+        let mut response_builder = Response::builder().status(200).header("content-type", "text/plain");
+
+        for (header_name, header_value) in headers {
+            response_builder = response_builder.header(*header_name, *header_value);
+        }
+
+        return response_builder.body(Body::from("hello")).unwrap();
+    }
+
+    async fn get_status_code_async(request_headers: &[(&str, &str)], response_headers: &[(&str, &str)]) -> StatusCode {
+        let conditional_request = create_conditional_request(Method::GET, request_headers).unwrap();
+        let response = ConditionalRequestHandler::new(true)
+            .handle_response_async(&conditional_request, create_response(response_headers)).await;
+
+        return response.status();
+    }
+
+    #[test]
+    fn compares_etags() {
+        let etags = vec![String::from("\"a\""), String::from("W/\"b\"")];
+
+        assert!(is_weak_match(&etags, "W/\"a\""));
+        assert!(is_weak_match(&etags, "\"b\""));
+        assert!(is_strong_match(&etags, "\"a\""));
+        assert!(!is_strong_match(&etags, "\"b\""));
+        assert!(!is_strong_match(&etags, "W/\"a\""));
+        assert!(is_strong_match(&[String::from(ANY_ETAG)], "\"c\""));
+    }
+
+    #[test]
+    fn ignores_requests_if_disabled() {
+        assert!(ConditionalRequestHandler::new(false).create_request(&Request::new(Body::empty())).is_none());
+        assert!(create_conditional_request(Method::PUT, &[("if-match", "\"a\"")]).is_some());
+    }
+
+    async fn get_put_response_async(request_headers: &[(&str, &str)], response_headers: &[(&str, &str)]) -> Response<Body> {
+        let conditional_request = create_conditional_request(Method::PUT, request_headers).unwrap();
+
+        return ConditionalRequestHandler::new(true)
+            .handle_response_async(&conditional_request, create_response(response_headers)).await;
+    }
+
+    #[tokio::test]
+    async fn answers_put_with_matching_if_match() {
+        let response = get_put_response_async(&[("if-match", "\"v1\"")], &[("etag", "\"v1\"")]).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(hyper::body::to_bytes(response.into_body()).await.unwrap(), "hello");
+
+        let response = get_put_response_async(&[("if-match", "*")], &[("etag", "\"v1\"")]).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn answers_put_with_non_matching_if_match_with_precondition_failed() {
+        let response = get_put_response_async(&[("if-match", "\"v0\"")], &[("etag", "\"v1\"")]).await;
+
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(hyper::body::to_bytes(response.into_body()).await.unwrap().len(), 0);
+
+        // no ETag is derived from the body of a response to an unsafe request...
+        let response = get_put_response_async(&[("if-match", create_etag(b"hello").as_str())], &[]).await;
+
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+        let response = get_put_response_async(
+            &[("if-unmodified-since", "Sun, 18 Oct 2026 10:00:00 GMT")], &[("last-modified", LAST_MODIFIED)]).await;
+
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    }
+
+    #[tokio::test]
+    async fn adds_etag_derived_from_body() {
+        let conditional_request = create_conditional_request(Method::GET, &[]).unwrap();
+        let response = ConditionalRequestHandler::new(true).handle_response_async(&conditional_request, create_response(&[])).await;
+        let etag = get_etag(&response).unwrap();
+
+        assert_eq!(etag, create_etag(b"hello"));
+        assert_eq!(etag.len(), 24);
+        assert_eq!(hyper::body::to_bytes(response.into_body()).await.unwrap(), "hello");
+
+        // an ETag of the parent process is kept...
+        let response = ConditionalRequestHandler::new(true)
+            .handle_response_async(&conditional_request, create_response(&[("etag", "W/\"v1\"")])).await;
+
+        assert_eq!(get_etag(&response).unwrap(), "W/\"v1\"");
+    }
+
+    #[tokio::test]
+    async fn answers_not_modified() {
+        let etag = create_etag(b"hello");
+
+        assert_eq!(get_status_code_async(&[("if-none-match", etag.as_str())], &[]).await, StatusCode::NOT_MODIFIED);
+        assert_eq!(get_status_code_async(&[("if-none-match", "\"other\", W/\"v1\"")], &[("etag", "\"v1\"")]).await, StatusCode::NOT_MODIFIED);
+        assert_eq!(get_status_code_async(&[("if-none-match", "\"other\"")], &[]).await, StatusCode::OK);
+        assert_eq!(get_status_code_async(&[("if-modified-since", LAST_MODIFIED)], &[("last-modified", LAST_MODIFIED)]).await,
+            StatusCode::NOT_MODIFIED);
+        assert_eq!(get_status_code_async(&[("if-modified-since", "Sun, 18 Oct 2026 10:00:00 GMT")], &[("last-modified", LAST_MODIFIED)]).await,
+            StatusCode::OK);
+        // If-None-Match takes precedence over If-Modified-Since...
+        assert_eq!(get_status_code_async(
+            &[("if-none-match", "\"other\""), ("if-modified-since", LAST_MODIFIED)], &[("last-modified", LAST_MODIFIED)]).await,
+            StatusCode::OK);
+
+        let conditional_request = create_conditional_request(Method::HEAD, &[("if-none-match", "\"v1\"")]).unwrap();
+        let response = ConditionalRequestHandler::new(true)
+            .handle_response_async(&conditional_request, create_response(&[("etag", "\"v1\""), ("cache-control", "max-age=60")])).await;
+
+        // the headers describing the body are removed...
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert!(!response.headers().contains_key("content-type"));
+        assert!(response.headers().contains_key("cache-control"));
+    }
+
+    #[tokio::test]
+    async fn answers_precondition_failed() {
+        let etag = create_etag(b"hello");
+
+        assert_eq!(get_status_code_async(&[("if-match", etag.as_str())], &[]).await, StatusCode::OK);
+        assert_eq!(get_status_code_async(&[("if-match", "*")], &[]).await, StatusCode::OK);
+        assert_eq!(get_status_code_async(&[("if-match", "\"other\"")], &[]).await, StatusCode::PRECONDITION_FAILED);
+        // weak ETags never match strongly...
+        assert_eq!(get_status_code_async(&[("if-match", "W/\"v1\"")], &[("etag", "W/\"v1\"")]).await, StatusCode::PRECONDITION_FAILED);
+        assert_eq!(get_status_code_async(&[("if-unmodified-since", "Sun, 18 Oct 2026 10:00:00 GMT")], &[("last-modified", LAST_MODIFIED)]).await,
+            StatusCode::PRECONDITION_FAILED);
+        assert_eq!(get_status_code_async(&[("if-unmodified-since", LAST_MODIFIED)], &[("last-modified", LAST_MODIFIED)]).await,
+            StatusCode::OK);
+        // If-Match takes precedence over If-Unmodified-Since...
+        assert_eq!(get_status_code_async(
+            &[("if-match", etag.as_str()), ("if-unmodified-since", "Sun, 18 Oct 2026 10:00:00 GMT")], &[("last-modified", LAST_MODIFIED)]).await,
+            StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn fails_if_body_cannot_be_read() {
+        let conditional_request = create_conditional_request(Method::GET, &[]).unwrap();
+        let chunks: Vec<Result<&str, std::io::Error>> = vec![Ok("hel"), Err(std::io::Error::other("broken"))];
+        let response = Response::new(Body::wrap_stream(futures::stream::iter(chunks)));
+        let response = ConditionalRequestHandler::new(true).handle_response_async(&conditional_request, response).await;
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
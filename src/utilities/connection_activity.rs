@@ -0,0 +1,168 @@
+#![allow(clippy::needless_return, clippy::assign_op_pattern)]
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    time::{sleep_until, Duration, Instant, Sleep},
+};
+
+struct ActivityState {
+    active_request_count: usize,
+    last_active_at: Instant,
+    // upgraded connections (e.g. web socket) are long-lived and never idle...
+    is_upgraded: bool,
+}
+
+// tracks whether a connection is busy. a connection is idle when
+// no request is being handled and nothing has been read or written for a while...
+#[derive(Clone)]
+pub struct ConnectionActivity {
+    state_arc: Arc<Mutex<ActivityState>>,
+}
+
+// marks the connection as busy until dropped...
+pub struct RequestActivityGuard {
+    connection_activity: ConnectionActivity,
+}
+
+impl ConnectionActivity {
+    pub fn new() -> Self {
+        let state = ActivityState {
+            active_request_count: 0,
+            last_active_at: Instant::now(),
+            is_upgraded: false,
+        };
+
+        return ConnectionActivity { state_arc: Arc::new(Mutex::new(state)) };
+    }
+
+    pub fn begin_request(&self) -> RequestActivityGuard {
+        let mut state = self.state_arc.lock().unwrap();
+
+        state.active_request_count = state.active_request_count + 1;
+
+        return RequestActivityGuard { connection_activity: self.clone() };
+    }
+
+    fn end_request(&self) {
+        let mut state = self.state_arc.lock().unwrap();
+
+        state.active_request_count = state.active_request_count.saturating_sub(1);
+        state.last_active_at = Instant::now();
+    }
+
+    pub fn mark_upgraded(&self) {
+        let mut state = self.state_arc.lock().unwrap();
+
+        state.is_upgraded = true;
+    }
+
+    fn record_activity(&self) {
+        let mut state = self.state_arc.lock().unwrap();
+
+        state.last_active_at = Instant::now();
+    }
+
+    fn get_idle_deadline(&self, idle_timeout: Duration) -> Option<Instant> {
+        let state = self.state_arc.lock().unwrap();
+
+        // a connection with a request in progress or an upgraded connection is never idle...
+        if state.active_request_count > 0 || state.is_upgraded {
+            return None;
+        }
+
+        return Some(state.last_active_at + idle_timeout);
+    }
+}
+
+impl Drop for RequestActivityGuard {
+    fn drop(&mut self) {
+        self.connection_activity.end_request();
+    }
+}
+
+// ends the stream (as if the client closed it) once the connection has been idle
+// for too long, so that the HTTP server closes the connection gracefully...
+pub struct IdleTimeoutStream<S> {
+    inner: S,
+    connection_activity: ConnectionActivity,
+    // none means the connection never times out...
+    idle_timeout_option: Option<Duration>,
+    sleep: Pin<Box<Sleep>>,
+}
+
+impl<S> IdleTimeoutStream<S> {
+    pub fn new(inner: S, connection_activity: ConnectionActivity, idle_timeout_option: Option<Duration>) -> Self {
+        return IdleTimeoutStream {
+            inner,
+            connection_activity,
+            idle_timeout_option,
+            sleep: Box::pin(sleep_until(Instant::now())),
+        };
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for IdleTimeoutStream<S> {
+    fn poll_read(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled_length = buffer.filled().len();
+        let poll = Pin::new(&mut this.inner).poll_read(context, buffer);
+
+        // anything the client sends keeps the connection busy...
+        if let Poll::Ready(Ok(())) = poll {
+            if buffer.filled().len() > filled_length {
+                this.connection_activity.record_activity();
+            }
+        }
+
+        if poll.is_ready() || this.idle_timeout_option.is_none() {
+            return poll;
+        }
+
+        let idle_deadline_option = this.connection_activity.get_idle_deadline(this.idle_timeout_option.unwrap());
+
+        // while a request is in progress, we shall simply wait for the client...
+        if idle_deadline_option.is_none() {
+            return Poll::Pending;
+        }
+
+        this.sleep.as_mut().reset(idle_deadline_option.unwrap());
+
+        // once the deadline passes, we shall report end of stream...
+        if this.sleep.as_mut().poll(context).is_ready() {
+            return Poll::Ready(Ok(()));
+        }
+
+        return Poll::Pending;
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for IdleTimeoutStream<S> {
+    fn poll_write(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(context, buffer);
+
+        // responses that are still being written keep the connection busy...
+        if let Poll::Ready(Ok(bytes_written)) = poll {
+            if bytes_written > 0 {
+                this.connection_activity.record_activity();
+            }
+        }
+
+        return poll;
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_flush(context);
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_shutdown(context);
+    }
+}
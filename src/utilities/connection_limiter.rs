@@ -0,0 +1,304 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::assign_op_pattern)]
+
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    io,
+    net::IpAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::Notify,
+    time::{timeout_at, Duration, Instant},
+};
+
+use crate::remote_address::RemoteAddress;
+
+struct ConnectionCounts {
+    active_connection_count: usize,
+    queued_connection_count: usize,
+    rejected_connection_count: u64,
+    connection_counts_per_ip_address: HashMap<IpAddr, usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStatistics {
+    pub active_connection_count: usize,
+    pub maximum_connection_count: usize,
+    pub queued_connection_count: usize,
+    pub rejected_connection_count: u64,
+    pub client_count: usize,
+    pub maximum_connection_count_per_ip_address: usize,
+}
+
+#[derive(Clone)]
+pub struct ConnectionLimiter {
+    counts_arc: Arc<Mutex<ConnectionCounts>>,
+    // woken up whenever a connection is closed so that queued connections may proceed...
+    released_arc: Arc<Notify>,
+    // zero means unlimited...
+    maximum_connection_count: usize,
+    maximum_connection_count_per_ip_address: usize,
+    queue_timeout: Duration,
+}
+
+// releases the slot of the connection when dropped...
+pub struct ConnectionPermit {
+    connection_limiter: ConnectionLimiter,
+    ip_address_option: Option<IpAddr>,
+}
+
+fn get_ip_address(remote_address: &RemoteAddress) -> Option<IpAddr> {
+    return match remote_address {
+        RemoteAddress::Ip(socket_address) => Some(socket_address.ip()),
+        RemoteAddress::Unix(_) => None,
+    };
+}
+
+impl ConnectionLimiter {
+    pub fn new(
+        maximum_connection_count: usize,
+        maximum_connection_count_per_ip_address: usize,
+        queue_timeout_in_milliseconds: u64,
+    ) -> Self {
+        let counts = ConnectionCounts {
+            active_connection_count: 0,
+            queued_connection_count: 0,
+            rejected_connection_count: 0,
+            connection_counts_per_ip_address: HashMap::new(),
+        };
+
+        return ConnectionLimiter {
+            counts_arc: Arc::new(Mutex::new(counts)),
+            released_arc: Arc::new(Notify::new()),
+            maximum_connection_count,
+            maximum_connection_count_per_ip_address,
+            queue_timeout: Duration::from_millis(queue_timeout_in_milliseconds),
+        };
+    }
+
+    fn try_acquire_for_ip_address(&self, counts: &mut ConnectionCounts, ip_address_option: Option<IpAddr>) -> bool {
+        // peers without an IP address (e.g. Unix domain socket) are only subject to the global limit...
+        if ip_address_option.is_none() {
+            return true;
+        }
+
+        let ip_address = ip_address_option.unwrap();
+        let connection_count = counts.connection_counts_per_ip_address.get(&ip_address).copied().unwrap_or(0);
+
+        if self.maximum_connection_count_per_ip_address > 0
+            && connection_count >= self.maximum_connection_count_per_ip_address {
+            return false;
+        }
+
+        counts.connection_counts_per_ip_address.insert(ip_address, connection_count + 1);
+
+        return true;
+    }
+
+    fn try_acquire(&self, ip_address_option: Option<IpAddr>) -> bool {
+        let mut counts = self.counts_arc.lock().unwrap();
+
+        if self.maximum_connection_count > 0 && counts.active_connection_count >= self.maximum_connection_count {
+            return false;
+        }
+
+        if !self.try_acquire_for_ip_address(&mut counts, ip_address_option) {
+            return false;
+        }
+
+        counts.active_connection_count = counts.active_connection_count + 1;
+
+        return true;
+    }
+
+    fn try_assign(&self, ip_address_option: Option<IpAddr>) -> bool {
+        let mut counts = self.counts_arc.lock().unwrap();
+
+        return self.try_acquire_for_ip_address(&mut counts, ip_address_option);
+    }
+
+    fn release(&self, ip_address_option: Option<IpAddr>) {
+        let mut counts = self.counts_arc.lock().unwrap();
+
+        counts.active_connection_count = counts.active_connection_count.saturating_sub(1);
+
+        if ip_address_option.is_some() {
+            let ip_address = ip_address_option.unwrap();
+            let connection_count = counts.connection_counts_per_ip_address.get(&ip_address).copied().unwrap_or(0);
+
+            // we shall not keep entries of clients that have no connection left...
+            if connection_count <= 1 {
+                counts.connection_counts_per_ip_address.remove(&ip_address);
+            } else {
+                counts.connection_counts_per_ip_address.insert(ip_address, connection_count - 1);
+            }
+        }
+
+        drop(counts);
+
+        self.released_arc.notify_waiters();
+    }
+
+    fn update_queued_connection_count(&self, is_queued: bool) {
+        let mut counts = self.counts_arc.lock().unwrap();
+
+        if is_queued {
+            counts.queued_connection_count = counts.queued_connection_count + 1;
+        } else {
+            counts.queued_connection_count = counts.queued_connection_count.saturating_sub(1);
+        }
+    }
+
+    fn reject(&self) {
+        let mut counts = self.counts_arc.lock().unwrap();
+
+        counts.rejected_connection_count = counts.rejected_connection_count + 1;
+    }
+
+    // waits (at most for the queue timeout) until the given attempt succeeds...
+    async fn wait_async(&self, try_acquire: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + self.queue_timeout;
+        let mut is_queued = false;
+
+        loop {
+            // we must register for the notification before checking
+            // so that a release in between is not missed...
+            let released = self.released_arc.notified();
+
+            tokio::pin!(released);
+            released.as_mut().enable();
+
+            if try_acquire() {
+                break;
+            }
+
+            if !is_queued {
+                is_queued = true;
+
+                self.update_queued_connection_count(true);
+            }
+
+            // if the connection could not get a slot in time, it shall be closed...
+            if timeout_at(deadline, released).await.is_err() {
+                self.update_queued_connection_count(false);
+                self.reject();
+
+                return false;
+            }
+        }
+
+        if is_queued {
+            self.update_queued_connection_count(false);
+        }
+
+        return true;
+    }
+
+    pub async fn acquire_async(&self, remote_address: &RemoteAddress) -> Option<ConnectionPermit> {
+        let ip_address_option = get_ip_address(remote_address);
+
+        if !self.wait_async(|| self.try_acquire(ip_address_option)).await {
+            return None;
+        }
+
+        return Some(ConnectionPermit {
+            connection_limiter: self.clone(),
+            ip_address_option,
+        });
+    }
+
+    // takes a slot of the global limit only, e.g. while the PROXY protocol header that
+    // tells who the client is has yet to arrive. see ConnectionPermit::assign_async()...
+    pub async fn acquire_for_unknown_client_async(&self) -> Option<ConnectionPermit> {
+        if !self.wait_async(|| self.try_acquire(None)).await {
+            return None;
+        }
+
+        return Some(ConnectionPermit {
+            connection_limiter: self.clone(),
+            ip_address_option: None,
+        });
+    }
+
+    pub fn get_statistics(&self) -> ConnectionStatistics {
+        let counts = self.counts_arc.lock().unwrap();
+
+        return ConnectionStatistics {
+            active_connection_count: counts.active_connection_count,
+            maximum_connection_count: self.maximum_connection_count,
+            queued_connection_count: counts.queued_connection_count,
+            rejected_connection_count: counts.rejected_connection_count,
+            client_count: counts.connection_counts_per_ip_address.len(),
+            maximum_connection_count_per_ip_address: self.maximum_connection_count_per_ip_address,
+        };
+    }
+}
+
+impl ConnectionPermit {
+    // charges a permit of an unknown client to the client once it is known. false if
+    // the client has reached its own limit (the permit shall be dropped then)...
+    pub async fn assign_async(&mut self, remote_address: &RemoteAddress) -> bool {
+        let ip_address_option = get_ip_address(remote_address);
+
+        if ip_address_option.is_none() || self.ip_address_option.is_some() {
+            return true;
+        }
+
+        let connection_limiter = self.connection_limiter.clone();
+
+        if !connection_limiter.wait_async(|| connection_limiter.try_assign(ip_address_option)).await {
+            return false;
+        }
+
+        self.ip_address_option = ip_address_option;
+
+        return true;
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.connection_limiter.release(self.ip_address_option);
+    }
+}
+
+// keeps the permit as long as the stream is alive. this way upgraded
+// connections (e.g. WebSocket) remain subject to the connection limits...
+pub struct PermittedStream<S> {
+    inner: S,
+    _connection_permit: ConnectionPermit,
+}
+
+impl<S> PermittedStream<S> {
+    pub fn new(inner: S, connection_permit: ConnectionPermit) -> Self {
+        return PermittedStream {
+            inner,
+            _connection_permit: connection_permit,
+        };
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for PermittedStream<S> {
+    fn poll_read(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_read(context, buffer);
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for PermittedStream<S> {
+    fn poll_write(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &[u8]) -> Poll<io::Result<usize>> {
+        return Pin::new(&mut self.get_mut().inner).poll_write(context, buffer);
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_flush(context);
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_shutdown(context);
+    }
+}
@@ -0,0 +1,450 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use std::{collections::HashMap, fs};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::DateTime;
+use ring::{aead, hmac, rand::{SecureRandom, SystemRandom}};
+use serde::{Deserialize, Serialize};
+use urlencoding::{decode, encode};
+use log::{debug, warn};
+
+// values of signed cookies look like "s:<value>.<signature>" and values of
+// encrypted cookies like "e:<nonce-and-ciphertext>". as plain values are
+// percent-encoded, a plain value never starts with either prefix...
+const SIGNED_VALUE_PREFIX: &str = "s:";
+const ENCRYPTED_VALUE_PREFIX: &str = "e:";
+const SIGNATURE_SEPARATOR: char = '.';
+const COOKIE_SEPARATOR: char = ';';
+const SIGNING_KEY_LABEL: &[u8] = b"volcano-http-server cookie signing";
+const ENCRYPTION_KEY_LABEL: &[u8] = b"volcano-http-server cookie encryption";
+const NONCE_LENGTH: usize = 12;
+// e.g. "Mon, 19 Oct 2026 10:00:00 GMT"...
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+// a cookie the parent process wants to set...
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializableCookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    // RFC 3339 date-time (e.g. what JavaScript's Date.toJSON() returns)...
+    #[serde(default)]
+    pub expires: Option<String>,
+    #[serde(default)]
+    pub max_age: Option<i64>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    // "Strict", "Lax" or "None"...
+    #[serde(default)]
+    pub same_site: Option<String>,
+    // the value is signed, so that it cannot be tampered with...
+    #[serde(default)]
+    pub signed: bool,
+    // the value is encrypted (and thereby authenticated), so that it cannot be read either...
+    #[serde(default)]
+    pub encrypted: bool,
+}
+
+// signs and encrypts cookie values with keys derived from the server-held secret...
+#[derive(Clone)]
+pub struct CookieSealer {
+    // none means no secret is configured...
+    signing_key_option: Option<hmac::Key>,
+    encryption_key_bytes_option: Option<Vec<u8>>,
+}
+
+fn derive_key(secret: &[u8], label: &[u8]) -> Vec<u8> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+
+    return hmac::sign(&key, label).as_ref().to_vec();
+}
+
+pub fn load_secret(secret_path: &str) -> Option<Vec<u8>> {
+    if secret_path.len() == 0 {
+        return None;
+    }
+
+    let secret_result = fs::read(secret_path);
+
+    if secret_result.is_err() {
+        let error = secret_result.unwrap_err();
+
+        eprintln!("An error occurred while reading cookie secret from '{}': {}", secret_path, error);
+
+        return None;
+    }
+
+    let secret = secret_result.unwrap();
+    // a trailing line break is most likely not meant to be part of the secret...
+    let secret = secret.trim_ascii().to_vec();
+
+    if secret.len() < 32 {
+        eprintln!("The cookie secret in '{}' is too short. At least 32 bytes are required.", secret_path);
+
+        return None;
+    }
+
+    return Some(secret);
+}
+
+// a token as per RFC 7230, i.e. no separators, spaces or control characters...
+fn is_valid_name(name: &str) -> bool {
+    return name.len() > 0
+        && name.chars().all(|character| character.is_ascii_graphic() && !"()<>@,;:\\\"/[]?={}".contains(character));
+}
+
+fn is_valid_attribute_value(value: &str) -> bool {
+    return value.chars().all(|character| (character.is_ascii_graphic() || character == ' ') && character != COOKIE_SEPARATOR);
+}
+
+fn format_expires(expires: &str) -> Option<String> {
+    let expires_result = DateTime::parse_from_rfc3339(expires);
+
+    if expires_result.is_err() {
+        return None;
+    }
+
+    return Some(expires_result.unwrap().naive_utc().format(HTTP_DATE_FORMAT).to_string());
+}
+
+fn decode_value(value: &str) -> String {
+    // browsers send back whatever they received, so a value that is not
+    // percent-encoded by us is passed on unchanged...
+    return decode(value).map_or(value.to_string(), |decoded_value| decoded_value.into_owned());
+}
+
+// parses the cookie header(s) into plain cookies and signed (or encrypted) cookies
+// whose values could be verified. cookies that fail verification are dropped...
+pub fn parse(cookie_headers: &[String], cookie_sealer: &CookieSealer) -> (HashMap<String, String>, HashMap<String, String>) {
+    let mut cookies: HashMap<String, String> = HashMap::new();
+    let mut signed_cookies: HashMap<String, String> = HashMap::new();
+
+    for cookie_header in cookie_headers {
+        for cookie in cookie_header.split(COOKIE_SEPARATOR) {
+            let index_of_equal_sign_option = cookie.find('=');
+
+            if index_of_equal_sign_option.is_none() {
+                continue;
+            }
+
+            let index_of_equal_sign = index_of_equal_sign_option.unwrap();
+            let name = cookie[..index_of_equal_sign].trim();
+            let mut value = cookie[index_of_equal_sign + 1..].trim();
+
+            // values may be enclosed in double quotes...
+            if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                value = &value[1..value.len() - 1];
+            }
+
+            if name.len() == 0 {
+                continue;
+            }
+
+            // browsers send the most specific cookie first, so the first one wins...
+            if cookies.contains_key(name) || signed_cookies.contains_key(name) {
+                continue;
+            }
+
+            if !cookie_sealer.is_enabled()
+                || !(value.starts_with(SIGNED_VALUE_PREFIX) || value.starts_with(ENCRYPTED_VALUE_PREFIX)) {
+                cookies.insert(name.to_string(), decode_value(value));
+
+                continue;
+            }
+
+            let unsealed_value_option = cookie_sealer.unseal(name, value);
+
+            if unsealed_value_option.is_none() {
+                debug!("Dropping cookie '{}' as its value could not be verified.", name);
+
+                continue;
+            }
+
+            signed_cookies.insert(name.to_string(), decode_value(unsealed_value_option.unwrap().as_str()));
+        }
+    }
+
+    return (cookies, signed_cookies);
+}
+
+impl CookieSealer {
+    pub fn new(secret_option: Option<Vec<u8>>) -> Self {
+        if secret_option.is_none() {
+            return CookieSealer {
+                signing_key_option: None,
+                encryption_key_bytes_option: None,
+            };
+        }
+
+        let secret = secret_option.unwrap();
+
+        return CookieSealer {
+            signing_key_option: Some(hmac::Key::new(hmac::HMAC_SHA256, &derive_key(&secret, SIGNING_KEY_LABEL))),
+            encryption_key_bytes_option: Some(derive_key(&secret, ENCRYPTION_KEY_LABEL)),
+        };
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        return self.signing_key_option.is_some();
+    }
+
+    fn create_encryption_key(&self) -> aead::LessSafeKey {
+        let key_bytes = self.encryption_key_bytes_option.as_ref().unwrap();
+        let unbound_key = aead::UnboundKey::new(&aead::AES_256_GCM, key_bytes).unwrap();
+
+        return aead::LessSafeKey::new(unbound_key);
+    }
+
+    // the name is part of what is signed, so that values cannot be swapped between cookies...
+    fn sign(&self, name: &str, value: &str) -> String {
+        let signing_key = self.signing_key_option.as_ref().unwrap();
+        let signature = hmac::sign(signing_key, format!("{}={}", name, value).as_bytes());
+
+        return format!("{}{}{}{}", SIGNED_VALUE_PREFIX, value, SIGNATURE_SEPARATOR, URL_SAFE_NO_PAD.encode(signature.as_ref()));
+    }
+
+    fn encrypt(&self, name: &str, value: &str) -> Option<String> {
+        let mut nonce_bytes = [0u8; NONCE_LENGTH];
+
+        if SystemRandom::new().fill(&mut nonce_bytes).is_err() {
+            return None;
+        }
+
+        let mut sealed_value = value.as_bytes().to_vec();
+        let seal_result = self.create_encryption_key().seal_in_place_append_tag(
+            aead::Nonce::assume_unique_for_key(nonce_bytes),
+            aead::Aad::from(name.as_bytes()),
+            &mut sealed_value,
+        );
+
+        if seal_result.is_err() {
+            return None;
+        }
+
+        let mut nonce_and_sealed_value = nonce_bytes.to_vec();
+
+        nonce_and_sealed_value.extend_from_slice(&sealed_value);
+
+        return Some(format!("{}{}", ENCRYPTED_VALUE_PREFIX, URL_SAFE_NO_PAD.encode(nonce_and_sealed_value)));
+    }
+
+    fn unseal(&self, name: &str, value: &str) -> Option<String> {
+        if let Some(signed_value) = value.strip_prefix(SIGNED_VALUE_PREFIX) {
+            let index_of_separator = signed_value.rfind(SIGNATURE_SEPARATOR)?;
+            let unsigned_value = &signed_value[..index_of_separator];
+            let signature = URL_SAFE_NO_PAD.decode(&signed_value[index_of_separator + 1..]).ok()?;
+            let signing_key = self.signing_key_option.as_ref().unwrap();
+
+            // the comparison takes constant time...
+            hmac::verify(signing_key, format!("{}={}", name, unsigned_value).as_bytes(), &signature).ok()?;
+
+            return Some(unsigned_value.to_string());
+        }
+
+        let nonce_and_sealed_value = URL_SAFE_NO_PAD.decode(&value[ENCRYPTED_VALUE_PREFIX.len()..]).ok()?;
+
+        if nonce_and_sealed_value.len() < NONCE_LENGTH {
+            return None;
+        }
+
+        let nonce = aead::Nonce::try_assume_unique_for_key(&nonce_and_sealed_value[..NONCE_LENGTH]).ok()?;
+        let mut sealed_value = nonce_and_sealed_value[NONCE_LENGTH..].to_vec();
+        let opened_value = self.create_encryption_key()
+            .open_in_place(nonce, aead::Aad::from(name.as_bytes()), &mut sealed_value)
+            .ok()?;
+
+        return String::from_utf8(opened_value.to_vec()).ok();
+    }
+
+    // renders the cookie as the value of a Set-Cookie header. none means the cookie is invalid...
+    pub fn to_set_cookie(&self, cookie: &SerializableCookie) -> Option<String> {
+        if !is_valid_name(cookie.name.as_str()) {
+            warn!("Invalid cookie name '{}'. The cookie is not set.", cookie.name);
+
+            return None;
+        }
+
+        // the value is percent-encoded so that it only consists of allowed characters...
+        let encoded_value = encode(cookie.value.as_str()).into_owned();
+        let value = if cookie.encrypted || cookie.signed {
+            // we shall never fall back to an unprotected value...
+            if !self.is_enabled() {
+                warn!("Cookie '{}' shall be signed or encrypted, but no cookie secret is configured. The cookie is not set.", cookie.name);
+
+                return None;
+            }
+
+            if cookie.encrypted {
+                self.encrypt(cookie.name.as_str(), encoded_value.as_str())?
+            } else {
+                self.sign(cookie.name.as_str(), encoded_value.as_str())
+            }
+        } else {
+            encoded_value
+        };
+        let mut set_cookie = format!("{}={}", cookie.name, value);
+
+        if cookie.domain.is_some() && is_valid_attribute_value(cookie.domain.as_ref().unwrap()) {
+            set_cookie.push_str(format!("; Domain={}", cookie.domain.as_ref().unwrap()).as_str());
+        }
+
+        if cookie.path.is_some() && is_valid_attribute_value(cookie.path.as_ref().unwrap()) {
+            set_cookie.push_str(format!("; Path={}", cookie.path.as_ref().unwrap()).as_str());
+        }
+
+        if cookie.expires.is_some() {
+            let expires_option = format_expires(cookie.expires.as_ref().unwrap());
+
+            if expires_option.is_none() {
+                warn!("Invalid expiry date '{}' of cookie '{}'. Expected RFC 3339.", cookie.expires.as_ref().unwrap(), cookie.name);
+            } else {
+                set_cookie.push_str(format!("; Expires={}", expires_option.unwrap()).as_str());
+            }
+        }
+
+        if cookie.max_age.is_some() {
+            set_cookie.push_str(format!("; Max-Age={}", cookie.max_age.unwrap()).as_str());
+        }
+
+        let same_site_option = cookie.same_site.as_ref().and_then(|same_site| match same_site.to_lowercase().as_str() {
+            "strict" => Some("Strict"),
+            "lax" => Some("Lax"),
+            "none" => Some("None"),
+            _ => None,
+        });
+
+        // browsers reject cookies with SameSite=None that are not secure...
+        if cookie.secure || same_site_option == Some("None") {
+            set_cookie.push_str("; Secure");
+        }
+
+        if cookie.http_only {
+            set_cookie.push_str("; HttpOnly");
+        }
+
+        if same_site_option.is_some() {
+            set_cookie.push_str(format!("; SameSite={}", same_site_option.unwrap()).as_str());
+        } else if cookie.same_site.is_some() {
+            warn!("Invalid SameSite value '{}' of cookie '{}'. Expected Strict, Lax or None.", cookie.same_site.as_ref().unwrap(), cookie.name);
+        }
+
+        return Some(set_cookie);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_cookie_sealer() -> CookieSealer {
+        return CookieSealer::new(Some(b"0123456789abcdef0123456789abcdef".to_vec()));
+    }
+
+    fn create_cookie(json: &str) -> SerializableCookie {
+        return serde_json::from_str(json).unwrap();
+    }
+
+    #[test]
+    fn parses_cookie_headers() {
+        let cookie_headers = vec![
+            String::from("session=abc; theme=\"dark\"; empty=; =nameless; invalid"),
+            String::from("session=shadowed; greeting=hello%20world; raw=100%"),
+        ];
+        let (cookies, signed_cookies) = parse(&cookie_headers, &CookieSealer::new(None));
+
+        assert_eq!(cookies.len(), 5);
+        assert_eq!(cookies["session"], "abc");
+        assert_eq!(cookies["theme"], "dark");
+        assert_eq!(cookies["empty"], "");
+        assert_eq!(cookies["greeting"], "hello world");
+        assert_eq!(cookies["raw"], "100%");
+        assert!(signed_cookies.is_empty());
+    }
+
+    #[test]
+    fn round_trips_signed_and_encrypted_cookies() {
+        let cookie_sealer = create_cookie_sealer();
+        let signed_cookie = cookie_sealer.to_set_cookie(
+            &create_cookie(r#"{ "name": "user", "value": "alice; admin", "signed": true }"#)).unwrap();
+        let encrypted_cookie = cookie_sealer.to_set_cookie(
+            &create_cookie(r#"{ "name": "token", "value": "secret", "encrypted": true }"#)).unwrap();
+
+        assert!(signed_cookie.starts_with("user=s:alice%3B%20admin."));
+        assert!(encrypted_cookie.starts_with("token=e:"));
+        assert!(!encrypted_cookie.contains("secret"));
+
+        let cookie_headers = vec![format!("{}; {}", signed_cookie, encrypted_cookie)];
+        let (cookies, signed_cookies) = parse(&cookie_headers, &cookie_sealer);
+
+        assert!(cookies.is_empty());
+        assert_eq!(signed_cookies["user"], "alice; admin");
+        assert_eq!(signed_cookies["token"], "secret");
+    }
+
+    #[test]
+    fn drops_tampered_or_swapped_cookies() {
+        let cookie_sealer = create_cookie_sealer();
+        let signed_cookie = cookie_sealer.to_set_cookie(
+            &create_cookie(r#"{ "name": "user", "value": "alice", "signed": true }"#)).unwrap();
+        let encrypted_cookie = cookie_sealer.to_set_cookie(
+            &create_cookie(r#"{ "name": "token", "value": "secret", "encrypted": true }"#)).unwrap();
+        let cookie_headers = vec![
+            signed_cookie.replace("alice", "mallory"),
+            // a value sealed for another cookie shall not be accepted...
+            signed_cookie.replace("user=", "role="),
+            encrypted_cookie.replace("token=", "other="),
+        ];
+        let (cookies, signed_cookies) = parse(&cookie_headers, &cookie_sealer);
+
+        assert!(cookies.is_empty());
+        assert!(signed_cookies.is_empty());
+
+        // signatures made with another secret are not accepted either...
+        let other_cookie_sealer = CookieSealer::new(Some(b"fedcba9876543210fedcba9876543210".to_vec()));
+        let (_, signed_cookies) = parse(&[signed_cookie], &other_cookie_sealer);
+
+        assert!(signed_cookies.is_empty());
+    }
+
+    #[test]
+    fn renders_set_cookie_attributes() {
+        let cookie_sealer = CookieSealer::new(None);
+        let set_cookie = cookie_sealer.to_set_cookie(&create_cookie(r#"{
+            "name": "id",
+            "value": "a b",
+            "domain": "example.com",
+            "path": "/",
+            "expires": "2026-10-19T12:00:00+02:00",
+            "maxAge": 3600,
+            "httpOnly": true,
+            "sameSite": "none"
+        }"#)).unwrap();
+
+        assert_eq!(
+            set_cookie,
+            "id=a%20b; Domain=example.com; Path=/; Expires=Mon, 19 Oct 2026 10:00:00 GMT; Max-Age=3600; Secure; HttpOnly; SameSite=None",
+        );
+
+        // invalid attributes are left out...
+        let set_cookie = cookie_sealer.to_set_cookie(&create_cookie(
+            r#"{ "name": "id", "value": "1", "path": "/; Secure", "expires": "tomorrow", "sameSite": "sometimes" }"#)).unwrap();
+
+        assert_eq!(set_cookie, "id=1");
+    }
+
+    #[test]
+    fn refuses_invalid_or_unprotected_cookies() {
+        let cookie_sealer = CookieSealer::new(None);
+
+        assert!(cookie_sealer.to_set_cookie(&create_cookie(r#"{ "name": "a b", "value": "1" }"#)).is_none());
+        assert!(cookie_sealer.to_set_cookie(&create_cookie(r#"{ "name": "", "value": "1" }"#)).is_none());
+        // without a secret, a signed cookie shall not be set in plain...
+        assert!(cookie_sealer.to_set_cookie(&create_cookie(r#"{ "name": "a", "value": "1", "signed": true }"#)).is_none());
+    }
+}
@@ -0,0 +1,390 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use hyper::{http::HeaderValue, Body, Method, Request, Response, StatusCode};
+
+const ORIGIN_HEADER_NAME: &str = "origin";
+const VARY_HEADER_NAME: &str = "vary";
+const ACCESS_CONTROL_REQUEST_METHOD_HEADER_NAME: &str = "access-control-request-method";
+const ACCESS_CONTROL_REQUEST_HEADERS_HEADER_NAME: &str = "access-control-request-headers";
+const ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME: &str = "access-control-allow-origin";
+const ACCESS_CONTROL_ALLOW_CREDENTIALS_HEADER_NAME: &str = "access-control-allow-credentials";
+const ACCESS_CONTROL_ALLOW_METHODS_HEADER_NAME: &str = "access-control-allow-methods";
+const ACCESS_CONTROL_ALLOW_HEADERS_HEADER_NAME: &str = "access-control-allow-headers";
+const ACCESS_CONTROL_EXPOSE_HEADERS_HEADER_NAME: &str = "access-control-expose-headers";
+const ACCESS_CONTROL_MAX_AGE_HEADER_NAME: &str = "access-control-max-age";
+const WILDCARD: &str = "*";
+
+#[derive(Clone, Debug)]
+pub struct CorsPolicy {
+    // exact origins (e.g. "https://example.com"), patterns (e.g. "https://*.example.com")
+    // or "*". empty means CORS is not handled by the server...
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    // empty means the headers requested by the browser are allowed...
+    pub allowed_headers: Vec<String>,
+    pub exposed_headers: Vec<String>,
+    pub are_credentials_allowed: bool,
+    // zero means the browser decides how long preflight results are cached...
+    pub max_age_in_seconds: u64,
+}
+
+fn parse_list(list: &str) -> Vec<String> {
+    return list.split(',')
+        .map(|item| item.trim())
+        .filter(|item| item.len() > 0)
+        .map(String::from)
+        .collect();
+}
+
+pub fn create_policy(
+    allowed_origins: &str,
+    allowed_methods: &str,
+    allowed_headers: &str,
+    exposed_headers: &str,
+    are_credentials_allowed: bool,
+    max_age_in_seconds: u64,
+) -> CorsPolicy {
+    return CorsPolicy {
+        // origins are compared case insensitively...
+        allowed_origins: parse_list(allowed_origins.to_lowercase().as_str()),
+        allowed_methods: parse_list(allowed_methods.to_uppercase().as_str()),
+        allowed_headers: parse_list(allowed_headers),
+        exposed_headers: parse_list(exposed_headers),
+        are_credentials_allowed,
+        max_age_in_seconds,
+    };
+}
+
+// "*" matches any sequence of characters (e.g. "https://*.example.com"
+// matches "https://api.example.com")...
+pub fn matches_pattern(origin: &str, pattern: &str) -> bool {
+    let segments: Vec<&str> = pattern.split(WILDCARD).collect();
+
+    // if the pattern contains no wildcard, the origin must match exactly...
+    if segments.len() == 1 {
+        return origin.eq(pattern);
+    }
+
+    let first_segment = segments[0];
+    let last_segment = segments[segments.len() - 1];
+
+    if origin.len() < first_segment.len() + last_segment.len()
+        || !origin.starts_with(first_segment)
+        || !origin.ends_with(last_segment) {
+        return false;
+    }
+
+    // the segments in between must appear in order...
+    let mut remaining_origin = &origin[first_segment.len()..origin.len() - last_segment.len()];
+
+    for segment in &segments[1..segments.len() - 1] {
+        let index_option = remaining_origin.find(segment);
+
+        if index_option.is_none() {
+            return false;
+        }
+
+        remaining_origin = &remaining_origin[index_option.unwrap() + segment.len()..];
+    }
+
+    return true;
+}
+
+fn get_header_value<'a>(request: &'a Request<Body>, header_name: &str) -> Option<&'a str> {
+    return request.headers()
+        .get(header_name)
+        .and_then(|header_value| header_value.to_str().ok());
+}
+
+pub fn get_origin(request: &Request<Body>) -> Option<String> {
+    return get_header_value(request, ORIGIN_HEADER_NAME).map(String::from);
+}
+
+// a preflight asks whether the actual request is allowed before sending it...
+pub fn is_preflight_request(request: &Request<Body>) -> bool {
+    return request.method() == Method::OPTIONS
+        && request.headers().contains_key(ORIGIN_HEADER_NAME)
+        && request.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD_HEADER_NAME);
+}
+
+fn add_header(response: &mut Response<Body>, header_name: &'static str, value: &str) {
+    let header_value_result = HeaderValue::from_str(value);
+
+    if header_value_result.is_ok() {
+        response.headers_mut().insert(header_name, header_value_result.unwrap());
+    }
+}
+
+// caches must not serve a response tailored to one request to another that
+// differs in the given header (e.g. the origin)...
+fn add_vary(response: &mut Response<Body>, header_name: &str) {
+    let vary = response.headers()
+        .get(VARY_HEADER_NAME)
+        .and_then(|header_value| header_value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let is_header_listed = vary.split(',')
+        .any(|listed_header_name| listed_header_name.trim().eq_ignore_ascii_case(header_name)
+            || listed_header_name.trim().eq(WILDCARD));
+
+    if is_header_listed {
+        return;
+    }
+
+    let vary = if vary.trim().len() == 0 { header_name.to_string() } else { format!("{}, {}", vary, header_name) };
+
+    add_header(response, VARY_HEADER_NAME, vary.as_str());
+}
+
+impl CorsPolicy {
+    pub fn is_enabled(&self) -> bool {
+        return self.allowed_origins.len() > 0;
+    }
+
+    fn is_any_origin_allowed(&self) -> bool {
+        return self.allowed_origins.iter().any(|allowed_origin| allowed_origin.eq(WILDCARD));
+    }
+
+    // "*" matches every origin, while the other entries only match the origins they name...
+    fn is_origin_listed(&self, origin: &str) -> bool {
+        // the opaque "null" origin (e.g. sandboxed documents) must be listed explicitly...
+        if origin.eq("null") {
+            return self.allowed_origins.iter().any(|allowed_origin| allowed_origin.eq("null"));
+        }
+
+        return self.allowed_origins.iter()
+            .filter(|allowed_origin| allowed_origin.as_str() != WILDCARD)
+            .any(|allowed_origin| matches_pattern(origin, allowed_origin.as_str()));
+    }
+
+    fn is_origin_allowed(&self, origin: &str) -> bool {
+        return self.is_origin_listed(origin) || (self.is_any_origin_allowed() && !origin.eq("null"));
+    }
+
+    // allowing credentials for "*" would let any site read responses with the
+    // user's cookies, so credentials are only allowed for listed origins...
+    fn are_credentials_allowed_for(&self, origin: &str) -> bool {
+        return self.are_credentials_allowed && self.is_origin_listed(origin);
+    }
+
+    // adds the headers common to preflight and actual responses. returns false if the origin is not allowed...
+    fn add_origin_headers(&self, response: &mut Response<Body>, origin: &str) -> bool {
+        let origin = origin.to_lowercase();
+
+        if !self.is_origin_allowed(origin.as_str()) {
+            add_vary(response, ORIGIN_HEADER_NAME);
+
+            return false;
+        }
+
+        // browsers reject "*" for requests with credentials, so the origin is echoed instead...
+        if self.are_credentials_allowed_for(origin.as_str()) {
+            add_header(response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME, origin.as_str());
+            add_header(response, ACCESS_CONTROL_ALLOW_CREDENTIALS_HEADER_NAME, "true");
+            add_vary(response, ORIGIN_HEADER_NAME);
+        } else if self.is_any_origin_allowed() {
+            add_header(response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME, WILDCARD);
+
+            // whether the origin is echoed depends on the origin...
+            if self.are_credentials_allowed {
+                add_vary(response, ORIGIN_HEADER_NAME);
+            }
+        } else {
+            add_header(response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME, origin.as_str());
+            add_vary(response, ORIGIN_HEADER_NAME);
+        }
+
+        return true;
+    }
+
+    // preflights are answered without involving the parent process. if the origin is not
+    // allowed, the response carries no CORS headers and the browser blocks the request...
+    pub fn create_preflight_response(&self, request: &Request<Body>) -> Response<Body> {
+        let mut response = Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .unwrap();
+        let origin = get_header_value(request, ORIGIN_HEADER_NAME).unwrap_or("");
+
+        if !self.add_origin_headers(&mut response, origin) {
+            return response;
+        }
+
+        let requested_method = get_header_value(request, ACCESS_CONTROL_REQUEST_METHOD_HEADER_NAME).unwrap_or("");
+        let requested_headers = get_header_value(request, ACCESS_CONTROL_REQUEST_HEADERS_HEADER_NAME);
+        let is_any_method_allowed = self.allowed_methods.iter().any(|method| method.eq(WILDCARD));
+        let is_any_header_allowed = self.allowed_headers.len() == 0
+            || self.allowed_headers.iter().any(|header_name| header_name.eq(WILDCARD));
+
+        // "*" is taken literally for requests with credentials, hence we echo what is requested...
+        if is_any_method_allowed && self.are_credentials_allowed_for(origin.to_lowercase().as_str()) {
+            add_header(&mut response, ACCESS_CONTROL_ALLOW_METHODS_HEADER_NAME, requested_method);
+        } else {
+            add_header(&mut response, ACCESS_CONTROL_ALLOW_METHODS_HEADER_NAME, self.allowed_methods.join(", ").as_str());
+        }
+
+        if !is_any_header_allowed {
+            add_header(&mut response, ACCESS_CONTROL_ALLOW_HEADERS_HEADER_NAME, self.allowed_headers.join(", ").as_str());
+        } else if requested_headers.is_some() {
+            // echoing the requested headers works with and without credentials...
+            add_header(&mut response, ACCESS_CONTROL_ALLOW_HEADERS_HEADER_NAME, requested_headers.unwrap());
+            add_vary(&mut response, ACCESS_CONTROL_REQUEST_HEADERS_HEADER_NAME);
+        }
+
+        if self.max_age_in_seconds > 0 {
+            add_header(&mut response, ACCESS_CONTROL_MAX_AGE_HEADER_NAME, self.max_age_in_seconds.to_string().as_str());
+        }
+
+        return response;
+    }
+
+    pub fn add_response_headers(&self, response: &mut Response<Body>, origin: &str) {
+        if !self.add_origin_headers(response, origin) {
+            return;
+        }
+
+        if self.exposed_headers.len() > 0 {
+            add_header(response, ACCESS_CONTROL_EXPOSE_HEADERS_HEADER_NAME, self.exposed_headers.join(", ").as_str());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_request(method: Method, headers: &[(&str, &str)]) -> Request<Body> {
+        let mut request_builder = Request::builder().method(method).uri("/resource");
+
+        for (header_name, header_value) in headers {
+            request_builder = request_builder.header(*header_name, *header_value);
+        }
+
+        return request_builder.body(Body::empty()).unwrap();
+    }
+
+    fn get_header<'a>(response: &'a Response<Body>, header_name: &str) -> Option<&'a str> {
+        return response.headers()
+            .get(header_name)
+            .map(|header_value| header_value.to_str().unwrap());
+    }
+
+    #[test]
+    fn matches_exact_origins_and_patterns() {
+        assert!(matches_pattern("https://example.com", "https://example.com"));
+        assert!(!matches_pattern("https://example.com.evil.com", "https://example.com"));
+        assert!(matches_pattern("https://api.example.com", "https://*.example.com"));
+        assert!(matches_pattern("https://a.b.example.com", "https://*.example.com"));
+        assert!(!matches_pattern("https://example.com", "https://*.example.com"));
+        assert!(!matches_pattern("https://api.example.com.evil.com", "https://*.example.com"));
+        assert!(!matches_pattern("http://api.example.com", "https://*.example.com"));
+        assert!(matches_pattern("https://api.example.com:8443", "https://*.example.com:*"));
+        assert!(!matches_pattern("https://example.com", "https://*.*.example.com"));
+        assert!(matches_pattern("anything", "*"));
+    }
+
+    #[test]
+    fn answers_preflight_for_allowed_origin() {
+        let cors_policy = create_policy(
+            "https://example.com", "get, put", "x-custom", "x-exposed", false, 600);
+        let request = create_request(Method::OPTIONS, &[
+            ("origin", "https://example.com"),
+            ("access-control-request-method", "PUT"),
+            ("access-control-request-headers", "x-custom"),
+        ]);
+
+        assert!(is_preflight_request(&request));
+
+        let response = cors_policy.create_preflight_response(&request);
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME), Some("https://example.com"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_METHODS_HEADER_NAME), Some("GET, PUT"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_HEADERS_HEADER_NAME), Some("x-custom"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_MAX_AGE_HEADER_NAME), Some("600"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_CREDENTIALS_HEADER_NAME), None);
+        assert_eq!(get_header(&response, VARY_HEADER_NAME), Some("origin"));
+    }
+
+    #[test]
+    fn answers_preflight_for_disallowed_origin_without_cors_headers() {
+        let cors_policy = create_policy("https://example.com", "GET", "", "", true, 0);
+        let request = create_request(Method::OPTIONS, &[
+            ("origin", "https://evil.com"),
+            ("access-control-request-method", "GET"),
+        ]);
+        let response = cors_policy.create_preflight_response(&request);
+
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME), None);
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_METHODS_HEADER_NAME), None);
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_CREDENTIALS_HEADER_NAME), None);
+        assert_eq!(get_header(&response, VARY_HEADER_NAME), Some("origin"));
+    }
+
+    #[test]
+    fn does_not_allow_credentials_for_any_origin() {
+        let cors_policy = create_policy("*", "*", "", "", true, 0);
+        let mut response = Response::new(Body::empty());
+
+        cors_policy.add_response_headers(&mut response, "https://evil.com");
+
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME), Some("*"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_CREDENTIALS_HEADER_NAME), None);
+
+        // "*" is taken literally by browsers when no credentials are involved...
+        let request = create_request(Method::OPTIONS, &[
+            ("origin", "https://evil.com"),
+            ("access-control-request-method", "DELETE"),
+        ]);
+        let response = cors_policy.create_preflight_response(&request);
+
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME), Some("*"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_METHODS_HEADER_NAME), Some("*"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_CREDENTIALS_HEADER_NAME), None);
+    }
+
+    #[test]
+    fn allows_credentials_for_listed_origins_only() {
+        let cors_policy = create_policy("https://example.com, *", "*", "", "x-exposed", true, 0);
+        let mut response = Response::new(Body::empty());
+
+        cors_policy.add_response_headers(&mut response, "https://Example.com");
+
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME), Some("https://example.com"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_CREDENTIALS_HEADER_NAME), Some("true"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_EXPOSE_HEADERS_HEADER_NAME), Some("x-exposed"));
+        assert_eq!(get_header(&response, VARY_HEADER_NAME), Some("origin"));
+
+        // with credentials, "*" would be taken literally, so the requested method is echoed...
+        let request = create_request(Method::OPTIONS, &[
+            ("origin", "https://example.com"),
+            ("access-control-request-method", "DELETE"),
+        ]);
+        let response = cors_policy.create_preflight_response(&request);
+
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_METHODS_HEADER_NAME), Some("DELETE"));
+
+        let mut response = Response::new(Body::empty());
+
+        cors_policy.add_response_headers(&mut response, "https://other.com");
+
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME), Some("*"));
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_CREDENTIALS_HEADER_NAME), None);
+        assert_eq!(get_header(&response, VARY_HEADER_NAME), Some("origin"));
+    }
+
+    #[test]
+    fn requires_null_origin_to_be_listed() {
+        let mut response = Response::new(Body::empty());
+
+        create_policy("*", "GET", "", "", false, 0).add_response_headers(&mut response, "null");
+
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME), None);
+
+        let mut response = Response::new(Body::empty());
+
+        create_policy("null", "GET", "", "", false, 0).add_response_headers(&mut response, "null");
+
+        assert_eq!(get_header(&response, ACCESS_CONTROL_ALLOW_ORIGIN_HEADER_NAME), Some("null"));
+    }
+}
@@ -0,0 +1,79 @@
+#![allow(clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use chrono::Utc;
+use hyper::{http::HeaderValue, Body, Response};
+
+const X_POWERED_BY_HEADER_NAME: &str = "x-powered-by";
+const SERVER_HEADER_NAME: &str = "server";
+const DATE_HEADER_NAME: &str = "date";
+const STRICT_TRANSPORT_SECURITY_HEADER_NAME: &str = "strict-transport-security";
+const X_CONTENT_TYPE_OPTIONS_HEADER_NAME: &str = "x-content-type-options";
+const REFERRER_POLICY_HEADER_NAME: &str = "referrer-policy";
+const X_FRAME_OPTIONS_HEADER_NAME: &str = "x-frame-options";
+const CONTENT_SECURITY_POLICY_HEADER_NAME: &str = "content-security-policy";
+const NO_SNIFF: &str = "nosniff";
+// e.g. "Mon, 19 Oct 2026 10:00:00 GMT"...
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+#[derive(Clone, Debug)]
+pub struct SecurityHeaderPolicy {
+    pub is_enabled: bool,
+    // the following are not sent if empty...
+    pub strict_transport_security: String,
+    pub referrer_policy: String,
+    pub frame_options: String,
+    pub content_security_policy: String,
+}
+
+fn set_default_header(response: &mut Response<Body>, header_name: &'static str, value: &str) {
+    let headers = response.headers_mut();
+
+    // if the parent process has already set the header, we shall not override it.
+    // the parent process may also remove a default header by setting it empty...
+    if headers.contains_key(header_name) {
+        let is_empty = headers.get_all(header_name).iter().all(|header_value| header_value.is_empty());
+
+        if is_empty {
+            headers.remove(header_name);
+        }
+
+        return;
+    }
+
+    if value.len() == 0 {
+        return;
+    }
+
+    let header_value_result = HeaderValue::from_str(value);
+
+    if header_value_result.is_ok() {
+        headers.insert(header_name, header_value_result.unwrap());
+    }
+}
+
+pub fn add_default_headers(
+    response: &mut Response<Body>,
+    powered_by: &str,
+    server_name: &str,
+    security_header_policy: &SecurityHeaderPolicy,
+    is_tls_enabled: bool,
+) {
+    set_default_header(response, X_POWERED_BY_HEADER_NAME, powered_by);
+    set_default_header(response, SERVER_HEADER_NAME, server_name);
+    // hyper adds the date to HTTP/1 responses itself, but HTTP/3 responses would lack it...
+    set_default_header(response, DATE_HEADER_NAME, Utc::now().format(HTTP_DATE_FORMAT).to_string().as_str());
+
+    if !security_header_policy.is_enabled {
+        return;
+    }
+
+    // browsers ignore HSTS received over plain HTTP...
+    if is_tls_enabled {
+        set_default_header(response, STRICT_TRANSPORT_SECURITY_HEADER_NAME, security_header_policy.strict_transport_security.as_str());
+    }
+
+    set_default_header(response, X_CONTENT_TYPE_OPTIONS_HEADER_NAME, NO_SNIFF);
+    set_default_header(response, REFERRER_POLICY_HEADER_NAME, security_header_policy.referrer_policy.as_str());
+    set_default_header(response, X_FRAME_OPTIONS_HEADER_NAME, security_header_policy.frame_options.as_str());
+    set_default_header(response, CONTENT_SECURITY_POLICY_HEADER_NAME, security_header_policy.content_security_policy.as_str());
+}
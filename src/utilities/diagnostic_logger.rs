@@ -0,0 +1,340 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use chrono::{SecondsFormat, Utc};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde_json::json;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    os::unix::net::UnixDatagram,
+    sync::{Mutex, OnceLock},
+};
+
+// the standard output is reserved for communication with the parent process...
+const STANDARD_ERROR_OUTPUT: &str = "stderr";
+const JOURNALD_OUTPUT: &str = "journald";
+const JOURNALD_SOCKET_PATH: &str = "/run/systemd/journal/socket";
+const SYSLOG_IDENTIFIER: &str = "volcano-http-server";
+// records of our own modules are prefixed with the crate name...
+const CRATE_NAME_PREFIX: &str = concat!(env!("CARGO_CRATE_NAME"), "::");
+
+static DIAGNOSTIC_LOGGER: OnceLock<DiagnosticLogger> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogFormat {
+    // one line of plain text per record...
+    Human,
+    // one JSON object per line...
+    Json,
+}
+
+#[derive(Clone, Debug)]
+pub struct LogLevels {
+    pub default_level: LevelFilter,
+    // sorted so that the most specific module comes first...
+    pub module_levels: Vec<(String, LevelFilter)>,
+}
+
+enum LogOutput {
+    StandardError,
+    // none means the file could not be reopened...
+    File(Mutex<Option<File>>),
+    Journald(UnixDatagram),
+}
+
+struct DiagnosticLogger {
+    log_levels: LogLevels,
+    format: LogFormat,
+    output_path: String,
+    output: LogOutput,
+}
+
+fn parse_level(level: &str) -> Option<LevelFilter> {
+    // accepts off, error, warn, info, debug and trace (case insensitive)...
+    return level.trim().parse::<LevelFilter>().ok();
+}
+
+pub fn parse_levels(levels: &str) -> LogLevels {
+    let mut log_levels = LogLevels {
+        default_level: LevelFilter::Info,
+        module_levels: Vec::new(),
+    };
+
+    // e.g. "info,http_server=debug,quinn=warn" logs at info level, except
+    // for the HTTP server (debug) and the QUIC library (warn)...
+    for directive in levels.split(',') {
+        let directive = directive.trim();
+
+        if directive.len() == 0 {
+            continue;
+        }
+
+        let index_of_separator_option = directive.rfind('=');
+        let (module, level) = if index_of_separator_option.is_none() {
+            ("", directive)
+        } else {
+            let index_of_separator = index_of_separator_option.unwrap();
+
+            (directive[..index_of_separator].trim(), &directive[index_of_separator + 1..])
+        };
+        let level_option = parse_level(level);
+
+        if level_option.is_none() {
+            eprintln!("Invalid log level '{}'. Expected off, error, warn, info, debug or trace.", directive);
+
+            continue;
+        }
+
+        if module.len() == 0 {
+            log_levels.default_level = level_option.unwrap();
+        } else {
+            log_levels.module_levels.push((module.to_string(), level_option.unwrap()));
+        }
+    }
+
+    log_levels.module_levels.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+
+    return log_levels;
+}
+
+pub fn parse_format(format: &str) -> LogFormat {
+    return match format.trim().to_lowercase().as_str() {
+        "human" => LogFormat::Human,
+        "json" => LogFormat::Json,
+        _ => {
+            eprintln!("Invalid log format '{}'. Expected human or json. Falling back to human.", format);
+
+            LogFormat::Human
+        },
+    };
+}
+
+impl LogLevels {
+    fn get_level(&self, target: &str) -> LevelFilter {
+        let module = target.strip_prefix(CRATE_NAME_PREFIX).unwrap_or(target);
+
+        for (configured_module, level) in self.module_levels.iter() {
+            // "quinn" matches "quinn" and "quinn::endpoint" but not "quinn_proto"...
+            let is_match = module.starts_with(configured_module.as_str())
+                && (module.len() == configured_module.len()
+                    || module[configured_module.len()..].starts_with("::"));
+
+            if is_match {
+                return *level;
+            }
+        }
+
+        return self.default_level;
+    }
+
+    fn get_maximum_level(&self) -> LevelFilter {
+        return self.module_levels
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default_level, |maximum_level, level| maximum_level.max(level));
+    }
+}
+
+fn open_file(path: &str) -> Option<File> {
+    let file_result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path);
+
+    if file_result.is_err() {
+        let error = file_result.unwrap_err();
+
+        eprintln!("An error occurred while opening log file '{}': {}", path, error);
+
+        return None;
+    }
+
+    return Some(file_result.unwrap());
+}
+
+fn create_output(output_path: &str) -> LogOutput {
+    if output_path.len() == 0 || output_path.eq(STANDARD_ERROR_OUTPUT) {
+        return LogOutput::StandardError;
+    }
+
+    if output_path.eq(JOURNALD_OUTPUT) {
+        let socket_result = UnixDatagram::unbound()
+            .and_then(|socket| socket.connect(JOURNALD_SOCKET_PATH).map(|_| socket));
+
+        if socket_result.is_err() {
+            let error = socket_result.unwrap_err();
+
+            eprintln!("An error occurred while connecting to journald, logging to the standard error instead: {}", error);
+
+            return LogOutput::StandardError;
+        }
+
+        return LogOutput::Journald(socket_result.unwrap());
+    }
+
+    let file_option = open_file(output_path);
+
+    // if the file cannot be opened, we shall not lose the records...
+    if file_option.is_none() {
+        return LogOutput::StandardError;
+    }
+
+    return LogOutput::File(Mutex::new(file_option));
+}
+
+fn get_journald_priority(level: Level) -> u8 {
+    return match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    };
+}
+
+fn append_journald_field(buffer: &mut Vec<u8>, name: &str, value: &str) {
+    buffer.extend_from_slice(name.as_bytes());
+
+    // values spanning multiple lines must be prefixed with their length...
+    if value.contains('\n') {
+        buffer.push(b'\n');
+        buffer.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        buffer.push(b'=');
+    }
+
+    buffer.extend_from_slice(value.as_bytes());
+    buffer.push(b'\n');
+}
+
+fn format_human(record: &Record, module: &str) -> String {
+    return format!(
+        "{} {:<5} {}: {}\n",
+        Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        record.level(),
+        module,
+        record.args(),
+    );
+}
+
+fn format_json(record: &Record, module: &str) -> String {
+    let mut line = json!({
+        "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        "level": record.level().as_str(),
+        "module": module,
+        "message": record.args().to_string(),
+    }).to_string();
+
+    line.push('\n');
+
+    return line;
+}
+
+impl DiagnosticLogger {
+    fn write_to_journald(&self, socket: &UnixDatagram, record: &Record, module: &str) {
+        let mut buffer: Vec<u8> = Vec::new();
+
+        append_journald_field(&mut buffer, "PRIORITY", get_journald_priority(record.level()).to_string().as_str());
+        append_journald_field(&mut buffer, "SYSLOG_IDENTIFIER", SYSLOG_IDENTIFIER);
+        append_journald_field(&mut buffer, "MESSAGE", record.args().to_string().as_str());
+        append_journald_field(&mut buffer, "MODULE", module);
+
+        if record.file().is_some() {
+            append_journald_field(&mut buffer, "CODE_FILE", record.file().unwrap());
+        }
+
+        if record.line().is_some() {
+            append_journald_field(&mut buffer, "CODE_LINE", record.line().unwrap().to_string().as_str());
+        }
+
+        // there is nowhere else to report the failure...
+        _ = socket.send(&buffer);
+    }
+
+    fn reopen(&self) {
+        // only files can be moved away by log rotation tools...
+        if let LogOutput::File(file_mutex) = &self.output {
+            let mut file_option = file_mutex.lock().unwrap();
+
+            // the previous file is closed first...
+            *file_option = None;
+            *file_option = open_file(self.output_path.as_str());
+        }
+    }
+}
+
+impl Log for DiagnosticLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return metadata.level() <= self.log_levels.get_level(metadata.target());
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let module = record.target().strip_prefix(CRATE_NAME_PREFIX).unwrap_or(record.target());
+
+        if let LogOutput::Journald(socket) = &self.output {
+            // journald stores structured fields, hence the format does not apply...
+            self.write_to_journald(socket, record, module);
+
+            return;
+        }
+
+        let line = match self.format {
+            LogFormat::Human => format_human(record, module),
+            LogFormat::Json => format_json(record, module),
+        };
+
+        match &self.output {
+            LogOutput::File(file_mutex) => {
+                let mut file_option = file_mutex.lock().unwrap();
+
+                // if the file could not be reopened, the record is lost...
+                if file_option.is_some() {
+                    _ = file_option.as_mut().unwrap().write_all(line.as_bytes());
+                }
+            },
+            _ => {
+                _ = io::stderr().write_all(line.as_bytes());
+            },
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+pub fn initialize(log_levels: &LogLevels, format: LogFormat, output_path: &str) {
+    let diagnostic_logger = DiagnosticLogger {
+        log_levels: log_levels.clone(),
+        format,
+        output_path: output_path.to_string(),
+        output: create_output(output_path),
+    };
+
+    if DIAGNOSTIC_LOGGER.set(diagnostic_logger).is_err() {
+        return;
+    }
+
+    let set_logger_result = log::set_logger(DIAGNOSTIC_LOGGER.get().unwrap());
+
+    if set_logger_result.is_err() {
+        let error = set_logger_result.unwrap_err();
+
+        eprintln!("An error occurred while initializing diagnostic logger: {}", error);
+
+        return;
+    }
+
+    // records above the maximum level are discarded without even being formatted...
+    log::set_max_level(log_levels.get_maximum_level());
+}
+
+// reopens the log file after it has been moved away by an external tool (e.g. logrotate)...
+pub fn reopen() {
+    let diagnostic_logger_option = DIAGNOSTIC_LOGGER.get();
+
+    if diagnostic_logger_option.is_some() {
+        diagnostic_logger_option.unwrap().reopen();
+    }
+}
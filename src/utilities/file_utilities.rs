@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 use std::{io::{self, BufReader}, fs::{self, File}};
 
 const BUFFER_LENGTH_IN_BYTES: usize = 8192;         // 8 KB...
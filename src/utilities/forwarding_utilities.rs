@@ -0,0 +1,394 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use ipnet::IpNet;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+use crate::remote_address::RemoteAddress;
+
+pub const FORWARDED_HEADER_NAME: &str = "forwarded";
+pub const X_FORWARDED_FOR_HEADER_NAME: &str = "x-forwarded-for";
+pub const X_FORWARDED_PROTO_HEADER_NAME: &str = "x-forwarded-proto";
+pub const X_FORWARDED_HOST_HEADER_NAME: &str = "x-forwarded-host";
+const HOST_HEADER_NAME: &str = "host";
+
+// the forwarding headers that the trusted proxies set. the other kind is
+// never honoured, as it may have been sent by the client itself...
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrustedProxyHeader {
+    // X-Forwarded-For along with X-Forwarded-Proto and X-Forwarded-Host...
+    XForwardedFor,
+    // Forwarded (RFC 7239)...
+    Forwarded,
+}
+
+pub struct ForwardedInformation {
+    pub client_ip_address: Option<String>,
+    pub client_port: Option<u16>,
+    pub scheme: Option<String>,
+    pub host: Option<String>,
+}
+
+// a single hop of the forwarding chain...
+struct ForwardedElement {
+    node: String,
+    protocol: Option<String>,
+    host: Option<String>,
+}
+
+pub fn parse_trusted_proxies(trusted_proxies: &str) -> Vec<IpNet> {
+    let mut networks: Vec<IpNet> = Vec::new();
+
+    for trusted_proxy in trusted_proxies.split(',') {
+        let trusted_proxy = trusted_proxy.trim();
+
+        if trusted_proxy.len() == 0 {
+            continue;
+        }
+
+        // a bare IP address is treated as a network with a single address...
+        let network_result = IpNet::from_str(trusted_proxy)
+            .or_else(|_| IpAddr::from_str(trusted_proxy).map(IpNet::from));
+
+        if network_result.is_err() {
+            let error = network_result.unwrap_err();
+
+            eprintln!("An error occurred while parsing trusted proxy '{}': {}", trusted_proxy, error);
+
+            continue;
+        }
+
+        networks.push(network_result.unwrap());
+    }
+
+    return networks;
+}
+
+pub fn parse_trusted_proxy_header(trusted_proxy_header: &str) -> TrustedProxyHeader {
+    return match trusted_proxy_header.trim().to_lowercase().as_str() {
+        "" | X_FORWARDED_FOR_HEADER_NAME => TrustedProxyHeader::XForwardedFor,
+        FORWARDED_HEADER_NAME => TrustedProxyHeader::Forwarded,
+        _ => {
+            eprintln!("Invalid trusted proxy header '{}'. Expected x-forwarded-for or forwarded. Falling back to x-forwarded-for.", trusted_proxy_header);
+
+            TrustedProxyHeader::XForwardedFor
+        },
+    };
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        return &value[1..value.len() - 1];
+    }
+
+    return value;
+}
+
+fn parse_node(node: &str) -> (Option<IpAddr>, Option<u16>) {
+    let node = unquote(node);
+
+    // "192.0.2.43" or "2001:db8::1"...
+    let ip_address_result = IpAddr::from_str(node);
+
+    if ip_address_result.is_ok() {
+        return (Some(ip_address_result.unwrap()), None);
+    }
+
+    // "192.0.2.43:47011" or "[2001:db8::1]:4711"...
+    let socket_address_result = SocketAddr::from_str(node);
+
+    if socket_address_result.is_ok() {
+        let socket_address = socket_address_result.unwrap();
+
+        return (Some(socket_address.ip()), Some(socket_address.port()));
+    }
+
+    // "[2001:db8::1]" without port...
+    if node.starts_with('[') && node.ends_with(']') {
+        return (IpAddr::from_str(&node[1..node.len() - 1]).ok(), None);
+    }
+
+    // "unknown" or obfuscated identifiers...
+    return (None, None);
+}
+
+fn get_joined_header_values(header_name: &str, headers: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let values_option = headers.get(header_name);
+
+    if values_option.is_none() {
+        return Vec::new();
+    }
+
+    // multiple header lines are equivalent to a single comma separated line...
+    return values_option.unwrap()
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(|value| value.trim().to_string())
+        .filter(|value| value.len() > 0)
+        .collect();
+}
+
+fn parse_forwarded_header(headers: &HashMap<String, Vec<String>>) -> Vec<ForwardedElement> {
+    let mut elements: Vec<ForwardedElement> = Vec::new();
+
+    for element in get_joined_header_values(FORWARDED_HEADER_NAME, headers) {
+        let mut forwarded_element = ForwardedElement {
+            node: String::from(""),
+            protocol: None,
+            host: None,
+        };
+
+        for pair in element.split(';') {
+            let index_of_separator_option = pair.find('=');
+
+            if index_of_separator_option.is_none() {
+                continue;
+            }
+
+            let index_of_separator = index_of_separator_option.unwrap();
+            let name = pair[..index_of_separator].trim().to_lowercase();
+            let value = unquote(&pair[index_of_separator + 1..]).to_string();
+
+            match name.as_str() {
+                "for" => forwarded_element.node = value,
+                "proto" => forwarded_element.protocol = Some(value.to_lowercase()),
+                "host" => forwarded_element.host = Some(value),
+                _ => {},
+            }
+        }
+
+        elements.push(forwarded_element);
+    }
+
+    return elements;
+}
+
+fn parse_x_forwarded_headers(headers: &HashMap<String, Vec<String>>) -> Vec<ForwardedElement> {
+    let nodes = get_joined_header_values(X_FORWARDED_FOR_HEADER_NAME, headers);
+    let protocols = get_joined_header_values(X_FORWARDED_PROTO_HEADER_NAME, headers);
+    let hosts = get_joined_header_values(X_FORWARDED_HOST_HEADER_NAME, headers);
+    let mut elements: Vec<ForwardedElement> = Vec::with_capacity(nodes.len());
+
+    for (index, node) in nodes.iter().enumerate() {
+        elements.push(ForwardedElement {
+            node: node.to_owned(),
+            // if the proxies appended one value per hop, we shall use the value of
+            // the same hop. otherwise, the value set by the nearest proxy is used...
+            protocol: if protocols.len() == nodes.len() { protocols.get(index) } else { protocols.last() }
+                .map(|protocol| protocol.to_lowercase()),
+            host: if hosts.len() == nodes.len() { hosts.get(index) } else { hosts.last() }
+                .cloned(),
+        });
+    }
+
+    return elements;
+}
+
+fn is_trusted(ip_address: &IpAddr, trusted_proxies: &[IpNet]) -> bool {
+    // IPv4 addresses may arrive mapped into IPv6 on dual stack sockets...
+    let ip_address = match ip_address {
+        IpAddr::V6(ipv6_address) => ipv6_address.to_ipv4_mapped().map_or(*ip_address, IpAddr::V4),
+        IpAddr::V4(_) => *ip_address,
+    };
+
+    return trusted_proxies.iter().any(|network| network.contains(&ip_address));
+}
+
+pub fn is_peer_trusted(remote_address: &RemoteAddress, trusted_proxies: &[IpNet]) -> bool {
+    // peers connected via Unix domain socket are local processes
+    // (e.g. nginx on the same host) and are always trusted...
+    return match remote_address {
+        RemoteAddress::Ip(socket_address) => is_trusted(&socket_address.ip(), trusted_proxies),
+        RemoteAddress::Unix(_) => true,
+    };
+}
+
+pub fn resolve(
+    remote_address: &RemoteAddress,
+    headers: &HashMap<String, Vec<String>>,
+    trusted_proxies: &[IpNet],
+    trusted_proxy_header: TrustedProxyHeader,
+) -> ForwardedInformation {
+    let peer_information = ForwardedInformation {
+        client_ip_address: remote_address.get_ip_address(),
+        client_port: remote_address.get_port(),
+        scheme: None,
+        host: None,
+    };
+
+    // forwarding headers are only honoured if they were set by a trusted proxy...
+    if !is_peer_trusted(remote_address, trusted_proxies) {
+        return peer_information;
+    }
+
+    // only the header set by the trusted proxies is read. e.g. if they append to
+    // X-Forwarded-For, a Forwarded header can only have come from the client...
+    let elements = match trusted_proxy_header {
+        TrustedProxyHeader::XForwardedFor => parse_x_forwarded_headers(headers),
+        TrustedProxyHeader::Forwarded => parse_forwarded_header(headers),
+    };
+
+    if elements.len() == 0 {
+        return peer_information;
+    }
+
+    // walking from right to left, the first address that is not a trusted proxy is the client.
+    // if every address is trusted, the left-most address is the client...
+    let index_of_client = elements
+        .iter()
+        .rposition(|element| {
+            let (ip_address_option, _) = parse_node(&element.node);
+
+            ip_address_option.is_none() || !is_trusted(&ip_address_option.unwrap(), trusted_proxies)
+        })
+        .unwrap_or(0);
+    let client_element = &elements[index_of_client];
+    let (client_ip_address_option, client_port) = parse_node(&client_element.node);
+    let client_ip_address = client_ip_address_option
+        .map(|ip_address| ip_address.to_string())
+        // obfuscated identifiers are passed through as is...
+        .or(Some(unquote(&client_element.node).to_string()).filter(|node| node.len() > 0));
+
+    return ForwardedInformation {
+        client_ip_address,
+        client_port,
+        scheme: client_element.protocol.clone(),
+        host: client_element.host.clone(),
+    };
+}
+
+pub fn get_host<'a>(uri_host: Option<&'a str>, headers: &'a HashMap<String, Vec<String>>) -> Option<&'a str> {
+    let host_header_values_option = headers.get(HOST_HEADER_NAME);
+
+    // HTTP/1.1 carries the host in the header, HTTP/2 and HTTP/3 carry it in the URI...
+    if host_header_values_option.is_some() && host_header_values_option.unwrap().len() > 0 {
+        return Some(host_header_values_option.unwrap()[0].as_str());
+    }
+
+    return uri_host;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_remote_address(ip_address: &str) -> RemoteAddress {
+        return RemoteAddress::Ip(SocketAddr::new(IpAddr::from_str(ip_address).unwrap(), 40000));
+    }
+
+    fn create_headers(pairs: &[(&str, &str)]) -> HashMap<String, Vec<String>> {
+        let mut headers: HashMap<String, Vec<String>> = HashMap::new();
+
+        for (name, value) in pairs {
+            headers.entry(name.to_string()).or_default().push(value.to_string());
+        }
+
+        return headers;
+    }
+
+    #[test]
+    fn ignores_headers_of_untrusted_peers() {
+        let trusted_proxies = parse_trusted_proxies("10.0.0.0/8");
+        let headers = create_headers(&[("x-forwarded-for", "1.2.3.4"), ("x-forwarded-proto", "https")]);
+        let information = resolve(&create_remote_address("192.0.2.1"), &headers, &trusted_proxies, TrustedProxyHeader::XForwardedFor);
+
+        assert_eq!(information.client_ip_address.as_deref(), Some("192.0.2.1"));
+        assert_eq!(information.client_port, Some(40000));
+        assert_eq!(information.scheme, None);
+    }
+
+    #[test]
+    fn resolves_right_most_untrusted_address() {
+        let trusted_proxies = parse_trusted_proxies("10.0.0.0/8");
+        // the client made up the first address, the proxies appended the others...
+        let headers = create_headers(&[
+            ("x-forwarded-for", "6.6.6.6, 203.0.113.7"),
+            ("x-forwarded-for", "10.0.0.2"),
+            ("x-forwarded-proto", "HTTPS"),
+            ("x-forwarded-host", "example.com"),
+        ]);
+        let information = resolve(&create_remote_address("10.0.0.1"), &headers, &trusted_proxies, TrustedProxyHeader::XForwardedFor);
+
+        assert_eq!(information.client_ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(information.client_port, None);
+        assert_eq!(information.scheme.as_deref(), Some("https"));
+        assert_eq!(information.host.as_deref(), Some("example.com"));
+    }
+
+    #[test]
+    fn resolves_left_most_address_if_every_address_is_trusted() {
+        let trusted_proxies = parse_trusted_proxies("10.0.0.0/8");
+        let headers = create_headers(&[("x-forwarded-for", "10.0.0.3, 10.0.0.2")]);
+        let information = resolve(&create_remote_address("10.0.0.1"), &headers, &trusted_proxies, TrustedProxyHeader::XForwardedFor);
+
+        assert_eq!(information.client_ip_address.as_deref(), Some("10.0.0.3"));
+    }
+
+    #[test]
+    fn ignores_forwarded_header_sent_by_client_behind_x_forwarded_for_proxy() {
+        let trusted_proxies = parse_trusted_proxies("10.0.0.1");
+        let headers = create_headers(&[("forwarded", "for=1.2.3.4"), ("x-forwarded-for", "203.0.113.7")]);
+        let information = resolve(&create_remote_address("10.0.0.1"), &headers, &trusted_proxies, TrustedProxyHeader::XForwardedFor);
+
+        assert_eq!(information.client_ip_address.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn ignores_x_forwarded_for_sent_by_client_behind_forwarded_proxy() {
+        let trusted_proxies = parse_trusted_proxies("10.0.0.1");
+        let headers = create_headers(&[("x-forwarded-for", "1.2.3.4"), ("forwarded", "for=203.0.113.7")]);
+        let information = resolve(&create_remote_address("10.0.0.1"), &headers, &trusted_proxies, TrustedProxyHeader::Forwarded);
+
+        assert_eq!(information.client_ip_address.as_deref(), Some("203.0.113.7"));
+
+        let headers = create_headers(&[("x-forwarded-for", "1.2.3.4")]);
+        let information = resolve(&create_remote_address("10.0.0.1"), &headers, &trusted_proxies, TrustedProxyHeader::Forwarded);
+
+        assert_eq!(information.client_ip_address.as_deref(), Some("10.0.0.1"));
+    }
+
+    #[test]
+    fn parses_forwarded_header() {
+        let trusted_proxies = parse_trusted_proxies("10.0.0.1");
+        let headers = create_headers(&[("forwarded", "for=\"[2001:db8::1]:4711\";Proto=HTTPS;host=\"example.com\", for=10.0.0.1")]);
+        let information = resolve(&create_remote_address("10.0.0.1"), &headers, &trusted_proxies, TrustedProxyHeader::Forwarded);
+
+        assert_eq!(information.client_ip_address.as_deref(), Some("2001:db8::1"));
+        assert_eq!(information.client_port, Some(4711));
+        assert_eq!(information.scheme.as_deref(), Some("https"));
+        assert_eq!(information.host.as_deref(), Some("example.com"));
+    }
+
+    #[test]
+    fn passes_obfuscated_identifiers_through() {
+        let trusted_proxies = parse_trusted_proxies("10.0.0.1");
+        let headers = create_headers(&[("forwarded", "for=_hidden")]);
+        let information = resolve(&create_remote_address("10.0.0.1"), &headers, &trusted_proxies, TrustedProxyHeader::Forwarded);
+
+        assert_eq!(information.client_ip_address.as_deref(), Some("_hidden"));
+        assert_eq!(information.client_port, None);
+    }
+
+    #[test]
+    fn trusts_ipv4_mapped_peers() {
+        let trusted_proxies = parse_trusted_proxies("127.0.0.1, invalid");
+        let headers = create_headers(&[("x-forwarded-for", "203.0.113.7")]);
+        let information = resolve(&create_remote_address("::ffff:127.0.0.1"), &headers, &trusted_proxies, TrustedProxyHeader::XForwardedFor);
+
+        assert_eq!(trusted_proxies.len(), 1);
+        assert_eq!(information.client_ip_address.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn parses_trusted_proxy_header() {
+        assert_eq!(parse_trusted_proxy_header(""), TrustedProxyHeader::XForwardedFor);
+        assert_eq!(parse_trusted_proxy_header("X-Forwarded-For"), TrustedProxyHeader::XForwardedFor);
+        assert_eq!(parse_trusted_proxy_header(" forwarded "), TrustedProxyHeader::Forwarded);
+        assert_eq!(parse_trusted_proxy_header("x-real-ip"), TrustedProxyHeader::XForwardedFor);
+    }
+}
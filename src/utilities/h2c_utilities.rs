@@ -0,0 +1,476 @@
+#![allow(clippy::needless_return, clippy::assign_op_pattern)]
+
+const CONNECTION_HEADER_NAME: &str = "connection";
+const UPGRADE_HEADER_NAME: &str = "upgrade";
+const UPGRADE_HEADER_VALUE_H2C: &str = "h2c";
+const HTTP2_SETTINGS_HEADER_NAME: &str = "http2-settings";
+const CONTENT_LENGTH_HEADER_NAME: &str = "content-length";
+const TRANSFER_ENCODING_HEADER_NAME: &str = "transfer-encoding";
+const TE_HEADER_NAME: &str = "te";
+const TE_HEADER_VALUE_TRAILERS: &str = "trailers";
+// these headers are not allowed in HTTP/2 requests...
+const CONNECTION_SPECIFIC_HEADER_NAMES: [&str; 7] = [
+    CONNECTION_HEADER_NAME,
+    UPGRADE_HEADER_NAME,
+    HTTP2_SETTINGS_HEADER_NAME,
+    TRANSFER_ENCODING_HEADER_NAME,
+    "keep-alive",
+    "proxy-connection",
+    "host",
+];
+const CLIENT_CONNECTION_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+const FRAME_HEADER_LENGTH: usize = 9;
+const FRAME_TYPE_HEADERS: u8 = 0x1;
+const FRAME_TYPE_SETTINGS: u8 = 0x4;
+const FRAME_TYPE_CONTINUATION: u8 = 0x9;
+const FRAME_FLAG_END_STREAM: u8 = 0x1;
+const FRAME_FLAG_END_HEADERS: u8 = 0x4;
+// frames up to this size are accepted by every HTTP/2 server...
+const DEFAULT_MAXIMUM_FRAME_SIZE: usize = 16384;
+// the upgrade request is always assigned stream 1...
+const UPGRADE_STREAM_IDENTIFIER: u32 = 1;
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use hyper::{http::HeaderValue, Body, Request, Response, StatusCode, Version};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
+
+fn contains_token(header_value_option: Option<&HeaderValue>, token: &str) -> bool {
+    return header_value_option
+        .and_then(|header_value| header_value.to_str().ok())
+        .unwrap_or_default()
+        .split(',')
+        .any(|value| value.trim().eq_ignore_ascii_case(token));
+}
+
+fn has_body(request: &Request<Body>) -> bool {
+    if request.headers().contains_key(TRANSFER_ENCODING_HEADER_NAME) {
+        return true;
+    }
+
+    let content_length_option = request.headers().get(CONTENT_LENGTH_HEADER_NAME);
+
+    return content_length_option.is_some() && content_length_option.unwrap() != "0";
+}
+
+pub fn is_upgrade_request(request: &Request<Body>) -> bool {
+    // upgrade is only defined for HTTP/1.1...
+    if request.version() != Version::HTTP_11 {
+        return false;
+    }
+
+    let headers = request.headers();
+    let connection_header_value_option = headers.get(CONNECTION_HEADER_NAME);
+
+    if !contains_token(connection_header_value_option, UPGRADE_HEADER_NAME)
+        || !contains_token(connection_header_value_option, HTTP2_SETTINGS_HEADER_NAME)
+        || !contains_token(headers.get(UPGRADE_HEADER_NAME), UPGRADE_HEADER_VALUE_H2C) {
+        return false;
+    }
+
+    // exactly one HTTP2-Settings header must be present...
+    if headers.get_all(HTTP2_SETTINGS_HEADER_NAME).iter().count() != 1 {
+        return false;
+    }
+
+    // the request becomes stream 1 of the HTTP/2 connection. requests with a body
+    // are answered over HTTP/1.1 instead, as the server may ignore the upgrade...
+    return !has_body(request);
+}
+
+pub fn create_switching_protocols_response() -> Response<Body> {
+    return Response::builder()
+        .status(StatusCode::SWITCHING_PROTOCOLS)
+        .header(CONNECTION_HEADER_NAME, UPGRADE_HEADER_NAME)
+        .header(UPGRADE_HEADER_NAME, UPGRADE_HEADER_VALUE_H2C)
+        .body(Body::empty())
+        .unwrap();
+}
+
+fn encode_integer(value: usize, prefix_length: u8, first_byte: u8, buffer: &mut Vec<u8>) {
+    let maximum_prefix_value = (1_usize << prefix_length) - 1;
+
+    if value < maximum_prefix_value {
+        buffer.push(first_byte | value as u8);
+
+        return;
+    }
+
+    buffer.push(first_byte | maximum_prefix_value as u8);
+
+    let mut remaining_value = value - maximum_prefix_value;
+
+    while remaining_value >= 128 {
+        buffer.push((remaining_value % 128 + 128) as u8);
+        remaining_value = remaining_value / 128;
+    }
+
+    buffer.push(remaining_value as u8);
+}
+
+fn encode_header(name: &[u8], value: &[u8], buffer: &mut Vec<u8>) {
+    // every field is encoded as "literal without indexing" with a literal name,
+    // so that the dynamic table remains untouched for the HTTP/2 connection.
+    // strings are never Huffman encoded...
+    buffer.push(0x00);
+    encode_integer(name.len(), 7, 0x00, buffer);
+    buffer.extend_from_slice(name);
+    encode_integer(value.len(), 7, 0x00, buffer);
+    buffer.extend_from_slice(value);
+}
+
+fn encode_header_block(request: &Request<Body>) -> Vec<u8> {
+    let mut header_block: Vec<u8> = Vec::new();
+    let headers = request.headers();
+    let uri = request.uri();
+    // the authority is taken from the request target if present, otherwise from the host header...
+    let authority = uri.authority()
+        .map(|authority| authority.as_str().as_bytes())
+        .or(headers.get("host").map(|host| host.as_bytes()))
+        .unwrap_or_default();
+    let path = uri.path_and_query().map_or("/", |path_and_query| path_and_query.as_str());
+
+    encode_header(b":method", request.method().as_str().as_bytes(), &mut header_block);
+    encode_header(b":scheme", b"http", &mut header_block);
+    encode_header(b":authority", authority, &mut header_block);
+    encode_header(b":path", path.as_bytes(), &mut header_block);
+
+    for (header_name, header_value) in headers.iter() {
+        let header_name = header_name.as_str();
+
+        // headers nominated by the connection header are connection-specific as well...
+        if CONNECTION_SPECIFIC_HEADER_NAMES.contains(&header_name)
+            || contains_token(headers.get(CONNECTION_HEADER_NAME), header_name) {
+            continue;
+        }
+
+        // TE is only allowed to indicate support for trailers...
+        if header_name == TE_HEADER_NAME && header_value != TE_HEADER_VALUE_TRAILERS {
+            continue;
+        }
+
+        encode_header(header_name.as_bytes(), header_value.as_bytes(), &mut header_block);
+    }
+
+    return header_block;
+}
+
+fn write_frame(frame_type: u8, flags: u8, stream_identifier: u32, payload: &[u8], buffer: &mut Vec<u8>) {
+    let payload_length = payload.len() as u32;
+
+    buffer.reserve(FRAME_HEADER_LENGTH + payload.len());
+    buffer.extend_from_slice(&payload_length.to_be_bytes()[1..]);
+    buffer.push(frame_type);
+    buffer.push(flags);
+    buffer.extend_from_slice(&stream_identifier.to_be_bytes());
+    buffer.extend_from_slice(payload);
+}
+
+// encodes the upgrade request as if the client had sent it on stream 1 of the
+// HTTP/2 connection. the request has no body, so the stream ends with the headers...
+pub fn encode_request_frames(request: &Request<Body>) -> Vec<u8> {
+    let header_block = encode_header_block(request);
+    let chunks: Vec<&[u8]> = header_block.chunks(DEFAULT_MAXIMUM_FRAME_SIZE).collect();
+    let mut buffer: Vec<u8> = Vec::new();
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        let is_last_chunk = index == chunks.len() - 1;
+        let end_headers_flag = if is_last_chunk { FRAME_FLAG_END_HEADERS } else { 0x0 };
+
+        // header blocks that do not fit in a single frame continue in CONTINUATION frames...
+        if index == 0 {
+            write_frame(FRAME_TYPE_HEADERS, FRAME_FLAG_END_STREAM | end_headers_flag,
+                UPGRADE_STREAM_IDENTIFIER, chunk, &mut buffer);
+        } else {
+            write_frame(FRAME_TYPE_CONTINUATION, end_headers_flag,
+                UPGRADE_STREAM_IDENTIFIER, chunk, &mut buffer);
+        }
+    }
+
+    return buffer;
+}
+
+// after the 101 response, the client sends the connection preface followed by
+// a SETTINGS frame. both are read here so that the request frames can be placed after them...
+pub async fn read_client_preface_async<S>(stream: &mut S) -> io::Result<Vec<u8>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut client_preface = vec![0u8; CLIENT_CONNECTION_PREFACE.len() + FRAME_HEADER_LENGTH];
+
+    stream.read_exact(&mut client_preface).await?;
+
+    if &client_preface[..CLIENT_CONNECTION_PREFACE.len()] != CLIENT_CONNECTION_PREFACE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid HTTP/2 connection preface."));
+    }
+
+    let frame_header = &client_preface[CLIENT_CONNECTION_PREFACE.len()..];
+
+    if frame_header[3] != FRAME_TYPE_SETTINGS {
+        return Err(io::Error::new(io::ErrorKind::InvalidData,
+            "HTTP/2 connection preface is not followed by a SETTINGS frame."));
+    }
+
+    let payload_length = u32::from_be_bytes([0, frame_header[0], frame_header[1], frame_header[2]]) as usize;
+
+    if payload_length > DEFAULT_MAXIMUM_FRAME_SIZE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "HTTP/2 SETTINGS frame is too large."));
+    }
+
+    let payload_offset = client_preface.len();
+
+    client_preface.resize(payload_offset + payload_length, 0);
+    stream.read_exact(&mut client_preface[payload_offset..]).await?;
+
+    return Ok(client_preface);
+}
+
+// yields the given bytes before anything that is read from the inner stream...
+pub struct PrefixedStream<S> {
+    inner: S,
+    prefix: Vec<u8>,
+    prefix_offset: usize,
+}
+
+impl<S> PrefixedStream<S> {
+    pub fn new(inner: S, prefix: Vec<u8>) -> Self {
+        return PrefixedStream {
+            inner,
+            prefix,
+            prefix_offset: 0,
+        };
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for PrefixedStream<S> {
+    fn poll_read(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        // if the prefix has been read completely, we shall read from the inner stream...
+        if this.prefix_offset == this.prefix.len() {
+            return Pin::new(&mut this.inner).poll_read(context, buffer);
+        }
+
+        let remaining_prefix = &this.prefix[this.prefix_offset..];
+        let length = remaining_prefix.len().min(buffer.remaining());
+
+        buffer.put_slice(&remaining_prefix[..length]);
+        this.prefix_offset = this.prefix_offset + length;
+
+        return Poll::Ready(Ok(()));
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for PrefixedStream<S> {
+    fn poll_write(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &[u8]) -> Poll<io::Result<usize>> {
+        return Pin::new(&mut self.get_mut().inner).poll_write(context, buffer);
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_flush(context);
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_shutdown(context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_upgrade_request() -> hyper::http::request::Builder {
+        return Request::builder()
+            .uri("/path?query=1")
+            .header("host", "example.com")
+            .header("connection", "Upgrade, HTTP2-Settings")
+            .header("upgrade", "h2c")
+            .header("http2-settings", "AAMAAABkAAQAoAAAAAIAAAAA");
+    }
+
+    // decodes header blocks produced by encode_header_block only...
+    fn decode_header_block(header_block: &[u8]) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = Vec::new();
+        let mut offset = 0;
+
+        fn decode_string(header_block: &[u8], offset: &mut usize) -> String {
+            let mut length = (header_block[*offset] & 0x7f) as usize;
+            *offset = *offset + 1;
+
+            if length == 0x7f {
+                let mut shift = 0;
+
+                loop {
+                    let byte = header_block[*offset];
+                    *offset = *offset + 1;
+                    length = length + (((byte & 0x7f) as usize) << shift);
+                    shift = shift + 7;
+
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                }
+            }
+
+            let value = String::from_utf8(header_block[*offset..*offset + length].to_vec()).unwrap();
+            *offset = *offset + length;
+
+            return value;
+        }
+
+        while offset < header_block.len() {
+            assert_eq!(header_block[offset], 0x00);
+            offset = offset + 1;
+
+            let name = decode_string(header_block, &mut offset);
+            let value = decode_string(header_block, &mut offset);
+
+            headers.push((name, value));
+        }
+
+        return headers;
+    }
+
+    #[test]
+    fn detects_upgrade_requests() {
+        assert!(is_upgrade_request(&create_upgrade_request().body(Body::empty()).unwrap()));
+        assert!(is_upgrade_request(&create_upgrade_request()
+            .header("content-length", "0").body(Body::empty()).unwrap()));
+
+        // requests with a body are answered over HTTP/1.1...
+        assert!(!is_upgrade_request(&create_upgrade_request()
+            .header("content-length", "5").body(Body::from("hello")).unwrap()));
+        assert!(!is_upgrade_request(&create_upgrade_request()
+            .header("transfer-encoding", "chunked").body(Body::empty()).unwrap()));
+
+        // the HTTP2-Settings header must be present exactly once...
+        let mut request = create_upgrade_request().body(Body::empty()).unwrap();
+        request.headers_mut().remove("http2-settings");
+        assert!(!is_upgrade_request(&request));
+
+        let request = create_upgrade_request()
+            .header("http2-settings", "AAMAAABk").body(Body::empty()).unwrap();
+        assert!(!is_upgrade_request(&request));
+
+        // and nominated by the connection header...
+        let mut request = create_upgrade_request().body(Body::empty()).unwrap();
+        request.headers_mut().insert("connection", HeaderValue::from_static("Upgrade"));
+        assert!(!is_upgrade_request(&request));
+
+        let mut request = create_upgrade_request().body(Body::empty()).unwrap();
+        request.headers_mut().insert("upgrade", HeaderValue::from_static("websocket"));
+        assert!(!is_upgrade_request(&request));
+
+        let request = create_upgrade_request().version(Version::HTTP_10).body(Body::empty()).unwrap();
+        assert!(!is_upgrade_request(&request));
+    }
+
+    #[test]
+    fn encodes_integers_as_specified_by_hpack() {
+        let mut buffer: Vec<u8> = Vec::new();
+
+        // examples from RFC 7541, appendix C.1...
+        encode_integer(10, 5, 0x00, &mut buffer);
+        assert_eq!(buffer, vec![0x0a]);
+
+        buffer.clear();
+        encode_integer(1337, 5, 0x00, &mut buffer);
+        assert_eq!(buffer, vec![0x1f, 0x9a, 0x0a]);
+
+        buffer.clear();
+        encode_integer(42, 8, 0x00, &mut buffer);
+        assert_eq!(buffer, vec![0x2a]);
+    }
+
+    #[test]
+    fn encodes_request_as_stream_one() {
+        let request = create_upgrade_request()
+            .method("HEAD")
+            .header("accept", "text/plain")
+            .header("te", "gzip")
+            .body(Body::empty())
+            .unwrap();
+        let frames = encode_request_frames(&request);
+        let payload_length = u32::from_be_bytes([0, frames[0], frames[1], frames[2]]) as usize;
+
+        assert_eq!(frames.len(), FRAME_HEADER_LENGTH + payload_length);
+        assert_eq!(frames[3], FRAME_TYPE_HEADERS);
+        assert_eq!(frames[4], FRAME_FLAG_END_STREAM | FRAME_FLAG_END_HEADERS);
+        assert_eq!(u32::from_be_bytes([frames[5], frames[6], frames[7], frames[8]]), 1);
+
+        let headers = decode_header_block(&frames[FRAME_HEADER_LENGTH..]);
+        let expected_headers: Vec<(String, String)> = [
+            (":method", "HEAD"),
+            (":scheme", "http"),
+            (":authority", "example.com"),
+            (":path", "/path?query=1"),
+            ("accept", "text/plain"),
+        ].iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+
+        // connection-specific headers are left out...
+        assert_eq!(headers, expected_headers);
+    }
+
+    #[test]
+    fn continues_large_header_blocks() {
+        let request = create_upgrade_request()
+            .header("x-large", "a".repeat(DEFAULT_MAXIMUM_FRAME_SIZE))
+            .body(Body::empty())
+            .unwrap();
+        let frames = encode_request_frames(&request);
+        let first_payload_length = u32::from_be_bytes([0, frames[0], frames[1], frames[2]]) as usize;
+
+        assert_eq!(first_payload_length, DEFAULT_MAXIMUM_FRAME_SIZE);
+        assert_eq!(frames[3], FRAME_TYPE_HEADERS);
+        assert_eq!(frames[4], FRAME_FLAG_END_STREAM);
+
+        let continuation = &frames[FRAME_HEADER_LENGTH + first_payload_length..];
+        let continuation_payload_length = u32::from_be_bytes([0, continuation[0], continuation[1], continuation[2]]) as usize;
+
+        assert_eq!(continuation.len(), FRAME_HEADER_LENGTH + continuation_payload_length);
+        assert_eq!(continuation[3], FRAME_TYPE_CONTINUATION);
+        assert_eq!(continuation[4], FRAME_FLAG_END_HEADERS);
+
+        let mut header_block = frames[FRAME_HEADER_LENGTH..FRAME_HEADER_LENGTH + first_payload_length].to_vec();
+        header_block.extend_from_slice(&continuation[FRAME_HEADER_LENGTH..]);
+
+        assert_eq!(decode_header_block(&header_block).last().unwrap().1.len(), DEFAULT_MAXIMUM_FRAME_SIZE);
+    }
+
+    #[tokio::test]
+    async fn reads_client_preface_and_prefixes_stream() {
+        let mut input: Vec<u8> = CLIENT_CONNECTION_PREFACE.to_vec();
+
+        write_frame(FRAME_TYPE_SETTINGS, 0x0, 0, &[0x00, 0x03, 0x00, 0x00, 0x00, 0x64], &mut input);
+        input.extend_from_slice(b"rest");
+
+        let mut stream: &[u8] = &input;
+        let client_preface = read_client_preface_async(&mut stream).await.unwrap();
+
+        assert_eq!(client_preface, input[..input.len() - 4]);
+
+        let mut prefixed_stream = PrefixedStream::new(stream, b"frames".to_vec());
+        let mut output: Vec<u8> = Vec::new();
+
+        prefixed_stream.read_to_end(&mut output).await.unwrap();
+        assert_eq!(output, b"framesrest");
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_client_preface() {
+        let mut stream: &[u8] = b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+
+        assert!(read_client_preface_async(&mut stream).await.is_err());
+
+        let mut input: Vec<u8> = CLIENT_CONNECTION_PREFACE.to_vec();
+
+        write_frame(FRAME_TYPE_HEADERS, 0x0, 1, &[], &mut input);
+
+        let mut stream: &[u8] = &input;
+
+        assert!(read_client_preface_async(&mut stream).await.is_err());
+    }
+}
@@ -0,0 +1,118 @@
+#![allow(clippy::needless_return)]
+
+use serde::Serialize;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::watch::Receiver;
+
+const READY_STATUS: &str = "ready";
+const NOT_READY_STATUS: &str = "notReady";
+
+struct HealthState {
+    is_draining: bool,
+    // none means no ping has been answered yet...
+    last_ping_answered_at_option: Option<Instant>,
+    last_ping_round_trip_option: Option<Duration>,
+    is_last_ping_answered: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcHealth {
+    pub is_connected: bool,
+    pub is_ping_enabled: bool,
+    pub is_responsive: bool,
+    pub last_ping_round_trip_in_milliseconds: Option<f64>,
+    pub milliseconds_since_last_answered_ping: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Readiness {
+    pub status: &'static str,
+    #[serde(skip)]
+    pub is_ready: bool,
+    pub is_draining: bool,
+    pub ipc: IpcHealth,
+}
+
+#[derive(Clone)]
+pub struct HealthMonitor {
+    // zero means the parent process is never pinged...
+    ping_interval_in_milliseconds: u64,
+    ping_timeout_in_milliseconds: u64,
+    state_arc: Arc<Mutex<HealthState>>,
+}
+
+impl HealthMonitor {
+    pub fn new(ping_interval_in_milliseconds: u64, ping_timeout_in_milliseconds: u64) -> Self {
+        let state = HealthState {
+            is_draining: false,
+            last_ping_answered_at_option: None,
+            last_ping_round_trip_option: None,
+            is_last_ping_answered: false,
+        };
+
+        return HealthMonitor {
+            ping_interval_in_milliseconds,
+            ping_timeout_in_milliseconds,
+            state_arc: Arc::new(Mutex::new(state)),
+        };
+    }
+
+    pub fn is_ping_enabled(&self) -> bool {
+        return self.ping_interval_in_milliseconds > 0;
+    }
+
+    pub fn get_ping_interval(&self) -> Duration {
+        return Duration::from_millis(self.ping_interval_in_milliseconds);
+    }
+
+    pub fn get_ping_timeout(&self) -> Duration {
+        return Duration::from_millis(self.ping_timeout_in_milliseconds);
+    }
+
+    pub fn begin_draining(&self) {
+        let mut state = self.state_arc.lock().unwrap();
+
+        state.is_draining = true;
+    }
+
+    // none means the ping was not answered within the timeout...
+    pub fn record_ping(&self, round_trip_option: Option<Duration>) {
+        let mut state = self.state_arc.lock().unwrap();
+
+        state.is_last_ping_answered = round_trip_option.is_some();
+
+        if round_trip_option.is_some() {
+            state.last_ping_answered_at_option = Some(Instant::now());
+            state.last_ping_round_trip_option = round_trip_option;
+        }
+    }
+
+    pub fn get_readiness(&self, receiver: &Receiver<(u64, String)>) -> Readiness {
+        let state = self.state_arc.lock().unwrap();
+        // the IPC thread drops the sender once the standard input is closed...
+        let is_connected = receiver.has_changed().is_ok();
+        // if pings are disabled, an open channel is assumed to be responsive...
+        let is_responsive = is_connected && (!self.is_ping_enabled() || state.is_last_ping_answered);
+        let is_ready = is_responsive && !state.is_draining;
+
+        return Readiness {
+            status: if is_ready { READY_STATUS } else { NOT_READY_STATUS },
+            is_ready,
+            is_draining: state.is_draining,
+            ipc: IpcHealth {
+                is_connected,
+                is_ping_enabled: self.is_ping_enabled(),
+                is_responsive,
+                last_ping_round_trip_in_milliseconds: state.last_ping_round_trip_option
+                    .map(|round_trip| round_trip.as_secs_f64() * 1000.0),
+                milliseconds_since_last_answered_ping: state.last_ping_answered_at_option
+                    .map(|answered_at| answered_at.elapsed().as_millis() as u64),
+            },
+        };
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero, clippy::assign_op_pattern)]
+
 use hyper::{
     body::HttpBody,
     http::HeaderValue,
@@ -6,43 +8,102 @@ use hyper::{
     HeaderMap,
     Request,
     Response,
+    StatusCode,
 };
 use rustls::{Certificate, PrivateKey};
 use serde::{Deserialize, Serialize};
 use serde_json::{to_string, Value};
 use std::{
     collections::HashMap,
+    fmt,
+    future::Future,
     io,
     net::{AddrParseError, SocketAddr},
     str::FromStr,
     borrow::BorrowMut,
     sync::Arc,
 };
+use tokio::time::{timeout, Duration};
 use tokio_rustls::TlsAcceptor;
 // use tokio::{io::{AsyncRead, AsyncWrite}, sync::watch::Receiver};
-use urlencoding::decode;
+use log::{debug, error, warn};
 
 use crate::{
     file_utilities,
+    forwarding_utilities,
     http_server_configuration::HttpServerConfiguration,
+    remote_address::{PeerCredentials, RemoteAddress},
+    request_limit_utilities,
+    request_id_utilities,
+    raw_header_recorder,
+    router,
+    tracer,
+    cookie_utilities::{self, CookieSealer, SerializableCookie},
+    url_encoded_utilities::{self, UrlEncodedParser},
 };
 
 const BOUNDARY_MARKER: &str = "boundary=";
 const BOUNDARY_MARKER_LENGTH: usize = BOUNDARY_MARKER.len();
+const ALTERNATIVE_SERVICE_HEADER_NAME: &str = "alt-svc";
+const COOKIE_HEADER_NAME: &str = "cookie";
+const SET_COOKIE_HEADER_NAME: &str = "set-cookie";
+// these headers are only meaningful for HTTP/1.x and must not be sent over HTTP/2 or HTTP/3...
+pub const CONNECTION_SPECIFIC_HEADER_NAMES: [&str; 5] = [
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "upgrade",
+];
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SerializableHttpRequest {
+    // correlates the request with its reply over IPC...
     pub request_id: u64,
-    pub remote_ip_address: String,
-    pub remote_port: i32,
+    // globally unique ID of the request (e.g. X-Request-Id)...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    // W3C trace context the parent process shall continue...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub traceparent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracestate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_port: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_credentials: Option<PeerCredentials>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_port: Option<i32>,
+    pub scheme: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    pub protocol_version: String,
     pub method: String,
     pub path: String,
+    // parameters of the matched route (e.g. "id" of "/users/:id"), if any...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_parameters: Option<HashMap<String, String>>,
     pub queries: HashMap<String, Vec<String>>,
+    // only present if the extended URL encoded parser is configured...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nested_queries: Option<Value>,
     pub headers: HashMap<String, Vec<String>>,
+    // only present if raw headers are preserved (HTTP/1 only)...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_headers: Option<Vec<String>>,
+    pub cookies: HashMap<String, String>,
+    // cookies whose signature (or encryption) has been verified...
+    pub signed_cookies: HashMap<String, String>,
     pub body_as_text: String,
     pub body: Value,
     pub url_encoded_from_data: HashMap<String, Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nested_url_encoded_form_data: Option<Value>,
 }
 
 impl SerializableHttpRequest {
@@ -52,7 +113,7 @@ impl SerializableHttpRequest {
         if result.is_err() {
             let error = result.unwrap_err();
 
-            eprintln!(
+            error!(
                 "An error occurred while serializing the HTTP request: {}",
                 error
             );
@@ -63,8 +124,11 @@ impl SerializableHttpRequest {
         return result.unwrap();
     }
 
-    pub fn to_string(&self) -> String {
-        return self.to_json();
+}
+
+impl fmt::Display for SerializableHttpRequest {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return formatter.write_str(self.to_json().as_str());
     }
 }
 
@@ -74,6 +138,9 @@ pub struct SerializableHttpResponse {
     pub request_id: u64,
     pub status_code: u16,
     pub headers: HashMap<String, Vec<String>>,
+    // rendered into Set-Cookie headers...
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cookies: Vec<SerializableCookie>,
     pub body: Value,
 }
 
@@ -84,7 +151,7 @@ impl SerializableHttpResponse {
         if deserialization_result.is_err() {
             let error = deserialization_result.unwrap_err();
 
-            eprintln!(
+            error!(
                 "An error occurred while deserializing into HTTP response: {}",
                 error
             );
@@ -95,7 +162,7 @@ impl SerializableHttpResponse {
         return Some(deserialization_result.unwrap());
     }
 
-    pub fn to_response(&self) -> Response<Body> {
+    pub fn to_response(&self, cookie_sealer: &CookieSealer) -> Response<Body> {
         let headers = self.headers.iter();
         let mut response = Response::builder().status(self.status_code);
 
@@ -105,6 +172,14 @@ impl SerializableHttpResponse {
             }
         }
 
+        for cookie in self.cookies.iter() {
+            let set_cookie_option = cookie_sealer.to_set_cookie(cookie);
+
+            if set_cookie_option.is_some() {
+                response = response.header(SET_COOKIE_HEADER_NAME, set_cookie_option.unwrap());
+            }
+        }
+
         let body_as_json = self.body.to_string();
         let body = Body::from(body_as_json);
 
@@ -114,13 +189,19 @@ impl SerializableHttpResponse {
 
 pub fn create_socket_address(host: String, port: u16) -> Result<SocketAddr, AddrParseError> {
     let mut socket_address_as_string: String = host;
-    socket_address_as_string.push_str(":");
+    socket_address_as_string.push(':');
     socket_address_as_string.push_str(port.to_string().as_str());
     let socket_address_result = SocketAddr::from_str(&socket_address_as_string);
 
     return socket_address_result;
 }
 
+// every byte becomes the character with the same code point, hence any header value
+// (e.g. obs-text) is carried losslessly. Node.js decodes header values the same way...
+pub fn to_latin1_string(bytes: &[u8]) -> String {
+    return bytes.iter().map(|byte| *byte as char).collect();
+}
+
 pub async fn to_serializable_header_map(
     header_map: &HeaderMap<HeaderValue>,
 ) -> HashMap<String, Vec<String>> {
@@ -133,7 +214,7 @@ pub async fn to_serializable_header_map(
         let mut header_values: Vec<String> = Vec::new();
 
         for header_value in header_values_iterator {
-            let header_value = header_value.to_str().unwrap().to_owned();
+            let header_value = to_latin1_string(header_value.as_bytes());
 
             header_values.push(header_value);
         }
@@ -147,31 +228,16 @@ pub async fn to_serializable_header_map(
 pub async fn parse_url_encoded_string_async(
     url_encoded_string: &str,
 ) -> HashMap<String, Vec<String>> {
-    let mut url_encoded_data_map: HashMap<String, Vec<String>> = HashMap::new();
-
-    if url_encoded_string.len() == 0 {
-        return url_encoded_data_map;
-    }
+    return url_encoded_utilities::parse(url_encoded_string);
+}
 
-    let splitted_url_encoded_string = url_encoded_string.split("&");
-
-    for url_encoded_string in splitted_url_encoded_string {
-        let splitted_data: Vec<&str> = url_encoded_string.split("=").collect();
-        let key = decode(splitted_data[0]).unwrap().into_owned(); // splitted_query[0].to_owned();
-        let value = decode(splitted_data[1]).unwrap().into_owned(); // splitted_query[1].to_owned();
-        let values_option = url_encoded_data_map.get_mut(&key);
-
-        if values_option.is_none() {
-            let mut values: Vec<String> = Vec::new();
-            values.push(value);
-            url_encoded_data_map.insert(key, values);
-        } else {
-            let values = values_option.unwrap();
-            values.push(value);
-        }
+// nested data is only produced if the extended parser is configured...
+fn parse_nested_url_encoded_string(url_encoded_string: &str, configuration: &HttpServerConfiguration) -> Option<Value> {
+    if configuration.url_encoded_parser != UrlEncodedParser::Extended {
+        return None;
     }
 
-    return url_encoded_data_map;
+    return Some(url_encoded_utilities::parse_nested(url_encoded_string));
 }
 
 pub fn get_header_value<'a>(
@@ -195,19 +261,31 @@ pub fn get_header_value<'a>(
     return value_option.unwrap();
 }
 
-pub async fn parse_body_as_text_async(_content_type: &str, body: &mut Body) -> String {
+pub async fn parse_body_as_text_async(
+    _content_type: &str,
+    body: &mut Body,
+    maximum_body_size: u64,
+) -> Result<String, StatusCode> {
     let mut body_as_text = String::from("");
+    let mut total_bytes_read: u64 = 0;
 
     while let Some(chunk) = HttpBody::data(body).await {
         if chunk.is_err() {
             let error = chunk.unwrap_err();
 
-            eprintln!("An error occurred while reading body as text: {}", error);
+            warn!("An error occurred while reading body as text: {}", error);
 
-            return String::from("");
+            return Ok(String::from(""));
         }
 
         let bytes = chunk.unwrap();
+        total_bytes_read = total_bytes_read + bytes.len() as u64;
+
+        // the limit is enforced while streaming so that we never
+        // hold more than the allowed number of bytes in memory...
+        if total_bytes_read > maximum_body_size {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
 
         // println!("Reading {} bytes", bytes.len());
 
@@ -217,12 +295,12 @@ pub async fn parse_body_as_text_async(_content_type: &str, body: &mut Body) -> S
         if bytes_to_string_conversion_result.is_err() {
             let error = bytes_to_string_conversion_result.unwrap_err();
 
-            eprintln!(
+            warn!(
                 "An error occurred while converting bytes to UTF-8 string: {}",
                 error
             );
 
-            return String::from("");
+            return Ok(String::from(""));
         }
 
         let chunk_as_string = bytes_to_string_conversion_result.unwrap();
@@ -230,21 +308,25 @@ pub async fn parse_body_as_text_async(_content_type: &str, body: &mut Body) -> S
         body_as_text.push_str(chunk_as_string.as_str());
     }
 
-    return body_as_text;
+    return Ok(body_as_text);
 }
 
-pub async fn parse_body_as_multipart_form_data_async(content_type: &str, body: &mut Body) {
+pub async fn parse_body_as_multipart_form_data_async(
+    content_type: &str,
+    body: &mut Body,
+    maximum_body_size: u64,
+) -> Result<(), StatusCode> {
     let index_of_boundary_marker_option = content_type.find(BOUNDARY_MARKER);
 
     if index_of_boundary_marker_option.is_none() {
-        return;
+        return Ok(());
     }
 
     let index_of_boundary_marker = index_of_boundary_marker_option.unwrap();
     let boundary = &content_type[index_of_boundary_marker + BOUNDARY_MARKER_LENGTH..];
     let _boundary_as_bytes = boundary.as_bytes();
 
-    println!("{} is the boundary.", boundary);
+    debug!("{} is the boundary.", boundary);
 
     let mut total_bytes_read = 0;
 
@@ -252,16 +334,51 @@ pub async fn parse_body_as_multipart_form_data_async(content_type: &str, body: &
         if chunk.is_err() {
             let error = chunk.unwrap_err();
 
-            eprintln!("An error occurred while reading body as text: {}", error);
+            warn!("An error occurred while reading body as text: {}", error);
 
-            return;
+            return Ok(());
         }
 
         let bytes = chunk.unwrap();
         total_bytes_read = total_bytes_read + bytes.len();
+
+        if total_bytes_read as u64 > maximum_body_size {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
     }
 
-    println!("Total {total_bytes_read} bytes read.");
+    debug!("Total {total_bytes_read} bytes read.");
+
+    return Ok(());
+}
+
+pub fn to_timeout(timeout_in_milliseconds: u64) -> Option<Duration> {
+    // zero means the timeout is disabled...
+    if timeout_in_milliseconds == 0 {
+        return None;
+    }
+
+    return Some(Duration::from_millis(timeout_in_milliseconds));
+}
+
+async fn read_body_with_timeout_async<T, F>(future: F, configuration: &HttpServerConfiguration) -> Result<T, StatusCode>
+where
+    F: Future<Output = Result<T, StatusCode>>,
+{
+    let body_read_timeout_option = to_timeout(configuration.body_read_timeout_in_milliseconds);
+
+    if body_read_timeout_option.is_none() {
+        return future.await;
+    }
+
+    let timeout_result = timeout(body_read_timeout_option.unwrap(), future).await;
+
+    // if the client does not send the body in time...
+    if timeout_result.is_err() {
+        return Err(StatusCode::REQUEST_TIMEOUT);
+    }
+
+    return timeout_result.unwrap();
 }
 
 // pub async fn parse_body_async(content_type: String, body: &mut Body) {
@@ -272,41 +389,65 @@ pub async fn parse_body_as_multipart_form_data_async(content_type: &str, body: &
 
 pub async fn serialize_http_request_async(
     request_id: u64,
-    remote_address: SocketAddr,
+    remote_address: RemoteAddress,
     mut request: impl BorrowMut<Request<Body>>,
-) -> SerializableHttpRequest {
+    configuration: &HttpServerConfiguration,
+) -> Result<SerializableHttpRequest, StatusCode> {
     let request: &mut Request<Body> = request.borrow_mut();
-    let mut remote_ip_address = remote_address.ip().to_string();
-    let remote_port = i32::from(remote_address.port());
+
+    let remote_ip_address = remote_address.get_ip_address();
+    let remote_port = remote_address.get_port().map(i32::from);
+    let peer_credentials = remote_address.get_peer_credentials();
+    let correlation_id = request_id_utilities::get(request);
+    let span_context_option = tracer::get_span_context(request);
+    let traceparent = span_context_option.as_ref().map(|span_context| span_context.to_traceparent());
+    let tracestate = span_context_option.and_then(|span_context| span_context.trace_state);
+    let protocol_version = format!("{:?}", request.version());
     let method = request.method().as_str().to_owned();
     let path = request.uri().path().to_owned();
+    let path_parameters = router::get_path_parameters(request);
     let query_string = if request.uri().query().is_none() {
         ""
     } else {
         request.uri().query().unwrap()
     };
     let queries = parse_url_encoded_string_async(query_string).await;
+    let nested_queries = parse_nested_url_encoded_string(query_string, configuration);
     let headers = to_serializable_header_map(request.headers()).await;
-    let forwarded_for = get_header_value("x-forwarded-for", 0, &headers);
-
-    if forwarded_for.len() > 0 {
-        remote_ip_address = forwarded_for.to_owned();
-    }
+    let raw_headers = raw_header_recorder::get(request);
+    let (cookies, signed_cookies) = cookie_utilities::parse(
+        headers.get(COOKIE_HEADER_NAME).map_or(&[], |cookie_headers| cookie_headers.as_slice()),
+        &configuration.cookie_sealer,
+    );
+    // resolves the client behind trusted proxies (if any)...
+    let forwarded_information = forwarding_utilities::resolve(
+        &remote_address, &headers, &configuration.trusted_proxies, configuration.trusted_proxy_header);
+    let client_ip_address = forwarded_information.client_ip_address;
+    let client_port = forwarded_information.client_port.map(i32::from);
+    let scheme = forwarded_information.scheme.unwrap_or(
+        String::from(if configuration.is_tls_enabled { "https" } else { "http" }));
+    let host = forwarded_information.host.or(forwarding_utilities::get_host(
+        request.uri().authority().map(|authority| authority.as_str()), &headers).map(String::from));
 
     let mut body_as_text = String::from("");
     let mut body: Value = Value::Null;
     let mut url_encoded_form_data: HashMap<String, Vec<String>> = HashMap::new();
+    let mut nested_url_encoded_form_data: Option<Value> = None;
     let content_type = get_header_value("content-type", 0, &headers);
     let is_json_content = content_type.contains("json");
     let is_text_content = content_type.contains("text");
     let is_url_encoded_form_data = "application/x-www-form-urlencoded".eq(content_type);
     let is_multipart_form_data = content_type.starts_with("multipart/form-data");
     let shall_parse_body_as_text = is_json_content || is_text_content || is_url_encoded_form_data;
+    let maximum_body_size = request_limit_utilities::get_maximum_body_size(content_type, configuration);
+
+    request_limit_utilities::check_content_length(request, maximum_body_size)?;
 
     // if content type is any of the text types or JSON types,
     // we shall parse the body as text...
     if shall_parse_body_as_text {
-        body_as_text = parse_body_as_text_async(content_type, request.body_mut()).await;
+        body_as_text = read_body_with_timeout_async(
+            parse_body_as_text_async(content_type, request.body_mut(), maximum_body_size), configuration).await?;
 
         if is_json_content {
             let result = Value::from_str(&body_as_text);
@@ -314,7 +455,7 @@ pub async fn serialize_http_request_async(
             if result.is_err() {
                 let error = result.unwrap_err();
 
-                eprintln!(
+                warn!(
                     "An error occurred while serializing body as JSON: {}",
                     error
                 );
@@ -323,6 +464,7 @@ pub async fn serialize_http_request_async(
             }
         } else if is_url_encoded_form_data {
             url_encoded_form_data = parse_url_encoded_string_async(body_as_text.as_str()).await;
+            nested_url_encoded_form_data = parse_nested_url_encoded_string(body_as_text.as_str(), configuration);
         }
     }
     // else if the content is multipart form data...
@@ -331,26 +473,42 @@ pub async fn serialize_http_request_async(
 
         // println!("{body}");
 
-        parse_body_as_multipart_form_data_async(content_type, request.body_mut()).await;
+        read_body_with_timeout_async(
+            parse_body_as_multipart_form_data_async(content_type, request.body_mut(), maximum_body_size), configuration).await?;
     }
 
     // println!("{content_type}");
 
-    return SerializableHttpRequest {
+    return Ok(SerializableHttpRequest {
         request_id,
+        correlation_id,
+        traceparent,
+        tracestate,
         remote_ip_address,
         remote_port,
+        peer_credentials,
+        client_ip_address,
+        client_port,
+        scheme,
+        host,
+        protocol_version,
         method,
         path,
+        path_parameters,
         queries,
+        nested_queries,
         headers,
+        raw_headers,
+        cookies,
+        signed_cookies,
         body_as_text,
         body,
         url_encoded_from_data: url_encoded_form_data,
-    };
+        nested_url_encoded_form_data,
+    });
 }
 
-fn load_tls_certificate_chain(file_path: String) -> io::Result<Vec<Certificate>> {
+pub fn load_tls_certificate_chain(file_path: String) -> io::Result<Vec<Certificate>> {
     let mut buffered_reader = file_utilities::create_buffered_file_reader(file_path)?;
     let certificates = rustls_pemfile::certs(&mut buffered_reader)?;
 
@@ -366,7 +524,7 @@ fn load_tls_certificate_chain(file_path: String) -> io::Result<Vec<Certificate>>
     return Ok(certificate_chain);
 }
 
-fn load_tls_private_key(file_path: String) -> io::Result<PrivateKey> {
+pub fn load_tls_private_key(file_path: String) -> io::Result<PrivateKey> {
     let mut buffered_reader = file_utilities::create_buffered_file_reader(file_path)?;
     let private_keys = rustls_pemfile::rsa_private_keys(&mut buffered_reader)?;
 
@@ -382,10 +540,80 @@ fn load_tls_private_key(file_path: String) -> io::Result<PrivateKey> {
     return Ok(private_key);
 }
 
+// the TCP (rustls 0.21) and the QUIC (rustls 0.23) listeners both load their
+// certificate chain and private key from here, so that they always serve the same...
+pub fn load_tls_credentials(configuration: &HttpServerConfiguration) -> io::Result<(Vec<Certificate>, PrivateKey)> {
+    let tls_certificate_chain_result = load_tls_certificate_chain(configuration.tls_certificate_path.clone());
+
+    if tls_certificate_chain_result.is_err() {
+        let error = tls_certificate_chain_result.unwrap_err();
+
+        return Err(io::Error::new(error.kind(), format!("Unable to load TLS certificate chain: {}", error)));
+    }
+
+    let tls_private_key_result = load_tls_private_key(configuration.tls_private_key_path.clone());
+
+    if tls_private_key_result.is_err() {
+        let error = tls_private_key_result.unwrap_err();
+
+        return Err(io::Error::new(error.kind(), format!("Unable to load TLS private key: {}", error)));
+    }
+
+    return Ok((tls_certificate_chain_result.unwrap(), tls_private_key_result.unwrap()));
+}
+
+pub fn add_alternative_service_header(response: &mut Response<Body>, configuration: &HttpServerConfiguration) {
+    // if the parent process has already advertised alternative services...
+    if response.headers().contains_key(ALTERNATIVE_SERVICE_HEADER_NAME) {
+        // we shall not override those...
+        return;
+    }
+
+    let alternative_service = format!(
+        "h3=\":{}\"; ma={}",
+        configuration.http3_port,
+        configuration.http3_alternative_service_max_age_in_seconds,
+    );
+    let header_value_result = HeaderValue::from_str(&alternative_service);
+
+    if header_value_result.is_err() {
+        let error = header_value_result.unwrap_err();
+
+        error!("An error occurred while creating Alt-Svc header: {}", error);
+
+        return;
+    }
+
+    response.headers_mut().insert(ALTERNATIVE_SERVICE_HEADER_NAME, header_value_result.unwrap());
+}
+
 pub fn create_http(configuration: &HttpServerConfiguration) -> Http {
     // let configuration = configuration.clone();
     let mut http = Http::new();
 
+    // HTTP/2 rejects oversized header lists before they reach us...
+    http.http2_max_header_list_size(configuration.maximum_header_size_in_bytes as u32);
+    // HTTP/1 responds with 431 once the head outgrows the read buffer, so a client can't
+    // make us buffer more than the configured limits allow. hyper itself never parses more
+    // than 100 headers...
+    http.max_buf_size(request_limit_utilities::get_maximum_head_size(configuration));
+
+    let header_read_timeout_option = to_timeout(configuration.header_read_timeout_in_milliseconds);
+
+    // slow clients (e.g. slowloris) shall not keep the connection open forever...
+    if header_read_timeout_option.is_some() {
+        http.http1_header_read_timeout(header_read_timeout_option.unwrap());
+    }
+
+    // pings detect dead peers on otherwise idle HTTP/2 connections...
+    http.http2_keep_alive_interval(to_timeout(configuration.http2_keep_alive_interval_in_milliseconds));
+
+    let http2_keep_alive_timeout_option = to_timeout(configuration.http2_keep_alive_timeout_in_milliseconds);
+
+    if http2_keep_alive_timeout_option.is_some() {
+        http.http2_keep_alive_timeout(http2_keep_alive_timeout_option.unwrap());
+    }
+
     // if HTTP/2 is not enabled...
     if !configuration.is_http2_enabled {
         // we shall set HTTP/1 only to true...
@@ -404,32 +632,17 @@ pub fn create_tls_acceptor(configuration: &HttpServerConfiguration) -> Option<Tl
         return None;
     }
 
-    let tls_certificate_chain_result =
-        load_tls_certificate_chain(configuration.tls_certificate_path);
+    let tls_credentials_result = load_tls_credentials(&configuration);
 
-    if tls_certificate_chain_result.is_err() {
-        let error = tls_certificate_chain_result.unwrap_err();
-
-        eprintln!(
-            "An error occurred while loading TLS certificate chain: {}",
-            error
-        );
-
-        return None;
-    }
-
-    let tls_private_key_result = load_tls_private_key(configuration.tls_private_key_path);
-
-    if tls_private_key_result.is_err() {
-        let error = tls_private_key_result.unwrap_err();
+    if tls_credentials_result.is_err() {
+        let error = tls_credentials_result.unwrap_err();
 
-        eprintln!("An error occurred while loading TLS private key: {}", error);
+        error!("An error occurred while loading TLS credentials: {}", error);
 
         return None;
     }
 
-    let tls_certificate_chain = tls_certificate_chain_result.unwrap();
-    let tls_private_key = tls_private_key_result.unwrap();
+    let (tls_certificate_chain, tls_private_key) = tls_credentials_result.unwrap();
     let tls_server_configuration_result = rustls::ServerConfig::builder()
         .with_safe_defaults()
         .with_no_client_auth()
@@ -438,7 +651,7 @@ pub fn create_tls_acceptor(configuration: &HttpServerConfiguration) -> Option<Tl
     if tls_server_configuration_result.is_err() {
         let error = tls_server_configuration_result.unwrap_err();
 
-        eprintln!(
+        error!(
             "An error occurred while creating TLS server configuration: {}",
             error
         );
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 use std::sync::{Arc, Mutex};
 
 const ZERO: u64 = 0_u64;
@@ -0,0 +1,297 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero, clippy::assign_op_pattern)]
+
+use hyper::{Method, StatusCode};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::connection_limiter::ConnectionStatistics;
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+// upper bounds in seconds...
+const DURATION_BUCKETS: [f64; 12] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+// methods outside this list are reported as "OTHER" so that
+// clients cannot create an unbounded number of series...
+const KNOWN_METHODS: [Method; 9] = [
+    Method::GET, Method::HEAD, Method::POST, Method::PUT, Method::DELETE,
+    Method::CONNECT, Method::OPTIONS, Method::TRACE, Method::PATCH,
+];
+
+#[derive(Clone)]
+struct Histogram {
+    // cumulative counts are computed when rendering...
+    bucket_counts: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+struct MetricsState {
+    request_durations: Mutex<BTreeMap<(String, u16), Histogram>>,
+    ipc_round_trip_durations: Mutex<Histogram>,
+    in_flight_request_count: AtomicI64,
+    pending_reply_count: AtomicI64,
+    web_socket_session_count: AtomicI64,
+    tls_handshake_failure_count: AtomicU64,
+    received_byte_count: AtomicU64,
+    sent_byte_count: AtomicU64,
+}
+
+#[derive(Clone)]
+pub struct Metrics {
+    state_arc: Arc<MetricsState>,
+}
+
+// decrements the corresponding gauge when dropped...
+pub struct GaugeGuard {
+    state_arc: Arc<MetricsState>,
+    select_gauge: fn(&MetricsState) -> &AtomicI64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        return Histogram {
+            bucket_counts: [0; DURATION_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        };
+    }
+
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let index_of_bucket_option = DURATION_BUCKETS.iter().position(|upper_bound| seconds <= *upper_bound);
+
+        // observations above the largest bucket only count towards "+Inf"...
+        if index_of_bucket_option.is_some() {
+            let index_of_bucket = index_of_bucket_option.unwrap();
+
+            self.bucket_counts[index_of_bucket] = self.bucket_counts[index_of_bucket] + 1;
+        }
+
+        self.sum = self.sum + seconds;
+        self.count = self.count + 1;
+    }
+
+    fn render(&self, output: &mut String, name: &str, labels: &str) {
+        let separator = if labels.len() == 0 { "" } else { "," };
+        let mut cumulative_count = 0_u64;
+
+        for (index, upper_bound) in DURATION_BUCKETS.iter().enumerate() {
+            cumulative_count = cumulative_count + self.bucket_counts[index];
+
+            _ = writeln!(output, "{}_bucket{{{}{}le=\"{}\"}} {}", name, labels, separator, upper_bound, cumulative_count);
+        }
+
+        _ = writeln!(output, "{}_bucket{{{}{}le=\"+Inf\"}} {}", name, labels, separator, self.count);
+
+        let labels = if labels.len() == 0 { String::new() } else { format!("{{{}}}", labels) };
+
+        _ = writeln!(output, "{}_sum{} {}", name, labels, self.sum);
+        _ = writeln!(output, "{}_count{} {}", name, labels, self.count);
+    }
+}
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        (self.select_gauge)(&self.state_arc).fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn write_header(output: &mut String, name: &str, metric_type: &str, help: &str) {
+    _ = writeln!(output, "# HELP {} {}", name, help);
+    _ = writeln!(output, "# TYPE {} {}", name, metric_type);
+}
+
+fn write_sample(output: &mut String, name: &str, metric_type: &str, help: &str, value: impl std::fmt::Display) {
+    write_header(output, name, metric_type, help);
+
+    _ = writeln!(output, "{} {}", name, value);
+}
+
+fn get_resident_memory_size_in_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    // e.g. "VmRSS:     12345 kB"...
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let size_in_kilobytes = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+
+    return Some(size_in_kilobytes * 1024);
+}
+
+fn get_open_file_descriptor_count() -> Option<usize> {
+    return fs::read_dir("/proc/self/fd").ok().map(|entries| entries.count());
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let state = MetricsState {
+            request_durations: Mutex::new(BTreeMap::new()),
+            ipc_round_trip_durations: Mutex::new(Histogram::new()),
+            in_flight_request_count: AtomicI64::new(0),
+            pending_reply_count: AtomicI64::new(0),
+            web_socket_session_count: AtomicI64::new(0),
+            tls_handshake_failure_count: AtomicU64::new(0),
+            received_byte_count: AtomicU64::new(0),
+            sent_byte_count: AtomicU64::new(0),
+        };
+
+        return Metrics { state_arc: Arc::new(state) };
+    }
+
+    fn increment(&self, select_gauge: fn(&MetricsState) -> &AtomicI64) -> GaugeGuard {
+        select_gauge(&self.state_arc).fetch_add(1, Ordering::Relaxed);
+
+        return GaugeGuard { state_arc: self.state_arc.clone(), select_gauge };
+    }
+
+    pub fn begin_request(&self) -> GaugeGuard {
+        return self.increment(|state| &state.in_flight_request_count);
+    }
+
+    pub fn begin_waiting_for_reply(&self) -> GaugeGuard {
+        return self.increment(|state| &state.pending_reply_count);
+    }
+
+    pub fn begin_web_socket_session(&self) -> GaugeGuard {
+        return self.increment(|state| &state.web_socket_session_count);
+    }
+
+    pub fn record_request(&self, method: &Method, status_code: StatusCode, duration: Duration) {
+        let method = if KNOWN_METHODS.contains(method) { method.as_str() } else { "OTHER" };
+        let mut request_durations = self.state_arc.request_durations.lock().unwrap();
+
+        request_durations
+            .entry((method.to_string(), status_code.as_u16()))
+            .or_insert_with(Histogram::new)
+            .observe(duration);
+    }
+
+    pub fn record_ipc_round_trip(&self, duration: Duration) {
+        self.state_arc.ipc_round_trip_durations.lock().unwrap().observe(duration);
+    }
+
+    pub fn record_tls_handshake_failure(&self) {
+        self.state_arc.tls_handshake_failure_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_transferred_bytes(&self, received_byte_count: u64, sent_byte_count: u64) {
+        self.state_arc.received_byte_count.fetch_add(received_byte_count, Ordering::Relaxed);
+        self.state_arc.sent_byte_count.fetch_add(sent_byte_count, Ordering::Relaxed);
+    }
+
+    // renders all metrics in Prometheus text exposition format...
+    pub fn render(&self, connection_statistics: &ConnectionStatistics) -> String {
+        let state = &self.state_arc;
+        let mut output = String::new();
+        // the lock is released before the other metrics are rendered...
+        let request_durations = state.request_durations.lock().unwrap().clone();
+
+        write_header(&mut output, "volcano_http_requests_total", "counter", "Total number of HTTP requests by method and status code.");
+
+        for ((method, status_code), histogram) in request_durations.iter() {
+            _ = writeln!(output, "volcano_http_requests_total{{method=\"{}\",status=\"{}\"}} {}", method, status_code, histogram.count);
+        }
+
+        write_header(&mut output, "volcano_http_request_duration_seconds", "histogram", "HTTP request latency by method and status code.");
+
+        for ((method, status_code), histogram) in request_durations.iter() {
+            let labels = format!("method=\"{}\",status=\"{}\"", method, status_code);
+
+            histogram.render(&mut output, "volcano_http_request_duration_seconds", labels.as_str());
+        }
+
+        write_sample(&mut output, "volcano_http_requests_in_flight", "gauge",
+            "Number of HTTP requests currently being handled.", state.in_flight_request_count.load(Ordering::Relaxed));
+        write_header(&mut output, "volcano_ipc_round_trip_duration_seconds", "histogram",
+            "Time between forwarding a request to the parent process and receiving its reply.");
+        state.ipc_round_trip_durations.lock().unwrap().render(&mut output, "volcano_ipc_round_trip_duration_seconds", "");
+        write_sample(&mut output, "volcano_ipc_pending_replies", "gauge",
+            "Number of requests waiting for a reply from the parent process.", state.pending_reply_count.load(Ordering::Relaxed));
+        write_sample(&mut output, "volcano_connections_active", "gauge",
+            "Number of open client connections.", connection_statistics.active_connection_count);
+        write_sample(&mut output, "volcano_connections_queued", "gauge",
+            "Number of connections waiting for a free slot.", connection_statistics.queued_connection_count);
+        write_sample(&mut output, "volcano_connections_rejected_total", "counter",
+            "Total number of connections rejected due to connection limits.", connection_statistics.rejected_connection_count);
+        write_sample(&mut output, "volcano_web_socket_sessions_active", "gauge",
+            "Number of open WebSocket sessions.", state.web_socket_session_count.load(Ordering::Relaxed));
+        write_sample(&mut output, "volcano_tls_handshake_failures_total", "counter",
+            "Total number of failed or timed out TLS handshakes.", state.tls_handshake_failure_count.load(Ordering::Relaxed));
+        write_sample(&mut output, "volcano_received_bytes_total", "counter",
+            "Total number of bytes received from clients.", state.received_byte_count.load(Ordering::Relaxed));
+        write_sample(&mut output, "volcano_sent_bytes_total", "counter",
+            "Total number of bytes sent to clients.", state.sent_byte_count.load(Ordering::Relaxed));
+
+        let resident_memory_size_option = get_resident_memory_size_in_bytes();
+
+        if resident_memory_size_option.is_some() {
+            write_sample(&mut output, "process_resident_memory_bytes", "gauge",
+                "Resident memory size in bytes.", resident_memory_size_option.unwrap());
+        }
+
+        let open_file_descriptor_count_option = get_open_file_descriptor_count();
+
+        if open_file_descriptor_count_option.is_some() {
+            write_sample(&mut output, "process_open_fds", "gauge",
+                "Number of open file descriptors.", open_file_descriptor_count_option.unwrap());
+        }
+
+        return output;
+    }
+}
+
+// counts the bytes transferred over a connection...
+pub struct MeteredStream<S> {
+    inner: S,
+    metrics: Metrics,
+}
+
+impl<S> MeteredStream<S> {
+    pub fn new(inner: S, metrics: Metrics) -> Self {
+        return MeteredStream { inner, metrics };
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for MeteredStream<S> {
+    fn poll_read(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled_length = buffer.filled().len();
+        let poll = Pin::new(&mut this.inner).poll_read(context, buffer);
+
+        if let Poll::Ready(Ok(())) = poll {
+            this.metrics.record_transferred_bytes((buffer.filled().len() - filled_length) as u64, 0);
+        }
+
+        return poll;
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for MeteredStream<S> {
+    fn poll_write(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(context, buffer);
+
+        if let Poll::Ready(Ok(bytes_written)) = poll {
+            this.metrics.record_transferred_bytes(0, bytes_written as u64);
+        }
+
+        return poll;
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_flush(context);
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_shutdown(context);
+    }
+}
@@ -0,0 +1,264 @@
+#![allow(clippy::needless_return, clippy::len_zero)]
+
+const VERSION_1_SIGNATURE: &[u8] = b"PROXY ";
+const VERSION_2_SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+// the longest possible version 1 header including CRLF...
+const VERSION_1_MAXIMUM_LENGTH: usize = 107;
+const VERSION_2_HEADER_LENGTH: usize = 16;
+// enough to tell both versions apart...
+const SIGNATURE_PREFIX_LENGTH: usize = 8;
+const VERSION_2_COMMAND_LOCAL: u8 = 0x0;
+const VERSION_2_COMMAND_PROXY: u8 = 0x1;
+const VERSION_2_FAMILY_INET: u8 = 0x1;
+const VERSION_2_FAMILY_INET6: u8 = 0x2;
+const LISTENER_SELECTOR_ALL: &str = "all";
+
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+fn create_invalid_data_error(message: &str) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+}
+
+pub fn parse_listener_selectors(listener_selectors: &str) -> Vec<String> {
+    return listener_selectors
+        .split(',')
+        .map(|selector| selector.trim().to_string())
+        .filter(|selector| selector.len() > 0)
+        .collect();
+}
+
+pub fn is_enabled_for(listener_selectors: &[String], listener_identifiers: &[&str]) -> bool {
+    return listener_selectors.iter().any(|selector| selector.eq(LISTENER_SELECTOR_ALL)
+        || listener_identifiers.contains(&selector.as_str()));
+}
+
+async fn read_version_1_header_async<S>(stream: &mut S, signature_prefix: &[u8]) -> io::Result<Option<SocketAddr>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut header: Vec<u8> = signature_prefix.to_vec();
+
+    // we shall read one byte at a time so that nothing beyond the header is consumed...
+    while !header.ends_with(b"\r\n") {
+        if header.len() >= VERSION_1_MAXIMUM_LENGTH {
+            return Err(create_invalid_data_error("PROXY protocol v1 header is too long."));
+        }
+
+        header.push(stream.read_u8().await?);
+    }
+
+    let header = String::from_utf8(header)
+        .map_err(|_| create_invalid_data_error("PROXY protocol v1 header is not valid ASCII."))?;
+    let fields: Vec<&str> = header.trim_end().split(' ').collect();
+
+    // "PROXY UNKNOWN" means the proxy could not determine the client...
+    if fields.len() >= 2 && fields[1] == "UNKNOWN" {
+        return Ok(None);
+    }
+
+    if fields.len() != 6 || (fields[1] != "TCP4" && fields[1] != "TCP6") {
+        return Err(create_invalid_data_error("PROXY protocol v1 header is malformed."));
+    }
+
+    let source_ip_address = IpAddr::from_str(fields[2])
+        .map_err(|_| create_invalid_data_error("PROXY protocol v1 source address is invalid."))?;
+    let source_port = fields[4].parse::<u16>()
+        .map_err(|_| create_invalid_data_error("PROXY protocol v1 source port is invalid."))?;
+
+    return Ok(Some(SocketAddr::new(source_ip_address, source_port)));
+}
+
+async fn read_version_2_header_async<S>(stream: &mut S, signature_prefix: &[u8]) -> io::Result<Option<SocketAddr>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut header = [0_u8; VERSION_2_HEADER_LENGTH];
+
+    header[..SIGNATURE_PREFIX_LENGTH].copy_from_slice(signature_prefix);
+    stream.read_exact(&mut header[SIGNATURE_PREFIX_LENGTH..]).await?;
+
+    if &header[..VERSION_2_SIGNATURE.len()] != VERSION_2_SIGNATURE {
+        return Err(create_invalid_data_error("PROXY protocol v2 signature is invalid."));
+    }
+
+    let version = header[12] >> 4;
+    let command = header[12] & 0x0F;
+    let family = header[13] >> 4;
+    let length = u16::from_be_bytes([header[14], header[15]]) as usize;
+
+    if version != 2 {
+        return Err(create_invalid_data_error("PROXY protocol version is not supported."));
+    }
+
+    // addresses and TLVs must be consumed even if we do not use them...
+    let mut addresses = vec![0_u8; length];
+
+    stream.read_exact(&mut addresses).await?;
+
+    // health checks from the proxy itself carry no client address...
+    if command == VERSION_2_COMMAND_LOCAL {
+        return Ok(None);
+    }
+
+    if command != VERSION_2_COMMAND_PROXY {
+        return Err(create_invalid_data_error("PROXY protocol v2 command is not supported."));
+    }
+
+    return match family {
+        VERSION_2_FAMILY_INET if length >= 12 => {
+            let source_ip_address = Ipv4Addr::new(addresses[0], addresses[1], addresses[2], addresses[3]);
+            let source_port = u16::from_be_bytes([addresses[8], addresses[9]]);
+
+            Ok(Some(SocketAddr::new(IpAddr::V4(source_ip_address), source_port)))
+        },
+        VERSION_2_FAMILY_INET6 if length >= 36 => {
+            let mut source_ip_address = [0_u8; 16];
+
+            source_ip_address.copy_from_slice(&addresses[..16]);
+
+            let source_port = u16::from_be_bytes([addresses[32], addresses[33]]);
+
+            Ok(Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::from(source_ip_address)), source_port)))
+        },
+        // unspecified or Unix domain socket addresses are not useful to us...
+        _ => Ok(None),
+    };
+}
+
+pub async fn read_header_async<S>(stream: &mut S) -> io::Result<Option<SocketAddr>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut signature_prefix = [0_u8; SIGNATURE_PREFIX_LENGTH];
+
+    stream.read_exact(&mut signature_prefix).await?;
+
+    if signature_prefix.starts_with(VERSION_1_SIGNATURE) {
+        return read_version_1_header_async(stream, &signature_prefix).await;
+    }
+
+    if VERSION_2_SIGNATURE.starts_with(&signature_prefix) {
+        return read_version_2_header_async(stream, &signature_prefix).await;
+    }
+
+    return Err(create_invalid_data_error("PROXY protocol header is missing."));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_version_2_header(command: u8, family: u8, addresses: &[u8]) -> Vec<u8> {
+        let mut header = VERSION_2_SIGNATURE.to_vec();
+
+        header.push(0x20 | command);
+        header.push(family << 4 | 0x1);
+        header.extend_from_slice(&(addresses.len() as u16).to_be_bytes());
+        header.extend_from_slice(addresses);
+
+        return header;
+    }
+
+    #[tokio::test]
+    async fn reads_version_1_header_without_consuming_request() {
+        let mut stream: &[u8] = b"PROXY TCP4 203.0.113.7 10.0.0.1 56324 443\r\nGET / HTTP/1.1\r\n";
+        let source_address = read_header_async(&mut stream).await.unwrap();
+
+        assert_eq!(source_address, Some(SocketAddr::from_str("203.0.113.7:56324").unwrap()));
+        assert_eq!(stream, b"GET / HTTP/1.1\r\n");
+    }
+
+    #[tokio::test]
+    async fn reads_version_1_ipv6_and_unknown_headers() {
+        let mut stream: &[u8] = b"PROXY TCP6 2001:db8::1 2001:db8::2 4711 443\r\n";
+
+        assert_eq!(read_header_async(&mut stream).await.unwrap(), Some(SocketAddr::from_str("[2001:db8::1]:4711").unwrap()));
+
+        let mut stream: &[u8] = b"PROXY UNKNOWN\r\n";
+
+        assert_eq!(read_header_async(&mut stream).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_version_1_headers() {
+        let long_header = format!("PROXY TCP4 {}\r\n", "1".repeat(VERSION_1_MAXIMUM_LENGTH));
+        let headers: [&[u8]; 4] = [
+            b"PROXY TCP4 203.0.113.7 10.0.0.1 56324\r\n",
+            b"PROXY UDP4 203.0.113.7 10.0.0.1 56324 443\r\n",
+            b"PROXY TCP4 203.0.113.300 10.0.0.1 56324 443\r\n",
+            long_header.as_bytes(),
+        ];
+
+        for header in headers {
+            let mut stream: &[u8] = header;
+            let error = read_header_async(&mut stream).await.unwrap_err();
+
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_version_2_headers() {
+        let mut addresses = vec![203, 0, 113, 7, 10, 0, 0, 1];
+
+        addresses.extend_from_slice(&56324_u16.to_be_bytes());
+        addresses.extend_from_slice(&443_u16.to_be_bytes());
+        // a TLV after the addresses shall be skipped...
+        addresses.extend_from_slice(&[0x04, 0x00, 0x01, 0xFF]);
+
+        let mut header = create_version_2_header(VERSION_2_COMMAND_PROXY, VERSION_2_FAMILY_INET, &addresses);
+
+        header.extend_from_slice(b"GET");
+
+        let mut stream: &[u8] = &header;
+
+        assert_eq!(read_header_async(&mut stream).await.unwrap(), Some(SocketAddr::from_str("203.0.113.7:56324").unwrap()));
+        assert_eq!(stream, b"GET");
+
+        let mut addresses = Ipv6Addr::from_str("2001:db8::1").unwrap().octets().to_vec();
+
+        addresses.extend_from_slice(&Ipv6Addr::from_str("2001:db8::2").unwrap().octets());
+        addresses.extend_from_slice(&4711_u16.to_be_bytes());
+        addresses.extend_from_slice(&443_u16.to_be_bytes());
+
+        let header = create_version_2_header(VERSION_2_COMMAND_PROXY, VERSION_2_FAMILY_INET6, &addresses);
+        let mut stream: &[u8] = &header;
+
+        assert_eq!(read_header_async(&mut stream).await.unwrap(), Some(SocketAddr::from_str("[2001:db8::1]:4711").unwrap()));
+    }
+
+    #[tokio::test]
+    async fn reads_version_2_local_command_without_address() {
+        let header = create_version_2_header(VERSION_2_COMMAND_LOCAL, 0x0, &[]);
+        let mut stream: &[u8] = &header;
+
+        assert_eq!(read_header_async(&mut stream).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_or_truncated_headers() {
+        let mut stream: &[u8] = b"GET / HTTP/1.1\r\n";
+
+        assert_eq!(read_header_async(&mut stream).await.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        let header = create_version_2_header(VERSION_2_COMMAND_PROXY, VERSION_2_FAMILY_INET, &[203, 0, 113, 7]);
+        let mut stream: &[u8] = &header[..header.len() - 2];
+
+        assert_eq!(read_header_async(&mut stream).await.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn matches_listener_selectors() {
+        let listener_selectors = parse_listener_selectors(" default, ,unix ");
+
+        assert_eq!(listener_selectors, vec!["default", "unix"]);
+        assert!(is_enabled_for(&listener_selectors, &["tcp", "default"]));
+        assert!(!is_enabled_for(&listener_selectors, &["fd:3"]));
+        assert!(is_enabled_for(&parse_listener_selectors("all"), &["fd:3"]));
+    }
+}
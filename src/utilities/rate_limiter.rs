@@ -0,0 +1,319 @@
+#![allow(clippy::needless_return, clippy::len_zero, clippy::assign_op_pattern)]
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// the buckets of idle clients are removed once their window has passed. beyond this
+// count, the least recently used buckets are removed early...
+const MAXIMUM_BUCKET_COUNT: usize = 100000;
+// so that the buckets are not scanned again for every new client...
+const EVICTED_BUCKET_COUNT: usize = MAXIMUM_BUCKET_COUNT / 16;
+
+#[derive(Clone, Debug)]
+pub struct RateLimitRule {
+    pub path_prefix: String,
+    pub limit: u64,
+    pub window_in_seconds: u64,
+}
+
+// a token bucket that holds up to "limit" tokens and is
+// refilled at a rate of "limit" tokens per window...
+struct TokenBucket {
+    tokens: f64,
+    last_refilled_at: Instant,
+}
+
+pub struct RateLimitDecision {
+    pub is_allowed: bool,
+    pub limit: u64,
+    pub remaining: u64,
+    // seconds until the bucket is full again...
+    pub reset_in_seconds: u64,
+    // seconds until the next request would be allowed...
+    pub retry_after_in_seconds: u64,
+    pub window_in_seconds: u64,
+}
+
+#[derive(Clone)]
+pub struct RateLimiter {
+    rules: Arc<Vec<RateLimitRule>>,
+    buckets_arc: Arc<Mutex<HashMap<(usize, String), TokenBucket>>>,
+    last_cleaned_up_at_arc: Arc<Mutex<Instant>>,
+}
+
+pub fn parse_rules(rules: &str) -> Vec<RateLimitRule> {
+    let mut parsed_rules: Vec<RateLimitRule> = Vec::new();
+
+    // e.g. "/api=100/60,/login=5/60" allows 100 requests per 60 seconds
+    // under "/api" and 5 requests per 60 seconds under "/login"...
+    for rule in rules.split(',') {
+        let rule = rule.trim();
+
+        if rule.len() == 0 {
+            continue;
+        }
+
+        let index_of_separator_option = rule.rfind('=');
+
+        if index_of_separator_option.is_none() {
+            eprintln!("Invalid rate limit rule '{}'. Expected <path-prefix>=<limit>/<window-in-seconds>.", rule);
+
+            continue;
+        }
+
+        let index_of_separator = index_of_separator_option.unwrap();
+        let path_prefix = rule[..index_of_separator].trim().to_string();
+        let quota: Vec<&str> = rule[index_of_separator + 1..].split('/').collect();
+        let limit_option = quota.first().and_then(|limit| limit.trim().parse::<u64>().ok());
+        let window_in_seconds_option = quota.get(1).and_then(|window| window.trim().parse::<u64>().ok());
+
+        if quota.len() != 2 || limit_option.is_none() || window_in_seconds_option.is_none()
+            || limit_option.unwrap() == 0 || window_in_seconds_option.unwrap() == 0 {
+            eprintln!("Invalid rate limit rule '{}'. Expected <path-prefix>=<limit>/<window-in-seconds>.", rule);
+
+            continue;
+        }
+
+        parsed_rules.push(RateLimitRule {
+            path_prefix,
+            limit: limit_option.unwrap(),
+            window_in_seconds: window_in_seconds_option.unwrap(),
+        });
+    }
+
+    return parsed_rules;
+}
+
+// prefixes match whole segments only (e.g. "/api" matches "/api" and "/api/users"
+// but not "/apiary")...
+fn is_prefix_of(path_prefix: &str, path: &str) -> bool {
+    if !path.starts_with(path_prefix) {
+        return false;
+    }
+
+    return path.len() == path_prefix.len()
+        || path_prefix.ends_with('/')
+        || path.as_bytes()[path_prefix.len()] == b'/';
+}
+
+impl RateLimiter {
+    pub fn new(rules: Vec<RateLimitRule>) -> Self {
+        return RateLimiter {
+            rules: Arc::new(rules),
+            buckets_arc: Arc::new(Mutex::new(HashMap::new())),
+            last_cleaned_up_at_arc: Arc::new(Mutex::new(Instant::now())),
+        };
+    }
+
+    fn find_rule(&self, path: &str) -> Option<(usize, &RateLimitRule)> {
+        // the rule with the longest matching prefix wins...
+        return self.rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| is_prefix_of(rule.path_prefix.as_str(), path))
+            .max_by_key(|(_, rule)| rule.path_prefix.len());
+    }
+
+    fn refill(bucket: &mut TokenBucket, rule: &RateLimitRule, now: Instant) {
+        let elapsed_in_seconds = now.duration_since(bucket.last_refilled_at).as_secs_f64();
+        let tokens_per_second = rule.limit as f64 / rule.window_in_seconds as f64;
+
+        bucket.tokens = (bucket.tokens + elapsed_in_seconds * tokens_per_second).min(rule.limit as f64);
+        bucket.last_refilled_at = now;
+    }
+
+    fn clean_up(&self, buckets: &mut HashMap<(usize, String), TokenBucket>, now: Instant, is_forced: bool) {
+        let mut last_cleaned_up_at = self.last_cleaned_up_at_arc.lock().unwrap();
+        let longest_window_in_seconds = self.rules.iter().map(|rule| rule.window_in_seconds).max().unwrap_or(0);
+
+        // we shall not scan the buckets more often than necessary...
+        if !is_forced && now.duration_since(*last_cleaned_up_at) < Duration::from_secs(longest_window_in_seconds) {
+            return;
+        }
+
+        *last_cleaned_up_at = now;
+
+        // a bucket that would be full by now carries no information...
+        buckets.retain(|(index_of_rule, _), bucket| {
+            let rule = &self.rules[*index_of_rule];
+
+            now.duration_since(bucket.last_refilled_at) < Duration::from_secs(rule.window_in_seconds)
+        });
+    }
+
+    // makes room for the given number of new buckets...
+    fn make_room(&self, buckets: &mut HashMap<(usize, String), TokenBucket>, bucket_count: usize, now: Instant) {
+        if buckets.len() + bucket_count <= MAXIMUM_BUCKET_COUNT {
+            return;
+        }
+
+        self.clean_up(buckets, now, true);
+
+        if buckets.len() + bucket_count <= MAXIMUM_BUCKET_COUNT {
+            return;
+        }
+
+        let mut bucket_keys: Vec<(Instant, (usize, String))> = buckets.iter()
+            .map(|(key, bucket)| (bucket.last_refilled_at, key.clone()))
+            .collect();
+
+        bucket_keys.sort_unstable_by_key(|(last_refilled_at, _)| *last_refilled_at);
+
+        for (_, key) in bucket_keys.into_iter().take(bucket_count.max(EVICTED_BUCKET_COUNT)) {
+            buckets.remove(&key);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        return self.rules.len() > 0;
+    }
+
+    // a request is allowed if every one of its keys (e.g. the API key and the IP
+    // address) has a token left. the most restrictive bucket is reported...
+    pub fn check(&self, path: &str, keys: &[String]) -> Option<RateLimitDecision> {
+        // if no rule applies to the path, the request is not limited...
+        let (index_of_rule, rule) = self.find_rule(path)?;
+        let now = Instant::now();
+        let mut buckets = self.buckets_arc.lock().unwrap();
+
+        self.clean_up(&mut buckets, now, false);
+
+        let new_bucket_count = keys.iter()
+            .filter(|key| !buckets.contains_key(&(index_of_rule, key.to_string())))
+            .count();
+
+        self.make_room(&mut buckets, new_bucket_count, now);
+
+        let mut is_allowed = true;
+
+        for key in keys {
+            let bucket = buckets
+                .entry((index_of_rule, key.to_string()))
+                .or_insert(TokenBucket { tokens: rule.limit as f64, last_refilled_at: now });
+
+            RateLimiter::refill(bucket, rule, now);
+
+            is_allowed = is_allowed && bucket.tokens >= 1.0;
+        }
+
+        let mut tokens = rule.limit as f64;
+
+        for key in keys {
+            let bucket = buckets.get_mut(&(index_of_rule, key.to_string())).unwrap();
+
+            // a token is only taken if the request is allowed by every bucket...
+            if is_allowed {
+                bucket.tokens = bucket.tokens - 1.0;
+            }
+
+            tokens = tokens.min(bucket.tokens);
+        }
+
+        let seconds_per_token = rule.window_in_seconds as f64 / rule.limit as f64;
+        let reset_in_seconds = ((rule.limit as f64 - tokens) * seconds_per_token).ceil() as u64;
+        let retry_after_in_seconds = if tokens >= 1.0 {
+            0
+        } else {
+            ((1.0 - tokens) * seconds_per_token).ceil().max(1.0) as u64
+        };
+
+        return Some(RateLimitDecision {
+            is_allowed,
+            limit: rule.limit,
+            remaining: tokens.floor() as u64,
+            reset_in_seconds,
+            retry_after_in_seconds,
+            window_in_seconds: rule.window_in_seconds,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_path_prefix<'a>(rate_limiter: &'a RateLimiter, path: &str) -> Option<&'a str> {
+        return rate_limiter.find_rule(path).map(|(_, rule)| rule.path_prefix.as_str());
+    }
+
+    fn to_keys(keys: &[&str]) -> Vec<String> {
+        return keys.iter().map(|key| key.to_string()).collect();
+    }
+
+    #[test]
+    fn parses_rules() {
+        let rules = parse_rules(" /api=100/60, /login=5/60, /invalid=5, /zero=0/60, /nothing");
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!((rules[0].path_prefix.as_str(), rules[0].limit, rules[0].window_in_seconds), ("/api", 100, 60));
+        assert_eq!((rules[1].path_prefix.as_str(), rules[1].limit, rules[1].window_in_seconds), ("/login", 5, 60));
+    }
+
+    #[test]
+    fn matches_prefixes_on_segment_boundaries() {
+        let rate_limiter = RateLimiter::new(parse_rules("/=1000/60,/api=100/60,/api/login=5/60,/files/=10/60"));
+
+        assert_eq!(find_path_prefix(&rate_limiter, "/api"), Some("/api"));
+        assert_eq!(find_path_prefix(&rate_limiter, "/api/users"), Some("/api"));
+        assert_eq!(find_path_prefix(&rate_limiter, "/api/login"), Some("/api/login"));
+        assert_eq!(find_path_prefix(&rate_limiter, "/api/loginx"), Some("/api"));
+        assert_eq!(find_path_prefix(&rate_limiter, "/apiary"), Some("/"));
+        assert_eq!(find_path_prefix(&rate_limiter, "/files/a.txt"), Some("/files/"));
+
+        let rate_limiter = RateLimiter::new(parse_rules("/api=100/60"));
+
+        assert_eq!(find_path_prefix(&rate_limiter, "/apiary"), None);
+        assert!(rate_limiter.check("/apiary", &to_keys(&["127.0.0.1"])).is_none());
+    }
+
+    #[test]
+    fn rejects_requests_once_tokens_are_used_up() {
+        let rate_limiter = RateLimiter::new(parse_rules("/login=2/60"));
+        let keys = to_keys(&["127.0.0.1"]);
+        let first_decision = rate_limiter.check("/login", &keys).unwrap();
+        let second_decision = rate_limiter.check("/login", &keys).unwrap();
+        let third_decision = rate_limiter.check("/login", &keys).unwrap();
+
+        assert!(first_decision.is_allowed && second_decision.is_allowed && !third_decision.is_allowed);
+        assert_eq!((first_decision.remaining, second_decision.remaining, third_decision.remaining), (1, 0, 0));
+        assert_eq!(third_decision.retry_after_in_seconds, 30);
+        assert_eq!(third_decision.reset_in_seconds, 60);
+        // other clients have buckets of their own...
+        assert!(rate_limiter.check("/login", &to_keys(&["127.0.0.2"])).unwrap().is_allowed);
+    }
+
+    #[test]
+    fn limits_address_even_if_key_header_changes() {
+        let rate_limiter = RateLimiter::new(parse_rules("/api=2/60"));
+
+        assert!(rate_limiter.check("/api", &to_keys(&["127.0.0.1", "x-api-key:a"])).unwrap().is_allowed);
+        assert!(rate_limiter.check("/api", &to_keys(&["127.0.0.1", "x-api-key:b"])).unwrap().is_allowed);
+        assert!(!rate_limiter.check("/api", &to_keys(&["127.0.0.1", "x-api-key:c"])).unwrap().is_allowed);
+    }
+
+    #[test]
+    fn takes_no_token_if_any_bucket_rejects() {
+        let rate_limiter = RateLimiter::new(parse_rules("/api=1/60"));
+
+        assert!(rate_limiter.check("/api", &to_keys(&["127.0.0.1", "x-api-key:a"])).unwrap().is_allowed);
+        assert!(!rate_limiter.check("/api", &to_keys(&["127.0.0.2", "x-api-key:a"])).unwrap().is_allowed);
+        // the rejected request did not take the token of the second address...
+        assert!(rate_limiter.check("/api", &to_keys(&["127.0.0.2"])).unwrap().is_allowed);
+    }
+
+    #[test]
+    fn keeps_bucket_count_bounded() {
+        let rate_limiter = RateLimiter::new(parse_rules("/=1/60"));
+
+        for index in 0..MAXIMUM_BUCKET_COUNT + 10 {
+            rate_limiter.check("/", &[index.to_string()]);
+        }
+
+        let bucket_count = rate_limiter.buckets_arc.lock().unwrap().len();
+
+        assert!(bucket_count <= MAXIMUM_BUCKET_COUNT && bucket_count > MAXIMUM_BUCKET_COUNT - EVICTED_BUCKET_COUNT);
+    }
+}
@@ -0,0 +1,521 @@
+#![allow(clippy::needless_return, clippy::len_zero, clippy::assign_op_pattern)]
+
+use hyper::{Body, HeaderMap, Request, Version};
+use std::{
+    collections::VecDeque,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use log::debug;
+
+use crate::http_utilities;
+
+// hyper does not accept larger request heads either...
+const MAXIMUM_HEAD_SIZE_IN_BYTES: usize = 8192 + 4096 * 100;
+const MAXIMUM_CHUNK_LINE_LENGTH: usize = 4096;
+// pipelined requests are recorded before hyper hands them to us...
+const MAXIMUM_PENDING_HEAD_COUNT: usize = 32;
+
+// header names and values exactly as sent by the client, in their original order
+// (flattened as name, value, name, value... like rawHeaders of Node.js)...
+#[derive(Clone, Debug)]
+pub struct RawHeaders(pub Vec<String>);
+
+enum ParserState {
+    Head,
+    // the number of body bytes remaining...
+    Body(u64),
+    ChunkSize,
+    ChunkData(u64),
+    ChunkDataEnd,
+    Trailers,
+    // either the connection no longer speaks HTTP/1 or we lost track of it...
+    Stopped,
+}
+
+struct RecorderState {
+    parser_state: ParserState,
+    head_buffer: Vec<u8>,
+    line_buffer: Vec<u8>,
+    pending_heads: VecDeque<Vec<(String, Vec<u8>)>>,
+}
+
+// hyper does not expose the original order and casing of request headers, so we
+// shall follow the HTTP/1 messages read from the connection and record the heads...
+#[derive(Clone)]
+pub struct RawHeaderRecorder {
+    state_arc: Arc<Mutex<RecorderState>>,
+}
+
+fn trim_whitespace(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|byte| *byte != b' ' && *byte != b'\t').unwrap_or(bytes.len());
+    let end = bytes.iter().rposition(|byte| *byte != b' ' && *byte != b'\t').map_or(start, |index| index + 1);
+
+    return &bytes[start..end];
+}
+
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    return line.strip_suffix(b"\r").unwrap_or(line);
+}
+
+fn get_header_value<'a>(headers: &'a [(String, Vec<u8>)], header_name: &str) -> Option<&'a [u8]> {
+    return headers.iter()
+        .rev()
+        .find(|(name, _)| name.eq_ignore_ascii_case(header_name))
+        .map(|(_, value)| value.as_slice());
+}
+
+// the headers parsed by hyper must be exactly the ones recorded, otherwise we have lost track...
+fn matches_header_map(raw_headers: &[(String, Vec<u8>)], header_map: &HeaderMap) -> bool {
+    if raw_headers.len() != header_map.len() {
+        return false;
+    }
+
+    for (header_name, _) in raw_headers {
+        let expected_values = raw_headers.iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(header_name))
+            .map(|(_, value)| value.as_slice());
+        let actual_values = header_map.get_all(header_name.to_ascii_lowercase().as_str())
+            .iter()
+            .map(|header_value| header_value.as_bytes());
+
+        if !expected_values.eq(actual_values) {
+            return false;
+        }
+    }
+
+    return true;
+}
+
+pub fn get(request: &Request<Body>) -> Option<Vec<String>> {
+    return request.extensions()
+        .get::<RawHeaders>()
+        .map(|raw_headers| raw_headers.0.clone());
+}
+
+impl RecorderState {
+    // we shall neither record nor hand out anything for this connection anymore...
+    fn stop(&mut self, reason: &str) {
+        debug!("Raw headers are no longer recorded for this connection: {}", reason);
+
+        self.parser_state = ParserState::Stopped;
+        self.head_buffer = Vec::new();
+        self.line_buffer = Vec::new();
+        self.pending_heads.clear();
+    }
+
+    fn complete_head(&mut self) {
+        let head = std::mem::take(&mut self.head_buffer);
+        let mut lines = head.split(|byte| *byte == b'\n').map(trim_line_ending);
+        let request_line = lines.next().unwrap_or(b"");
+
+        // e.g. the HTTP/2 connection preface ("PRI * HTTP/2.0")...
+        if !request_line.ends_with(b"HTTP/1.1") && !request_line.ends_with(b"HTTP/1.0") {
+            self.stop("not an HTTP/1 request");
+
+            return;
+        }
+
+        let mut headers: Vec<(String, Vec<u8>)> = Vec::new();
+
+        for line in lines {
+            if line.len() == 0 {
+                break;
+            }
+
+            let index_of_colon_option = line.iter().position(|byte| *byte == b':');
+
+            // folded lines (obsolete) are rejected by hyper...
+            if line[0] == b' ' || line[0] == b'\t' || index_of_colon_option.is_none() {
+                self.stop("malformed header line");
+
+                return;
+            }
+
+            let index_of_colon = index_of_colon_option.unwrap();
+            let header_name = http_utilities::to_latin1_string(&line[..index_of_colon]);
+
+            headers.push((header_name, trim_whitespace(&line[index_of_colon + 1..]).to_vec()));
+        }
+
+        let is_chunked = get_header_value(&headers, "transfer-encoding")
+            .and_then(|transfer_encoding| transfer_encoding.rsplit(|byte| *byte == b',').next())
+            .is_some_and(|transfer_coding| trim_whitespace(transfer_coding).eq_ignore_ascii_case(b"chunked"));
+        let content_length = get_header_value(&headers, "content-length")
+            .and_then(|content_length| std::str::from_utf8(content_length).ok())
+            .and_then(|content_length| content_length.split(',').next())
+            .and_then(|content_length| content_length.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+        let is_upgrade = request_line.starts_with(b"CONNECT ") || get_header_value(&headers, "upgrade").is_some();
+
+        self.pending_heads.push_back(headers);
+
+        if self.pending_heads.len() > MAXIMUM_PENDING_HEAD_COUNT {
+            self.stop("too many pending requests");
+
+            return;
+        }
+
+        // whatever follows an upgrade is not HTTP/1 anymore. the pending head is still handed out...
+        if is_upgrade {
+            self.parser_state = ParserState::Stopped;
+        } else if is_chunked {
+            self.parser_state = ParserState::ChunkSize;
+        } else if content_length > 0 {
+            self.parser_state = ParserState::Body(content_length);
+        }
+    }
+
+    // returns the completed line (if any) and the number of bytes consumed...
+    fn read_line(&mut self, bytes: &[u8]) -> (Option<Vec<u8>>, usize) {
+        let index_of_line_feed_option = bytes.iter().position(|byte| *byte == b'\n');
+        let consumed_length = index_of_line_feed_option.map_or(bytes.len(), |index| index + 1);
+
+        self.line_buffer.extend_from_slice(&bytes[..consumed_length]);
+
+        if index_of_line_feed_option.is_none() {
+            return (None, consumed_length);
+        }
+
+        let line = std::mem::take(&mut self.line_buffer);
+
+        return (Some(trim_line_ending(&line[..line.len() - 1]).to_vec()), consumed_length);
+    }
+
+    fn record(&mut self, bytes: &[u8]) {
+        let mut index = 0;
+
+        while index < bytes.len() {
+            let remaining_bytes = &bytes[index..];
+
+            match self.parser_state {
+                ParserState::Stopped => return,
+                ParserState::Head => {
+                    let byte = remaining_bytes[0];
+
+                    index = index + 1;
+
+                    // empty lines preceding a request line are ignored...
+                    if self.head_buffer.len() == 0 && (byte == b'\r' || byte == b'\n') {
+                        continue;
+                    }
+
+                    self.head_buffer.push(byte);
+
+                    if self.head_buffer.len() > MAXIMUM_HEAD_SIZE_IN_BYTES {
+                        self.stop("request head is too large");
+
+                        return;
+                    }
+
+                    if self.head_buffer.ends_with(b"\r\n\r\n") || self.head_buffer.ends_with(b"\n\n") {
+                        self.complete_head();
+                    }
+                },
+                ParserState::Body(remaining_length) | ParserState::ChunkData(remaining_length) => {
+                    let skipped_length = remaining_length.min(remaining_bytes.len() as u64);
+
+                    index = index + skipped_length as usize;
+
+                    let remaining_length = remaining_length - skipped_length;
+
+                    self.parser_state = match self.parser_state {
+                        ParserState::Body(_) if remaining_length == 0 => ParserState::Head,
+                        ParserState::Body(_) => ParserState::Body(remaining_length),
+                        _ if remaining_length == 0 => ParserState::ChunkDataEnd,
+                        _ => ParserState::ChunkData(remaining_length),
+                    };
+                },
+                ParserState::ChunkSize | ParserState::ChunkDataEnd | ParserState::Trailers => {
+                    let (line_option, consumed_length) = self.read_line(remaining_bytes);
+
+                    index = index + consumed_length;
+
+                    if self.line_buffer.len() > MAXIMUM_CHUNK_LINE_LENGTH {
+                        self.stop("chunk line is too long");
+
+                        return;
+                    }
+
+                    if line_option.is_none() {
+                        continue;
+                    }
+
+                    let line = line_option.unwrap();
+
+                    match self.parser_state {
+                        ParserState::ChunkSize => {
+                            // chunk extensions (e.g. "5;name=value") are ignored...
+                            let chunk_size_option = std::str::from_utf8(&line).ok()
+                                .and_then(|line| line.split(';').next())
+                                .and_then(|chunk_size| u64::from_str_radix(chunk_size.trim(), 16).ok());
+
+                            if chunk_size_option.is_none() {
+                                self.stop("malformed chunk size");
+
+                                return;
+                            }
+
+                            let chunk_size = chunk_size_option.unwrap();
+
+                            self.parser_state = if chunk_size == 0 { ParserState::Trailers } else { ParserState::ChunkData(chunk_size) };
+                        },
+                        ParserState::ChunkDataEnd => self.parser_state = ParserState::ChunkSize,
+                        // the message ends with an empty line after the trailers (if any)...
+                        _ => {
+                            if line.len() == 0 {
+                                self.parser_state = ParserState::Head;
+                            }
+                        },
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl RawHeaderRecorder {
+    pub fn new() -> Self {
+        let state = RecorderState {
+            parser_state: ParserState::Head,
+            head_buffer: Vec::new(),
+            line_buffer: Vec::new(),
+            pending_heads: VecDeque::new(),
+        };
+
+        return RawHeaderRecorder { state_arc: Arc::new(Mutex::new(state)) };
+    }
+
+    fn record(&self, bytes: &[u8]) {
+        let mut state = self.state_arc.lock().unwrap();
+
+        state.record(bytes);
+    }
+
+    // must be called for every request of the connection (in order), so that the
+    // recorded heads stay in line with the requests...
+    pub fn attach(&self, request: &mut Request<Body>) {
+        // HTTP/2 requests were never recorded...
+        if request.version() != Version::HTTP_10 && request.version() != Version::HTTP_11 {
+            return;
+        }
+
+        let mut state = self.state_arc.lock().unwrap();
+        let raw_headers_option = state.pending_heads.pop_front();
+
+        if raw_headers_option.is_none() {
+            return;
+        }
+
+        let raw_headers = raw_headers_option.unwrap();
+
+        if !matches_header_map(&raw_headers, request.headers()) {
+            state.stop("recorded headers do not match the request");
+
+            return;
+        }
+
+        let raw_headers = raw_headers.into_iter()
+            .flat_map(|(header_name, header_value)| [header_name, http_utilities::to_latin1_string(&header_value)])
+            .collect();
+
+        request.extensions_mut().insert(RawHeaders(raw_headers));
+    }
+}
+
+// records the bytes read from a connection (if a recorder is given)...
+pub struct RawHeaderRecordingStream<S> {
+    inner: S,
+    raw_header_recorder_option: Option<RawHeaderRecorder>,
+}
+
+impl<S> RawHeaderRecordingStream<S> {
+    pub fn new(inner: S, raw_header_recorder_option: Option<RawHeaderRecorder>) -> Self {
+        return RawHeaderRecordingStream { inner, raw_header_recorder_option };
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for RawHeaderRecordingStream<S> {
+    fn poll_read(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let filled_length = buffer.filled().len();
+        let poll = Pin::new(&mut this.inner).poll_read(context, buffer);
+
+        if let (Poll::Ready(Ok(())), Some(raw_header_recorder)) = (&poll, this.raw_header_recorder_option.as_ref()) {
+            raw_header_recorder.record(&buffer.filled()[filled_length..]);
+        }
+
+        return poll;
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for RawHeaderRecordingStream<S> {
+    fn poll_write(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &[u8]) -> Poll<io::Result<usize>> {
+        return Pin::new(&mut self.get_mut().inner).poll_write(context, buffer);
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_flush(context);
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return Pin::new(&mut self.get_mut().inner).poll_shutdown(context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_request(version: Version, headers: &[(&str, &str)]) -> Request<Body> {
+        let mut request_builder = Request::builder().version(version).uri("/");
+
+        for (header_name, header_value) in headers {
+            request_builder = request_builder.header(*header_name, *header_value);
+        }
+
+        return request_builder.body(Body::empty()).unwrap();
+    }
+
+    fn attach(raw_header_recorder: &RawHeaderRecorder, headers: &[(&str, &str)]) -> Option<Vec<String>> {
+        let mut request = create_request(Version::HTTP_11, headers);
+
+        raw_header_recorder.attach(&mut request);
+
+        return get(&request);
+    }
+
+    fn get_pending_head_count(raw_header_recorder: &RawHeaderRecorder) -> usize {
+        return raw_header_recorder.state_arc.lock().unwrap().pending_heads.len();
+    }
+
+    fn is_stopped(raw_header_recorder: &RawHeaderRecorder) -> bool {
+        return matches!(raw_header_recorder.state_arc.lock().unwrap().parser_state, ParserState::Stopped);
+    }
+
+    #[test]
+    fn records_head_split_across_reads() {
+        let raw_header_recorder = RawHeaderRecorder::new();
+        let head = b"GET / HTTP/1.1\r\nHost: example.com\r\nX-Custom-Header:  Some Value \r\n\r\n";
+
+        for byte in head.iter() {
+            raw_header_recorder.record(&[*byte]);
+        }
+
+        assert_eq!(
+            attach(&raw_header_recorder, &[("host", "example.com"), ("x-custom-header", "Some Value")]),
+            Some(vec![
+                String::from("Host"), String::from("example.com"),
+                String::from("X-Custom-Header"), String::from("Some Value"),
+            ]),
+        );
+    }
+
+    #[test]
+    fn skips_chunked_body_with_extensions_and_trailers() {
+        let raw_header_recorder = RawHeaderRecorder::new();
+        let bytes: &[u8] = b"POST /upload HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n\
+            5;name=value\r\nhello\r\nA\r\n0123456789\r\n0\r\nChecksum: abc\r\n\r\n\
+            GET /next HTTP/1.1\r\nHost: b\r\n\r\n";
+
+        // the chunk lines are split across reads as well...
+        for chunk in bytes.chunks(3) {
+            raw_header_recorder.record(chunk);
+        }
+
+        assert_eq!(get_pending_head_count(&raw_header_recorder), 2);
+        assert_eq!(
+            attach(&raw_header_recorder, &[("host", "a"), ("transfer-encoding", "chunked")]),
+            Some(vec![String::from("Host"), String::from("a"), String::from("Transfer-Encoding"), String::from("chunked")]),
+        );
+        assert_eq!(
+            attach(&raw_header_recorder, &[("host", "b")]),
+            Some(vec![String::from("Host"), String::from("b")]),
+        );
+    }
+
+    #[test]
+    fn records_pipelined_requests_in_order() {
+        let raw_header_recorder = RawHeaderRecorder::new();
+
+        raw_header_recorder.record(b"GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHOST: b\r\n\r\n");
+
+        assert_eq!(get_pending_head_count(&raw_header_recorder), 2);
+        assert_eq!(attach(&raw_header_recorder, &[("host", "a")]), Some(vec![String::from("Host"), String::from("a")]));
+        assert_eq!(attach(&raw_header_recorder, &[("host", "b")]), Some(vec![String::from("HOST"), String::from("b")]));
+        assert_eq!(attach(&raw_header_recorder, &[("host", "c")]), None);
+    }
+
+    #[test]
+    fn skips_body_followed_by_second_head() {
+        let raw_header_recorder = RawHeaderRecorder::new();
+
+        // the body looks like a head but must not be taken as one...
+        raw_header_recorder.record(b"POST / HTTP/1.1\r\nContent-Length: 22\r\n\r\nGET /fake HTTP/1.1\r\n\r\n");
+        raw_header_recorder.record(b"GET /real HTTP/1.1\r\nX-Real: yes\r\n\r\n");
+
+        assert_eq!(get_pending_head_count(&raw_header_recorder), 2);
+        assert!(attach(&raw_header_recorder, &[("content-length", "22")]).is_some());
+        assert_eq!(
+            attach(&raw_header_recorder, &[("x-real", "yes")]),
+            Some(vec![String::from("X-Real"), String::from("yes")]),
+        );
+    }
+
+    #[test]
+    fn stops_at_http2_connection_preface() {
+        let raw_header_recorder = RawHeaderRecorder::new();
+
+        raw_header_recorder.record(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n");
+        raw_header_recorder.record(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n");
+
+        assert!(is_stopped(&raw_header_recorder));
+        assert_eq!(get_pending_head_count(&raw_header_recorder), 0);
+
+        // HTTP/2 requests are left alone...
+        let mut request = create_request(Version::HTTP_2, &[("host", "a")]);
+
+        raw_header_recorder.attach(&mut request);
+
+        assert_eq!(get(&request), None);
+    }
+
+    #[test]
+    fn stops_after_upgrade_but_hands_out_upgrade_head() {
+        let raw_header_recorder = RawHeaderRecorder::new();
+
+        raw_header_recorder.record(b"GET /chat HTTP/1.1\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\r\n\x81\x05hello");
+        raw_header_recorder.record(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n");
+
+        assert!(is_stopped(&raw_header_recorder));
+        assert_eq!(
+            attach(&raw_header_recorder, &[("connection", "Upgrade"), ("upgrade", "websocket")]),
+            Some(vec![
+                String::from("Connection"), String::from("Upgrade"),
+                String::from("Upgrade"), String::from("websocket"),
+            ]),
+        );
+        assert_eq!(get_pending_head_count(&raw_header_recorder), 0);
+    }
+
+    #[test]
+    fn stops_recording_on_mismatch() {
+        let raw_header_recorder = RawHeaderRecorder::new();
+
+        raw_header_recorder.record(b"GET /a HTTP/1.1\r\nHost: a\r\n\r\nGET /b HTTP/1.1\r\nHost: b\r\n\r\n");
+
+        // hyper parsed something else than we did...
+        assert_eq!(attach(&raw_header_recorder, &[("host", "other")]), None);
+        assert!(is_stopped(&raw_header_recorder));
+
+        // the pending heads are dropped and nothing is recorded anymore...
+        assert_eq!(attach(&raw_header_recorder, &[("host", "b")]), None);
+
+        raw_header_recorder.record(b"GET /c HTTP/1.1\r\nHost: c\r\n\r\n");
+
+        assert_eq!(attach(&raw_header_recorder, &[("host", "c")]), None);
+    }
+}
@@ -0,0 +1,70 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use hyper::{header::HeaderName, http::HeaderValue, Body, Request, Response};
+use uuid::Uuid;
+use log::debug;
+
+// longer IDs are most likely not IDs at all...
+const MAXIMUM_REQUEST_ID_LENGTH: usize = 128;
+
+// globally unique ID of a request, as opposed to the process local ID that
+// correlates messages exchanged with the parent process...
+#[derive(Clone, Debug)]
+pub struct CorrelationId(pub String);
+
+fn is_valid_character(character: char) -> bool {
+    return character.is_ascii_alphanumeric() || "-_.:+=/@".contains(character);
+}
+
+// the ID ends up in logs and in requests to other services, so we
+// shall only accept IDs that cannot be abused for injection...
+pub fn is_valid(request_id: &str) -> bool {
+    return request_id.len() > 0
+        && request_id.len() <= MAXIMUM_REQUEST_ID_LENGTH
+        && request_id.chars().all(is_valid_character);
+}
+
+// UUID v7 begins with a timestamp, hence the IDs are sortable by creation time...
+pub fn generate() -> String {
+    return Uuid::now_v7().to_string();
+}
+
+// honours the ID sent by the client (or a proxy in front of us) and generates one
+// otherwise. the ID is attached to the request so that it travels with it...
+pub fn resolve(request: &mut Request<Body>, header_name: &str) -> String {
+    let header_value_option = request.headers()
+        .get(header_name)
+        .and_then(|header_value| header_value.to_str().ok())
+        .map(|header_value| header_value.trim());
+    let request_id = if header_value_option.is_some() && is_valid(header_value_option.unwrap()) {
+        header_value_option.unwrap().to_string()
+    } else {
+        if header_value_option.is_some() {
+            debug!("Invalid request ID '{}' received. Generating a new one.", header_value_option.unwrap());
+        }
+
+        generate()
+    };
+
+    request.extensions_mut().insert(CorrelationId(request_id.clone()));
+
+    return request_id;
+}
+
+pub fn get(request: &Request<Body>) -> Option<String> {
+    return request.extensions()
+        .get::<CorrelationId>()
+        .map(|correlation_id| correlation_id.0.clone());
+}
+
+// echoes the ID so that clients can refer to the request when reporting problems...
+pub fn add_header(response: &mut Response<Body>, header_name: &str, request_id: &str) {
+    let header_name_result = HeaderName::from_bytes(header_name.as_bytes());
+    let header_value_result = HeaderValue::from_str(request_id);
+
+    if header_name_result.is_err() || header_value_result.is_err() {
+        return;
+    }
+
+    response.headers_mut().insert(header_name_result.unwrap(), header_value_result.unwrap());
+}
@@ -0,0 +1,269 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero, clippy::assign_op_pattern)]
+
+const CONTENT_LENGTH_HEADER_NAME: &str = "content-length";
+const WILDCARD_SUBTYPE: &str = "/*";
+// the request line without the URI (e.g. "DELETE " and " HTTP/1.1\r\n") and the final CRLF...
+const REQUEST_LINE_AND_HEAD_END_LENGTH: usize = 64;
+// ": " and CRLF of every header line...
+const HEADER_LINE_OVERHEAD_LENGTH: usize = 4;
+// hyper refuses smaller read buffers...
+const MINIMUM_READ_BUFFER_SIZE: usize = 8192;
+
+use futures::stream::StreamExt;
+use hyper::{body::HttpBody, Body, Request, Response, StatusCode};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::http_server_configuration::HttpServerConfiguration;
+
+pub fn parse_maximum_body_sizes_per_content_type(maximum_body_sizes: &str) -> Vec<(String, u64)> {
+    let mut maximum_body_sizes_per_content_type: Vec<(String, u64)> = Vec::new();
+
+    // e.g. "application/json=1048576,text/*=65536"...
+    for entry in maximum_body_sizes.split(',') {
+        let entry = entry.trim();
+
+        if entry.len() == 0 {
+            continue;
+        }
+
+        let index_of_separator_option = entry.rfind('=');
+
+        if index_of_separator_option.is_none() {
+            eprintln!("Invalid maximum body size entry '{}'. Expected <content-type>=<size-in-bytes>.", entry);
+
+            continue;
+        }
+
+        let index_of_separator = index_of_separator_option.unwrap();
+        let content_type = entry[..index_of_separator].trim().to_lowercase();
+        let maximum_body_size_result = entry[index_of_separator + 1..].trim().parse::<u64>();
+
+        if maximum_body_size_result.is_err() {
+            let error = maximum_body_size_result.unwrap_err();
+
+            eprintln!("An error occurred while parsing maximum body size of '{}': {}", content_type, error);
+
+            continue;
+        }
+
+        maximum_body_sizes_per_content_type.push((content_type, maximum_body_size_result.unwrap()));
+    }
+
+    return maximum_body_sizes_per_content_type;
+}
+
+fn get_media_type(content_type: &str) -> String {
+    // parameters such as charset or boundary do not affect the limit...
+    return content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+}
+
+pub fn get_maximum_body_size(content_type: &str, configuration: &HttpServerConfiguration) -> u64 {
+    let media_type = get_media_type(content_type);
+    let mut wildcard_maximum_body_size_option: Option<u64> = None;
+
+    for (configured_content_type, maximum_body_size) in configuration.maximum_body_sizes_per_content_type.iter() {
+        // an exact match always wins...
+        if media_type.eq(configured_content_type) {
+            return *maximum_body_size;
+        }
+
+        // "text/*" matches every subtype of "text"...
+        if configured_content_type.ends_with(WILDCARD_SUBTYPE)
+            && media_type.starts_with(&configured_content_type[..configured_content_type.len() - 1]) {
+            wildcard_maximum_body_size_option = wildcard_maximum_body_size_option.or(Some(*maximum_body_size));
+        }
+    }
+
+    return wildcard_maximum_body_size_option.unwrap_or(configuration.maximum_body_size_in_bytes);
+}
+
+// the largest HTTP/1 request head (request line and headers) within the limits...
+pub fn get_maximum_head_size(configuration: &HttpServerConfiguration) -> usize {
+    let maximum_head_size = REQUEST_LINE_AND_HEAD_END_LENGTH
+        .saturating_add(configuration.maximum_uri_length)
+        .saturating_add(configuration.maximum_header_size_in_bytes)
+        .saturating_add(configuration.maximum_header_count.saturating_mul(HEADER_LINE_OVERHEAD_LENGTH));
+
+    return maximum_head_size.max(MINIMUM_READ_BUFFER_SIZE);
+}
+
+pub fn check_request_head(request: &Request<Body>, configuration: &HttpServerConfiguration) -> Result<(), StatusCode> {
+    let uri_length = request.uri().to_string().len();
+
+    if uri_length > configuration.maximum_uri_length {
+        return Err(StatusCode::URI_TOO_LONG);
+    }
+
+    let headers = request.headers();
+
+    if headers.len() > configuration.maximum_header_count {
+        return Err(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+    }
+
+    // the size of the header section is the total length of all names and values...
+    let header_size: usize = headers
+        .iter()
+        .map(|(header_name, header_value)| header_name.as_str().len() + header_value.len())
+        .sum();
+
+    if header_size > configuration.maximum_header_size_in_bytes {
+        return Err(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+    }
+
+    return Ok(());
+}
+
+pub fn check_content_length(request: &Request<Body>, maximum_body_size: u64) -> Result<(), StatusCode> {
+    let content_length_option = request.headers()
+        .get(CONTENT_LENGTH_HEADER_NAME)
+        .and_then(|header_value| header_value.to_str().ok())
+        .and_then(|header_value| header_value.trim().parse::<u64>().ok());
+
+    // if the client announces a body that is too large, we
+    // shall reject the request without reading the body...
+    if content_length_option.is_some() && content_length_option.unwrap() > maximum_body_size {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    return Ok(());
+}
+
+// a body that is streamed (e.g. to an upstream) instead of being read by us is counted while it
+// passes through. once it exceeds the limit, the stream fails and the returned flag is set...
+pub fn limit_body(body: Body, maximum_body_size: u64) -> (Body, Arc<AtomicBool>) {
+    let is_limit_exceeded_arc = Arc::new(AtomicBool::new(false));
+
+    // an empty body stays as it is, so that it can still be recognized as such...
+    if body.is_end_stream() {
+        return (body, is_limit_exceeded_arc);
+    }
+
+    let is_limit_exceeded_arc_clone = is_limit_exceeded_arc.clone();
+    let mut total_bytes_read: u64 = 0;
+    let limited_body = Body::wrap_stream(body.map(move |chunk_result| {
+        let chunk = chunk_result.map_err(io::Error::other)?;
+
+        total_bytes_read = total_bytes_read + chunk.len() as u64;
+
+        if total_bytes_read > maximum_body_size {
+            is_limit_exceeded_arc_clone.store(true, Ordering::Relaxed);
+
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Request body is too large."));
+        }
+
+        return Ok(chunk);
+    }));
+
+    return (limited_body, is_limit_exceeded_arc);
+}
+
+pub fn create_error_response(status_code: StatusCode) -> Response<Body> {
+    return Response::builder()
+        .status(status_code)
+        .body(Body::from(status_code.canonical_reason().unwrap_or("")))
+        .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_request(uri: &str, headers: &[(&str, &str)]) -> Request<Body> {
+        let mut request_builder = Request::builder().uri(uri);
+
+        for (header_name, header_value) in headers {
+            request_builder = request_builder.header(*header_name, *header_value);
+        }
+
+        return request_builder.body(Body::empty()).unwrap();
+    }
+
+    #[test]
+    fn parses_maximum_body_sizes_per_content_type() {
+        let maximum_body_sizes = parse_maximum_body_sizes_per_content_type(" Application/JSON=1048576, text/*=65536, invalid, image/png=big");
+
+        assert_eq!(maximum_body_sizes, vec![
+            (String::from("application/json"), 1048576),
+            (String::from("text/*"), 65536),
+        ]);
+    }
+
+    #[test]
+    fn prefers_exact_content_type_over_wildcard() {
+        let configuration = HttpServerConfiguration::from_arguments(&[
+            "--maximumBodySize", "100",
+            "--maximumBodySizePerContentType", "text/*=20,text/plain=10",
+        ]);
+
+        assert_eq!(get_maximum_body_size("text/plain; charset=utf-8", &configuration), 10);
+        assert_eq!(get_maximum_body_size("TEXT/HTML", &configuration), 20);
+        assert_eq!(get_maximum_body_size("application/json", &configuration), 100);
+        assert_eq!(get_maximum_body_size("", &configuration), 100);
+    }
+
+    #[test]
+    fn checks_uri_length_and_headers() {
+        let configuration = HttpServerConfiguration::from_arguments(&[
+            "--maximumUriLength", "16",
+            "--maximumHeaderCount", "2",
+            "--maximumHeaderSize", "20",
+        ]);
+
+        assert_eq!(check_request_head(&create_request("/short", &[("a", "1")]), &configuration), Ok(()));
+        assert_eq!(check_request_head(&create_request("/a/much/longer/path", &[]), &configuration), Err(StatusCode::URI_TOO_LONG));
+        assert_eq!(check_request_head(&create_request("/", &[("a", "1"), ("b", "2"), ("c", "3")]), &configuration),
+            Err(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE));
+        // 10 bytes of name and 11 bytes of value...
+        assert_eq!(check_request_head(&create_request("/", &[("x-too-long", "12345678901")]), &configuration),
+            Err(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE));
+    }
+
+    #[test]
+    fn checks_announced_content_length() {
+        assert_eq!(check_content_length(&create_request("/", &[("content-length", "10")]), 10), Ok(()));
+        assert_eq!(check_content_length(&create_request("/", &[("content-length", "11")]), 10), Err(StatusCode::PAYLOAD_TOO_LARGE));
+        // chunked bodies are limited while they are read...
+        assert_eq!(check_content_length(&create_request("/", &[("transfer-encoding", "chunked")]), 10), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn limits_streamed_body() {
+        let chunks: Vec<Result<&str, io::Error>> = vec![Ok("12345"), Ok("67890")];
+        let (body, is_limit_exceeded_arc) = limit_body(Body::wrap_stream(futures::stream::iter(chunks)), 10);
+
+        assert_eq!(hyper::body::to_bytes(body).await.unwrap(), "1234567890");
+        assert!(!is_limit_exceeded_arc.load(Ordering::Relaxed));
+
+        let chunks: Vec<Result<&str, io::Error>> = vec![Ok("12345"), Ok("67890"), Ok("1")];
+        let (body, is_limit_exceeded_arc) = limit_body(Body::wrap_stream(futures::stream::iter(chunks)), 10);
+
+        assert!(hyper::body::to_bytes(body).await.is_err());
+        assert!(is_limit_exceeded_arc.load(Ordering::Relaxed));
+
+        let (body, _) = limit_body(Body::empty(), 10);
+
+        assert!(body.is_end_stream());
+    }
+
+    #[test]
+    fn bounds_head_size_by_limits() {
+        let configuration = HttpServerConfiguration::from_arguments(&[]);
+
+        assert_eq!(get_maximum_head_size(&configuration), 64 + 8192 + 16384 + 100 * 4);
+        // hyper refuses read buffers below 8 KiB...
+        let configuration = HttpServerConfiguration::from_arguments(&["--maximumUriLength", "10", "--maximumHeaderSize", "10"]);
+
+        assert_eq!(get_maximum_head_size(&configuration), MINIMUM_READ_BUFFER_SIZE);
+    }
+}
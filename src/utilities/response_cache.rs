@@ -0,0 +1,714 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero, clippy::assign_op_pattern)]
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use hyper::{
+    header::{HeaderMap, HeaderValue},
+    http::uri::PathAndQuery,
+    Body, Method, Request, Response, StatusCode,
+};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use log::{debug, info, warn};
+
+const CACHE_CONTROL_HEADER_NAME: &str = "cache-control";
+const CACHE_STATUS_HEADER_NAME: &str = "cache-status";
+// comma separated tags by which cached responses can be purged (e.g. "user-1, users")...
+const CACHE_TAG_HEADER_NAME: &str = "cache-tag";
+// identifies us in the cache-status header (RFC 9211)...
+const CACHE_NAME: &str = "volcano";
+// statuses that may be cached (RFC 9110 section 15.1). others are never stored...
+const CACHEABLE_STATUS_CODES: [u16; 11] = [200, 203, 204, 300, 301, 308, 404, 405, 410, 414, 501];
+// bookkeeping (e.g. maps and timestamps) taken by every entry besides its headers and body...
+const ENTRY_OVERHEAD_IN_BYTES: usize = 256;
+// a single response may take at most this fraction of the memory budget, so that
+// one large response can't evict everything else...
+const MAXIMUM_ENTRY_SIZE_DIVISOR: usize = 8;
+// a revalidation that takes longer than this is assumed to be lost and may be started again...
+const REVALIDATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+// the parts of a request the cache needs, taken before the request is consumed...
+pub struct CacheRequest {
+    method: Method,
+    // the path and query (e.g. "/users/1?fields=name")...
+    primary_key: String,
+    headers: HeaderMap,
+    // false if the client asked for a response from the parent process (e.g. "no-cache")...
+    is_lookup_allowed: bool,
+}
+
+pub enum CacheLookup {
+    Fresh(Response<Body>),
+    // served stale while the response is being revalidated in the background...
+    StaleWhileRevalidate(Response<Body>),
+    // served instead of an error of the parent process...
+    StaleIfError(Response<Body>),
+    Miss,
+}
+
+// a purge request received from the parent process...
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeRequest {
+    // paths with query as they were requested (e.g. "/users/1")...
+    #[serde(default)]
+    pub keys: Vec<String>,
+    // as listed in the cache-tag header of the responses...
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub all: bool,
+}
+
+struct CacheEntry {
+    primary_key: String,
+    // values of the request headers named by the vary header of the response. the
+    // host is always included as virtual hosts may respond differently to the same path...
+    vary_header_values: Vec<(String, Option<String>)>,
+    status_code: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    tags: Vec<String>,
+    stored_at: Instant,
+    // age of the response when it was stored (i.e. its age header)...
+    initial_age: Duration,
+    freshness_lifetime: Duration,
+    stale_while_revalidate: Duration,
+    stale_if_error: Duration,
+    size_in_bytes: usize,
+    last_used_tick: u64,
+    revalidation_started_at: Option<Instant>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<u64, CacheEntry>,
+    entry_ids_by_primary_key: HashMap<String, Vec<u64>>,
+    // the first entry is the least recently used one...
+    entry_ids_by_last_used_tick: BTreeMap<u64, u64>,
+    next_entry_id: u64,
+    next_tick: u64,
+    size_in_bytes: usize,
+}
+
+// HTTP cache (RFC 9111) for responses of the parent process, shared by all connections...
+#[derive(Clone)]
+pub struct ResponseCache {
+    maximum_size_in_bytes: usize,
+    state_arc: Arc<Mutex<CacheState>>,
+}
+
+// e.g. "max-age=60, public" yields "max-age" => Some("60") and "public" => None...
+fn parse_cache_control(headers: &HeaderMap) -> HashMap<String, Option<String>> {
+    let mut directives: HashMap<String, Option<String>> = HashMap::new();
+
+    for header_value in headers.get_all(CACHE_CONTROL_HEADER_NAME).iter() {
+        let header_value = String::from_utf8_lossy(header_value.as_bytes());
+
+        for directive in header_value.split(',') {
+            let (name, value_option) = directive.split_once('=')
+                .map_or((directive, None), |(name, value)| (name, Some(value.trim().trim_matches('"').to_string())));
+            let name = name.trim().to_lowercase();
+
+            if name.len() > 0 {
+                directives.insert(name, value_option);
+            }
+        }
+    }
+
+    return directives;
+}
+
+fn get_seconds(directives: &HashMap<String, Option<String>>, name: &str) -> Option<Duration> {
+    return directives.get(name)
+        .and_then(|value_option| value_option.as_ref())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(Duration::from_secs);
+}
+
+fn get_header_as_string(headers: &HeaderMap, header_name: &str) -> Option<String> {
+    let header_values: Vec<String> = headers.get_all(header_name)
+        .iter()
+        .map(|header_value| String::from_utf8_lossy(header_value.as_bytes()).into_owned())
+        .collect();
+
+    if header_values.len() == 0 {
+        return None;
+    }
+
+    // multiple header lines are equivalent to a single comma separated line...
+    return Some(header_values.join(", "));
+}
+
+fn parse_http_date(headers: &HeaderMap, header_name: &str) -> Option<DateTime<Utc>> {
+    return get_header_as_string(headers, header_name)
+        .and_then(|http_date| DateTime::parse_from_rfc2822(http_date.as_str()).ok())
+        .map(|date_time| date_time.with_timezone(&Utc));
+}
+
+// explicit freshness as per RFC 9111 section 4.2.1. heuristic freshness is not used, so
+// responses without s-maxage, max-age or expires are not stored...
+fn get_freshness_lifetime(headers: &HeaderMap, directives: &HashMap<String, Option<String>>) -> Option<Duration> {
+    let max_age_option = get_seconds(directives, "s-maxage").or(get_seconds(directives, "max-age"));
+
+    if max_age_option.is_some() {
+        return max_age_option;
+    }
+
+    let expires_option = parse_http_date(headers, "expires");
+
+    // an invalid date (e.g. "0") means the response has already expired...
+    if expires_option.is_none() {
+        return headers.contains_key("expires").then_some(Duration::ZERO);
+    }
+
+    let date = parse_http_date(headers, "date").unwrap_or(Utc::now());
+
+    return Some((expires_option.unwrap() - date).to_std().unwrap_or(Duration::ZERO));
+}
+
+fn get_vary_header_names(headers: &HeaderMap) -> Vec<String> {
+    let mut vary_header_names: Vec<String> = get_header_as_string(headers, "vary")
+        .unwrap_or_default()
+        .split(',')
+        .map(|header_name| header_name.trim().to_lowercase())
+        .filter(|header_name| header_name.len() > 0)
+        .collect();
+
+    vary_header_names.push(String::from("host"));
+
+    return vary_header_names;
+}
+
+fn get_vary_header_values(vary_header_names: &[String], request_headers: &HeaderMap) -> Vec<(String, Option<String>)> {
+    return vary_header_names.iter()
+        .map(|header_name| (header_name.to_owned(), get_header_as_string(request_headers, header_name.as_str())))
+        .collect();
+}
+
+fn get_header_size_in_bytes(headers: &HeaderMap) -> usize {
+    return headers.iter()
+        .map(|(header_name, header_value)| header_name.as_str().len() + header_value.len())
+        .sum();
+}
+
+fn set_cache_status(response: &mut Response<Body>, cache_status: &str) {
+    let header_value = HeaderValue::from_str(format!("{}; {}", CACHE_NAME, cache_status).as_str()).unwrap();
+
+    response.headers_mut().insert(CACHE_STATUS_HEADER_NAME, header_value);
+}
+
+impl CacheRequest {
+    // GET and HEAD are served from the cache...
+    pub fn is_cacheable(&self) -> bool {
+        return self.method == Method::GET || self.method == Method::HEAD;
+    }
+
+    // a successful response to any other method (e.g. PUT or DELETE) invalidates
+    // what is cached for the same path (RFC 9111 section 4.4)...
+    pub fn is_invalidating(&self) -> bool {
+        return !self.is_cacheable() && self.method != Method::OPTIONS && self.method != Method::TRACE;
+    }
+}
+
+impl CacheEntry {
+    fn get_age(&self, now: Instant) -> Duration {
+        return self.initial_age + now.saturating_duration_since(self.stored_at);
+    }
+
+    fn to_response(&self, age: Duration, cache_status: &str) -> Response<Body> {
+        let mut response = Response::builder()
+            .status(self.status_code)
+            .body(Body::from(self.body.clone()))
+            .unwrap();
+
+        *response.headers_mut() = self.headers.clone();
+        response.headers_mut().insert("age", age.as_secs().into());
+        set_cache_status(&mut response, cache_status);
+
+        return response;
+    }
+}
+
+impl CacheState {
+    fn find_entry_id(&self, primary_key: &str, request_headers: &HeaderMap) -> Option<u64> {
+        let entry_ids = self.entry_ids_by_primary_key.get(primary_key)?;
+
+        // the variant whose vary headers match the ones of the request...
+        return entry_ids.iter()
+            .find(|entry_id| {
+                let entry = &self.entries[entry_id];
+                let vary_header_names: Vec<String> = entry.vary_header_values.iter()
+                    .map(|(header_name, _)| header_name.to_owned())
+                    .collect();
+
+                return entry.vary_header_values == get_vary_header_values(&vary_header_names, request_headers);
+            })
+            .copied();
+    }
+
+    fn touch(&mut self, entry_id: u64) {
+        let tick = self.next_tick;
+        let entry = self.entries.get_mut(&entry_id).unwrap();
+
+        self.next_tick = self.next_tick + 1;
+        self.entry_ids_by_last_used_tick.remove(&entry.last_used_tick);
+        self.entry_ids_by_last_used_tick.insert(tick, entry_id);
+        entry.last_used_tick = tick;
+    }
+
+    fn remove(&mut self, entry_id: u64) {
+        let entry_option = self.entries.remove(&entry_id);
+
+        if entry_option.is_none() {
+            return;
+        }
+
+        let entry = entry_option.unwrap();
+
+        self.entry_ids_by_last_used_tick.remove(&entry.last_used_tick);
+        self.size_in_bytes = self.size_in_bytes - entry.size_in_bytes;
+
+        let entry_ids = self.entry_ids_by_primary_key.get_mut(&entry.primary_key).unwrap();
+
+        entry_ids.retain(|other_entry_id| *other_entry_id != entry_id);
+
+        if entry_ids.len() == 0 {
+            self.entry_ids_by_primary_key.remove(&entry.primary_key);
+        }
+    }
+
+    fn insert(&mut self, entry: CacheEntry, maximum_size_in_bytes: usize) {
+        // the least recently used entries make room for the new one...
+        while self.size_in_bytes + entry.size_in_bytes > maximum_size_in_bytes {
+            let first_entry_option = self.entry_ids_by_last_used_tick.first_key_value();
+
+            if first_entry_option.is_none() {
+                break;
+            }
+
+            let entry_id = *first_entry_option.unwrap().1;
+
+            debug!("Evicting cached response of '{}'.", self.entries[&entry_id].primary_key);
+
+            self.remove(entry_id);
+        }
+
+        let entry_id = self.next_entry_id;
+
+        self.next_entry_id = self.next_entry_id + 1;
+        self.size_in_bytes = self.size_in_bytes + entry.size_in_bytes;
+        self.entry_ids_by_primary_key.entry(entry.primary_key.clone()).or_default().push(entry_id);
+        self.entries.insert(entry_id, entry);
+        self.touch(entry_id);
+    }
+}
+
+impl ResponseCache {
+    pub fn new(maximum_size_in_bytes: usize) -> Self {
+        return ResponseCache {
+            maximum_size_in_bytes,
+            state_arc: Arc::new(Mutex::new(CacheState::default())),
+        };
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        return self.maximum_size_in_bytes > 0;
+    }
+
+    // none if the request must bypass the cache...
+    pub fn create_request(&self, request: &Request<Body>) -> Option<CacheRequest> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let directives = parse_cache_control(request.headers());
+
+        // the client does not want the response to be stored...
+        if directives.contains_key("no-store") {
+            return None;
+        }
+
+        let is_pragma_no_cache = get_header_as_string(request.headers(), "pragma")
+            .is_some_and(|pragma| pragma.to_lowercase().contains("no-cache"));
+        let is_lookup_allowed = !directives.contains_key("no-cache")
+            && !is_pragma_no_cache
+            && get_seconds(&directives, "max-age") != Some(Duration::ZERO);
+
+        let mut headers = request.headers().clone();
+
+        // HTTP/2 and HTTP/3 carry the host in the URI...
+        if !headers.contains_key("host") && request.uri().authority().is_some() {
+            let host_result = HeaderValue::from_str(request.uri().authority().unwrap().as_str());
+
+            if host_result.is_ok() {
+                headers.insert("host", host_result.unwrap());
+            }
+        }
+
+        return Some(CacheRequest {
+            method: request.method().clone(),
+            primary_key: request.uri().path_and_query().map_or("/", PathAndQuery::as_str).to_string(),
+            headers,
+            is_lookup_allowed,
+        });
+    }
+
+    pub fn lookup(&self, cache_request: &CacheRequest) -> CacheLookup {
+        if !cache_request.is_lookup_allowed {
+            return CacheLookup::Miss;
+        }
+
+        let mut state = self.state_arc.lock().unwrap();
+        let entry_id_option = state.find_entry_id(cache_request.primary_key.as_str(), &cache_request.headers);
+
+        if entry_id_option.is_none() {
+            return CacheLookup::Miss;
+        }
+
+        let entry_id = entry_id_option.unwrap();
+        let now = Instant::now();
+
+        state.touch(entry_id);
+
+        let entry = state.entries.get_mut(&entry_id).unwrap();
+        let age = entry.get_age(now);
+
+        if age < entry.freshness_lifetime {
+            let time_to_live = entry.freshness_lifetime - age;
+
+            return CacheLookup::Fresh(entry.to_response(age, format!("hit; ttl={}", time_to_live.as_secs()).as_str()));
+        }
+
+        let staleness = age - entry.freshness_lifetime;
+        let is_revalidating = entry.revalidation_started_at
+            .is_some_and(|revalidation_started_at| now.duration_since(revalidation_started_at) < REVALIDATION_TIMEOUT);
+
+        if staleness < entry.stale_while_revalidate {
+            let response = entry.to_response(age, "hit; detail=stale-while-revalidate");
+
+            // a single revalidation at a time is enough. and as a HEAD response
+            // can't replace the entry, only GET requests revalidate it...
+            if is_revalidating || cache_request.method != Method::GET {
+                return CacheLookup::Fresh(response);
+            }
+
+            entry.revalidation_started_at = Some(now);
+
+            return CacheLookup::StaleWhileRevalidate(response);
+        }
+
+        if staleness < entry.stale_if_error {
+            return CacheLookup::StaleIfError(entry.to_response(age, "hit; detail=stale-if-error"));
+        }
+
+        return CacheLookup::Miss;
+    }
+
+    fn create_entry(&self, cache_request: &CacheRequest, response: &Response<Body>, body: &Bytes) -> Option<CacheEntry> {
+        let headers = response.headers();
+        let directives = parse_cache_control(headers);
+
+        // HEAD responses have no body to serve GET requests with...
+        if cache_request.method != Method::GET || !CACHEABLE_STATUS_CODES.contains(&response.status().as_u16()) {
+            return None;
+        }
+
+        // responses meant for a single client or that must be revalidated every time...
+        if ["no-store", "no-cache", "private"].iter().any(|directive| directives.contains_key(*directive)) {
+            return None;
+        }
+
+        // cookies belong to a single client...
+        if headers.contains_key("set-cookie") {
+            return None;
+        }
+
+        // responses to authorized requests are shared only if explicitly allowed (RFC 9111 section 3.5)...
+        if cache_request.headers.contains_key("authorization")
+            && !["public", "s-maxage", "must-revalidate"].iter().any(|directive| directives.contains_key(*directive)) {
+            return None;
+        }
+
+        let vary_header_names = get_vary_header_names(headers);
+
+        // the response varies on something other than request headers...
+        if vary_header_names.iter().any(|header_name| header_name == "*") {
+            return None;
+        }
+
+        let freshness_lifetime = get_freshness_lifetime(headers, &directives)?;
+
+        let tags: Vec<String> = get_header_as_string(headers, CACHE_TAG_HEADER_NAME)
+            .unwrap_or_default()
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| tag.len() > 0)
+            .collect();
+        let mut entry_headers = headers.clone();
+
+        entry_headers.remove(CACHE_TAG_HEADER_NAME);
+        entry_headers.remove(CACHE_STATUS_HEADER_NAME);
+
+        let size_in_bytes = ENTRY_OVERHEAD_IN_BYTES
+            + cache_request.primary_key.len()
+            + get_header_size_in_bytes(&entry_headers)
+            + body.len();
+
+        if size_in_bytes > self.maximum_size_in_bytes / MAXIMUM_ENTRY_SIZE_DIVISOR {
+            debug!("Response of '{}' is too large to be cached ({} bytes).", cache_request.primary_key, size_in_bytes);
+
+            return None;
+        }
+
+        let initial_age = get_header_as_string(headers, "age")
+            .and_then(|age| age.parse::<u64>().ok())
+            .map_or(Duration::ZERO, Duration::from_secs);
+
+        return Some(CacheEntry {
+            primary_key: cache_request.primary_key.clone(),
+            vary_header_values: get_vary_header_values(&vary_header_names, &cache_request.headers),
+            status_code: response.status(),
+            headers: entry_headers,
+            body: body.clone(),
+            tags,
+            stored_at: Instant::now(),
+            initial_age,
+            freshness_lifetime,
+            stale_while_revalidate: get_seconds(&directives, "stale-while-revalidate").unwrap_or(Duration::ZERO),
+            stale_if_error: get_seconds(&directives, "stale-if-error").unwrap_or(Duration::ZERO),
+            size_in_bytes,
+            last_used_tick: 0,
+            revalidation_started_at: None,
+        });
+    }
+
+    // stores the response of the parent process (if allowed) and returns it to be sent...
+    pub async fn store_async(&self, cache_request: &CacheRequest, response: Response<Body>, forward_reason: &str) -> Response<Body> {
+        let (parts, body) = response.into_parts();
+        // the body of a parent process response is already in memory...
+        let body_result = hyper::body::to_bytes(body).await;
+
+        if body_result.is_err() {
+            let error = body_result.unwrap_err();
+
+            warn!("An error occurred while reading response of '{}' to be cached: {}", cache_request.primary_key, error);
+
+            return Response::from_parts(parts, Body::empty());
+        }
+
+        let body = body_result.unwrap();
+        let mut response = Response::from_parts(parts, Body::from(body.clone()));
+        let entry_option = self.create_entry(cache_request, &response, &body);
+        let is_stored = entry_option.is_some();
+
+        // HEAD responses leave the cached GET response as it is...
+        if cache_request.method == Method::GET {
+            let mut state = self.state_arc.lock().unwrap();
+            let previous_entry_id_option = state.find_entry_id(cache_request.primary_key.as_str(), &cache_request.headers);
+
+            if let Some(previous_entry_id) = previous_entry_id_option {
+                // if the parent process failed, the stale response is kept (e.g. for stale-if-error)...
+                if !is_stored && response.status().is_server_error() {
+                    state.entries.get_mut(&previous_entry_id).unwrap().revalidation_started_at = None;
+                } else {
+                    state.remove(previous_entry_id);
+                }
+            }
+
+            if is_stored {
+                state.insert(entry_option.unwrap(), self.maximum_size_in_bytes);
+            }
+        }
+
+        // tags are meant for the cache only...
+        response.headers_mut().remove(CACHE_TAG_HEADER_NAME);
+        set_cache_status(&mut response, if is_stored {
+            format!("fwd={}; stored", forward_reason)
+        } else {
+            format!("fwd={}", forward_reason)
+        }.as_str());
+
+        return response;
+    }
+
+    // removes every variant cached for the path of the request...
+    pub fn invalidate(&self, cache_request: &CacheRequest) {
+        let mut state = self.state_arc.lock().unwrap();
+        let entry_ids = state.entry_ids_by_primary_key.get(&cache_request.primary_key).cloned().unwrap_or_default();
+
+        for entry_id in entry_ids {
+            state.remove(entry_id);
+        }
+    }
+
+    // returns the number of responses purged...
+    pub fn purge(&self, purge_request: &PurgeRequest) -> usize {
+        let mut state = self.state_arc.lock().unwrap();
+        let entry_ids: Vec<u64> = state.entries.iter()
+            .filter(|(_, entry)| purge_request.all
+                || purge_request.keys.contains(&entry.primary_key)
+                || entry.tags.iter().any(|tag| purge_request.tags.contains(tag)))
+            .map(|(entry_id, _)| *entry_id)
+            .collect();
+
+        for entry_id in entry_ids.iter() {
+            state.remove(*entry_id);
+        }
+
+        info!("Purged {} cached responses ({} bytes remain cached).", entry_ids.len(), state.size_in_bytes);
+
+        return entry_ids.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_cache_request(response_cache: &ResponseCache, uri: &str, headers: &[(&str, &str)]) -> CacheRequest {
+        let mut request_builder = Request::builder().uri(uri).header("host", "example.com");
+
+        for (header_name, header_value) in headers {
+            request_builder = request_builder.header(*header_name, *header_value);
+        }
+
+        return response_cache.create_request(&request_builder.body(Body::empty()).unwrap()).unwrap();
+    }
+
+    async fn store_async(response_cache: &ResponseCache, cache_request: &CacheRequest, headers: &[(&str, &str)]) -> Response<Body> {
+        let mut response_builder = Response::builder().status(200);
+
+        for (header_name, header_value) in headers {
+            response_builder = response_builder.header(*header_name, *header_value);
+        }
+
+        return response_cache.store_async(cache_request, response_builder.body(Body::from("cached")).unwrap(), "miss").await;
+    }
+
+    fn get_cache_status(response: &Response<Body>) -> &str {
+        return response.headers().get(CACHE_STATUS_HEADER_NAME).unwrap().to_str().unwrap();
+    }
+
+    fn get_lifetime(headers: &[(&'static str, &str)]) -> Option<Duration> {
+        let mut header_map = HeaderMap::new();
+
+        for (header_name, header_value) in headers {
+            header_map.append(*header_name, HeaderValue::from_str(header_value).unwrap());
+        }
+
+        return get_freshness_lifetime(&header_map, &parse_cache_control(&header_map));
+    }
+
+    #[test]
+    fn computes_freshness_lifetime() {
+        assert_eq!(get_lifetime(&[("cache-control", "max-age=60, s-maxage=120")]), Some(Duration::from_secs(120)));
+        assert_eq!(get_lifetime(&[("cache-control", "public"), ("cache-control", "max-age=\"30\"")]), Some(Duration::from_secs(30)));
+        assert_eq!(get_lifetime(&[
+            ("date", "Mon, 19 Oct 2026 10:00:00 GMT"),
+            ("expires", "Mon, 19 Oct 2026 10:05:00 GMT"),
+        ]), Some(Duration::from_secs(300)));
+        assert_eq!(get_lifetime(&[("expires", "0")]), Some(Duration::ZERO));
+        assert_eq!(get_lifetime(&[("cache-control", "public")]), None);
+    }
+
+    #[tokio::test]
+    async fn serves_fresh_responses_until_they_expire() {
+        let response_cache = ResponseCache::new(1024 * 1024);
+        let cache_request = create_cache_request(&response_cache, "/a?b=1", &[]);
+        let response = store_async(&response_cache, &cache_request, &[("cache-control", "max-age=60"), ("cache-tag", "a")]).await;
+
+        assert_eq!(get_cache_status(&response), "volcano; fwd=miss; stored");
+        assert!(!response.headers().contains_key(CACHE_TAG_HEADER_NAME));
+
+        let CacheLookup::Fresh(response) = response_cache.lookup(&cache_request) else { panic!("expected a fresh response") };
+
+        // the time to live is rounded down...
+        assert!(matches!(get_cache_status(&response), "volcano; hit; ttl=60" | "volcano; hit; ttl=59"));
+        assert_eq!(hyper::body::to_bytes(response.into_body()).await.unwrap(), "cached");
+        // the query is part of the key...
+        assert!(matches!(response_cache.lookup(&create_cache_request(&response_cache, "/a", &[])), CacheLookup::Miss));
+        // the client may ask for a response from the parent process...
+        assert!(matches!(response_cache.lookup(&create_cache_request(&response_cache, "/a?b=1", &[("cache-control", "no-cache")])), CacheLookup::Miss));
+
+        // a response that is older than its lifetime (as per its age header) is stale...
+        store_async(&response_cache, &cache_request, &[("cache-control", "max-age=60"), ("age", "60")]).await;
+
+        assert!(matches!(response_cache.lookup(&cache_request), CacheLookup::Miss));
+    }
+
+    #[tokio::test]
+    async fn serves_stale_responses_if_allowed() {
+        let response_cache = ResponseCache::new(1024 * 1024);
+        let cache_request = create_cache_request(&response_cache, "/a", &[]);
+
+        store_async(&response_cache, &cache_request, &[("cache-control", "max-age=60, stale-while-revalidate=30"), ("age", "70")]).await;
+
+        assert!(matches!(response_cache.lookup(&cache_request), CacheLookup::StaleWhileRevalidate(_)));
+        // a single revalidation at a time...
+        assert!(matches!(response_cache.lookup(&cache_request), CacheLookup::Fresh(_)));
+
+        store_async(&response_cache, &cache_request, &[("cache-control", "max-age=60, stale-if-error=30"), ("age", "70")]).await;
+
+        assert!(matches!(response_cache.lookup(&cache_request), CacheLookup::StaleIfError(_)));
+    }
+
+    #[tokio::test]
+    async fn keeps_a_variant_per_vary_header_values() {
+        let response_cache = ResponseCache::new(1024 * 1024);
+        let english_cache_request = create_cache_request(&response_cache, "/a", &[("accept-language", "en")]);
+        let german_cache_request = create_cache_request(&response_cache, "/a", &[("accept-language", "de")]);
+
+        store_async(&response_cache, &english_cache_request, &[("cache-control", "max-age=60"), ("vary", "Accept-Language")]).await;
+
+        assert!(matches!(response_cache.lookup(&english_cache_request), CacheLookup::Fresh(_)));
+        assert!(matches!(response_cache.lookup(&german_cache_request), CacheLookup::Miss));
+        assert!(matches!(response_cache.lookup(&create_cache_request(&response_cache, "/a", &[])), CacheLookup::Miss));
+
+        store_async(&response_cache, &german_cache_request, &[("cache-control", "max-age=60"), ("vary", "Accept-Language")]).await;
+
+        assert!(matches!(response_cache.lookup(&english_cache_request), CacheLookup::Fresh(_)));
+        assert!(matches!(response_cache.lookup(&german_cache_request), CacheLookup::Fresh(_)));
+
+        // the host is always part of the variant...
+        let request = Request::builder().uri("/a").header("host", "example.org").header("accept-language", "en").body(Body::empty()).unwrap();
+
+        assert!(matches!(response_cache.lookup(&response_cache.create_request(&request).unwrap()), CacheLookup::Miss));
+        // the path is purged with all of its variants...
+        assert_eq!(response_cache.purge(&PurgeRequest { keys: vec![String::from("/a")], tags: Vec::new(), all: false }), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_store_private_responses() {
+        let response_cache = ResponseCache::new(1024 * 1024);
+        let cache_request = create_cache_request(&response_cache, "/a", &[]);
+        let response_headers: [&[(&str, &str)]; 5] = [
+            &[("cache-control", "max-age=60, private")],
+            &[("cache-control", "max-age=60, no-store")],
+            &[("cache-control", "max-age=60"), ("set-cookie", "a=1")],
+            &[("cache-control", "max-age=60"), ("vary", "*")],
+            &[("last-modified", "Mon, 19 Oct 2026 10:00:00 GMT")],
+        ];
+
+        for headers in response_headers {
+            let response = store_async(&response_cache, &cache_request, headers).await;
+
+            assert_eq!(get_cache_status(&response), "volcano; fwd=miss");
+        }
+
+        // responses to authorized requests are shared only if explicitly allowed...
+        let cache_request = create_cache_request(&response_cache, "/a", &[("authorization", "Bearer x")]);
+
+        store_async(&response_cache, &cache_request, &[("cache-control", "max-age=60")]).await;
+
+        assert!(matches!(response_cache.lookup(&cache_request), CacheLookup::Miss));
+
+        store_async(&response_cache, &cache_request, &[("cache-control", "max-age=60, public")]).await;
+
+        assert!(matches!(response_cache.lookup(&cache_request), CacheLookup::Fresh(_)));
+    }
+}
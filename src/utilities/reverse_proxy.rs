@@ -0,0 +1,707 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use futures::future::join_all;
+use hyper::{
+    body::HttpBody,
+    client::{connect::{Connected, Connection}, HttpConnector},
+    header::{HeaderMap, HeaderValue},
+    http::uri::PathAndQuery,
+    service::Service,
+    Body, Client, Method, Request, Response, StatusCode, Uri, Version,
+};
+use ipnet::IpNet;
+use rustls::{ClientConfig, RootCertStore, ServerName};
+use serde::Deserialize;
+use std::{
+    error::Error,
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{atomic::{AtomicUsize, Ordering}, Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+    time::{sleep, timeout},
+};
+use tokio_rustls::{client::TlsStream, TlsConnector};
+use log::{debug, info, log, warn, Level};
+
+use crate::{
+    forwarding_utilities::{self, TrustedProxyHeader, FORWARDED_HEADER_NAME, X_FORWARDED_FOR_HEADER_NAME, X_FORWARDED_HOST_HEADER_NAME, X_FORWARDED_PROTO_HEADER_NAME},
+    http_server_configuration::HttpServerConfiguration,
+    http_utilities::{self, CONNECTION_SPECIFIC_HEADER_NAMES},
+    remote_address::RemoteAddress,
+};
+
+// these describe a single hop and must not be passed on by a proxy...
+const HOP_BY_HOP_HEADER_NAMES: [&str; 3] = ["te", "trailer", "proxy-authorization"];
+// methods that may be sent again without changing the outcome (RFC 9110 section 9.2.2)...
+const IDEMPOTENT_METHODS: [Method; 6] = [Method::GET, Method::HEAD, Method::OPTIONS, Method::TRACE, Method::PUT, Method::DELETE];
+const HTTP2_PROTOCOL_NAME: &[u8] = b"h2";
+const HTTP1_PROTOCOL_NAME: &[u8] = b"http/1.1";
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+// an upstream group as written in the routes file...
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpstreamGroupDefinition {
+    // a single upstream (e.g. "http://127.0.0.1:8080")...
+    #[serde(default)]
+    upstream: String,
+    // requests are balanced among these (e.g. ["http://10.0.0.1:8080", "https://10.0.0.2"])...
+    #[serde(default)]
+    upstreams: Vec<String>,
+    // if set, every upstream is probed periodically (e.g. "/health")...
+    #[serde(default)]
+    health_check_path: String,
+    // speaks HTTP/2 without upgrade (prior knowledge) to http:// upstreams.
+    // https:// upstreams negotiate HTTP/2 via ALPN anyway...
+    #[serde(default)]
+    http2: bool,
+    // passes the host of the request on instead of the host of the upstream...
+    #[serde(default)]
+    preserve_host: bool,
+}
+
+#[derive(Debug)]
+struct UpstreamHealth {
+    // result of the last health check...
+    is_healthy: bool,
+    // set when a request to the upstream failed...
+    unavailable_until: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct UpstreamTarget {
+    // e.g. "http://127.0.0.1:8080/api" without trailing slash...
+    base_uri: String,
+    health: Mutex<UpstreamHealth>,
+}
+
+#[derive(Debug)]
+struct UpstreamGroupState {
+    targets: Vec<UpstreamTarget>,
+    next_target_index: AtomicUsize,
+    health_check_path: String,
+    is_http2_prior_knowledge: bool,
+    is_host_preserved: bool,
+}
+
+// the upstreams of a proxy route. the state is shared by all clones...
+#[derive(Deserialize, Clone, Debug)]
+#[serde(from = "UpstreamGroupDefinition")]
+pub struct UpstreamGroup {
+    state_arc: Arc<UpstreamGroupState>,
+}
+
+// connects to http:// upstreams via TCP and to https:// upstreams via TLS...
+#[derive(Clone)]
+struct UpstreamConnector {
+    http_connector: HttpConnector,
+    tls_connector_option: Option<TlsConnector>,
+    upstream_ca_path: String,
+}
+
+enum UpstreamStream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+// forwards requests to upstream HTTP servers. connections to the upstreams are pooled...
+#[derive(Clone)]
+pub struct ReverseProxy {
+    client: Client<UpstreamConnector>,
+    http2_client: Client<UpstreamConnector>,
+    response_timeout_option: Option<Duration>,
+    retry_count: usize,
+    health_check_interval_option: Option<Duration>,
+}
+
+fn is_valid_upstream(upstream: &str) -> bool {
+    let upstream_uri_result = upstream.parse::<Uri>();
+
+    if upstream_uri_result.is_err() {
+        return false;
+    }
+
+    let upstream_uri = upstream_uri_result.unwrap();
+    let is_supported_scheme = upstream_uri.scheme_str() == Some("http") || upstream_uri.scheme_str() == Some("https");
+
+    return is_supported_scheme && upstream_uri.host().is_some() && upstream_uri.query().is_none();
+}
+
+impl From<UpstreamGroupDefinition> for UpstreamGroup {
+    fn from(upstream_group_definition: UpstreamGroupDefinition) -> Self {
+        let mut targets: Vec<UpstreamTarget> = Vec::with_capacity(upstream_group_definition.upstreams.len() + 1);
+        let upstreams = std::iter::once(&upstream_group_definition.upstream)
+            .filter(|upstream| upstream.len() > 0)
+            .chain(upstream_group_definition.upstreams.iter());
+
+        // invalid upstreams are skipped (the route is rejected if none is left)...
+        for upstream in upstreams {
+            if !is_valid_upstream(upstream) {
+                warn!("Invalid upstream '{}'. Expected an http:// or https:// URL.", upstream);
+
+                continue;
+            }
+
+            targets.push(UpstreamTarget {
+                base_uri: upstream.trim_end_matches('/').to_string(),
+                health: Mutex::new(UpstreamHealth { is_healthy: true, unavailable_until: None }),
+            });
+        }
+
+        let mut health_check_path = upstream_group_definition.health_check_path;
+
+        if health_check_path.len() > 0 && !health_check_path.starts_with('/') {
+            warn!("Invalid health check path '{}'. Paths must start with '/'.", health_check_path);
+
+            health_check_path = String::from("");
+        }
+
+        return UpstreamGroup {
+            state_arc: Arc::new(UpstreamGroupState {
+                targets,
+                next_target_index: AtomicUsize::new(0),
+                health_check_path,
+                is_http2_prior_knowledge: upstream_group_definition.http2,
+                is_host_preserved: upstream_group_definition.preserve_host,
+            }),
+        };
+    }
+}
+
+impl UpstreamTarget {
+    fn is_available(&self, now: Instant) -> bool {
+        let health = self.health.lock().unwrap();
+
+        return health.is_healthy && health.unavailable_until.is_none_or(|unavailable_until| now >= unavailable_until);
+    }
+
+    fn mark_failed(&self, cool_down_option: Option<Duration>) {
+        // zero disables health checking altogether...
+        if cool_down_option.is_none() {
+            return;
+        }
+
+        let mut health = self.health.lock().unwrap();
+
+        if health.unavailable_until.is_none() {
+            warn!("Upstream '{}' is unavailable for {:?}.", self.base_uri, cool_down_option.unwrap());
+        }
+
+        health.unavailable_until = Some(Instant::now() + cool_down_option.unwrap());
+    }
+
+    fn mark_succeeded(&self) {
+        self.health.lock().unwrap().unavailable_until = None;
+    }
+
+    fn set_healthy(&self, is_healthy: bool) {
+        let mut health = self.health.lock().unwrap();
+
+        if health.is_healthy != is_healthy {
+            if is_healthy {
+                info!("Upstream '{}' passed its health check.", self.base_uri);
+            } else {
+                warn!("Upstream '{}' failed its health check.", self.base_uri);
+            }
+        }
+
+        health.is_healthy = is_healthy;
+
+        // a passing health check ends the cool down as well...
+        if is_healthy {
+            health.unavailable_until = None;
+        }
+    }
+}
+
+impl UpstreamGroup {
+    pub fn get_target_count(&self) -> usize {
+        return self.state_arc.targets.len();
+    }
+
+    pub fn is_health_checked(&self) -> bool {
+        return self.state_arc.health_check_path.len() > 0;
+    }
+
+    // round robin among the available targets not tried yet. if none of
+    // them is available, we shall try one of them anyway...
+    fn select_target_index(&self, tried_target_indices: &[usize]) -> usize {
+        let targets = &self.state_arc.targets;
+        let now = Instant::now();
+        let first_index = self.state_arc.next_target_index.fetch_add(1, Ordering::Relaxed);
+        let mut fallback_index_option: Option<usize> = None;
+
+        for offset in 0..targets.len() {
+            let index = (first_index + offset) % targets.len();
+
+            if tried_target_indices.contains(&index) {
+                continue;
+            }
+
+            if targets[index].is_available(now) {
+                return index;
+            }
+
+            fallback_index_option.get_or_insert(index);
+        }
+
+        // if every target has been tried, the same one is tried again...
+        return fallback_index_option.unwrap_or(first_index % targets.len());
+    }
+}
+
+impl AsyncRead for UpstreamStream {
+    fn poll_read(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        return match self.get_mut() {
+            UpstreamStream::Plain(stream) => Pin::new(stream).poll_read(context, buffer),
+            UpstreamStream::Tls(stream) => Pin::new(stream).poll_read(context, buffer),
+        };
+    }
+}
+
+impl AsyncWrite for UpstreamStream {
+    fn poll_write(self: Pin<&mut Self>, context: &mut Context<'_>, buffer: &[u8]) -> Poll<io::Result<usize>> {
+        return match self.get_mut() {
+            UpstreamStream::Plain(stream) => Pin::new(stream).poll_write(context, buffer),
+            UpstreamStream::Tls(stream) => Pin::new(stream).poll_write(context, buffer),
+        };
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return match self.get_mut() {
+            UpstreamStream::Plain(stream) => Pin::new(stream).poll_flush(context),
+            UpstreamStream::Tls(stream) => Pin::new(stream).poll_flush(context),
+        };
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<io::Result<()>> {
+        return match self.get_mut() {
+            UpstreamStream::Plain(stream) => Pin::new(stream).poll_shutdown(context),
+            UpstreamStream::Tls(stream) => Pin::new(stream).poll_shutdown(context),
+        };
+    }
+}
+
+impl Connection for UpstreamStream {
+    fn connected(&self) -> Connected {
+        return match self {
+            UpstreamStream::Plain(stream) => stream.connected(),
+            UpstreamStream::Tls(stream) => {
+                let (tcp_stream, client_connection) = stream.get_ref();
+                let connected = tcp_stream.connected();
+
+                // the client speaks HTTP/2 on this connection if the upstream agreed to...
+                if client_connection.alpn_protocol() == Some(HTTP2_PROTOCOL_NAME) {
+                    connected.negotiated_h2()
+                } else {
+                    connected
+                }
+            },
+        };
+    }
+}
+
+impl Service<Uri> for UpstreamConnector {
+    type Response = UpstreamStream;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<UpstreamStream, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, context: &mut Context<'_>) -> Poll<Result<(), BoxError>> {
+        return self.http_connector.poll_ready(context).map_err(Into::into);
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let mut http_connector = self.http_connector.clone();
+        let tls_connector_option = self.tls_connector_option.clone();
+        let upstream_ca_path = self.upstream_ca_path.clone();
+
+        return Box::pin(async move {
+            let is_tls = uri.scheme_str() == Some("https");
+            // IPv6 addresses are enclosed in brackets within the URI...
+            let host = uri.host().unwrap_or("").trim_start_matches('[').trim_end_matches(']').to_string();
+            let tcp_stream = http_connector.call(uri).await?;
+
+            if !is_tls {
+                return Ok(UpstreamStream::Plain(tcp_stream));
+            }
+
+            if tls_connector_option.is_none() {
+                return Err(format!("No trusted certificate could be loaded from {}.", get_trust_source(upstream_ca_path.as_str())).into());
+            }
+
+            let server_name = ServerName::try_from(host.as_str())?;
+            let tls_stream = tls_connector_option.unwrap().connect(server_name, tcp_stream).await?;
+
+            return Ok(UpstreamStream::Tls(Box::new(tls_stream)));
+        });
+    }
+}
+
+fn get_trust_source(upstream_ca_path: &str) -> String {
+    if upstream_ca_path.len() == 0 {
+        return String::from("the system trust store");
+    }
+
+    return format!("'{}'", upstream_ca_path);
+}
+
+fn load_certificates(upstream_ca_path: &str) -> io::Result<Vec<Vec<u8>>> {
+    // without a CA file, the upstreams are verified against the trust store of the system...
+    if upstream_ca_path.len() == 0 {
+        let certificates = rustls_native_certs::load_native_certs()?;
+
+        return Ok(certificates.into_iter().map(|certificate| certificate.0).collect());
+    }
+
+    let certificates = http_utilities::load_tls_certificate_chain(upstream_ca_path.to_string())?;
+
+    return Ok(certificates.into_iter().map(|certificate| certificate.0).collect());
+}
+
+fn create_tls_connector(upstream_ca_path: &str) -> Option<TlsConnector> {
+    let certificates_result = load_certificates(upstream_ca_path);
+
+    // only matters if there are https:// upstreams, which will report it when used...
+    if certificates_result.is_err() {
+        let error = certificates_result.unwrap_err();
+
+        debug!("An error occurred while loading upstream CA certificates from {}: {}", get_trust_source(upstream_ca_path), error);
+
+        return None;
+    }
+
+    let certificates = certificates_result.unwrap();
+    let mut root_certificate_store = RootCertStore::empty();
+    let (added_certificate_count, ignored_certificate_count) = root_certificate_store.add_parsable_certificates(&certificates);
+
+    if ignored_certificate_count > 0 {
+        debug!("Ignored {} unparsable upstream CA certificates of {}.", ignored_certificate_count, get_trust_source(upstream_ca_path));
+    }
+
+    if added_certificate_count == 0 {
+        return None;
+    }
+
+    let mut client_configuration = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_certificate_store)
+        .with_no_client_auth();
+
+    client_configuration.alpn_protocols = vec![HTTP2_PROTOCOL_NAME.to_vec(), HTTP1_PROTOCOL_NAME.to_vec()];
+
+    return Some(TlsConnector::from(Arc::new(client_configuration)));
+}
+
+// removes the hop-by-hop headers as well as the ones listed in the connection header...
+fn remove_hop_by_hop_headers(headers: &mut HeaderMap) {
+    let listed_header_names: Vec<String> = headers.get_all("connection")
+        .iter()
+        .filter_map(|header_value| header_value.to_str().ok())
+        .flat_map(|header_value| header_value.split(','))
+        .map(|header_name| header_name.trim().to_lowercase())
+        .filter(|header_name| header_name.len() > 0)
+        .collect();
+
+    for header_name in listed_header_names.iter() {
+        headers.remove(header_name.as_str());
+    }
+
+    for header_name in CONNECTION_SPECIFIC_HEADER_NAMES.iter().chain(HOP_BY_HOP_HEADER_NAMES.iter()) {
+        headers.remove(*header_name);
+    }
+}
+
+// e.g. "for=192.0.2.43;proto=https" or "for=\"[2001:db8::1]\";proto=http"...
+fn create_forwarded_element(ip_address: &str, scheme: &str) -> String {
+    if ip_address.contains(':') {
+        return format!("for=\"[{}]\";proto={}", ip_address, scheme);
+    }
+
+    return format!("for={};proto={}", ip_address, scheme);
+}
+
+// tells the upstream who the client is. forwarding headers of untrusted peers and
+// the kind of headers the trusted proxies don't set might be spoofed, so they are
+// replaced instead of appended to...
+fn add_forwarding_headers(
+    headers: &mut HeaderMap,
+    request: &Request<Body>,
+    remote_address: &RemoteAddress,
+    scheme: &str,
+    trusted_proxies: &[IpNet],
+    trusted_proxy_header: TrustedProxyHeader,
+) {
+    let is_peer_trusted = forwarding_utilities::is_peer_trusted(remote_address, trusted_proxies);
+
+    if !is_peer_trusted || trusted_proxy_header != TrustedProxyHeader::Forwarded {
+        headers.remove(FORWARDED_HEADER_NAME);
+    }
+
+    if !is_peer_trusted || trusted_proxy_header != TrustedProxyHeader::XForwardedFor {
+        for header_name in [X_FORWARDED_FOR_HEADER_NAME, X_FORWARDED_PROTO_HEADER_NAME, X_FORWARDED_HOST_HEADER_NAME] {
+            headers.remove(header_name);
+        }
+    }
+
+    // the chain of a Forwarded header set by the trusted proxies is continued as well...
+    if headers.contains_key(FORWARDED_HEADER_NAME) && remote_address.get_ip_address().is_some() {
+        let forwarded_element = create_forwarded_element(remote_address.get_ip_address().unwrap().as_str(), scheme);
+        let mut forwarded_values: Vec<String> = headers.get_all(FORWARDED_HEADER_NAME)
+            .iter()
+            .map(|header_value| http_utilities::to_latin1_string(header_value.as_bytes()))
+            .collect();
+
+        forwarded_values.push(forwarded_element);
+
+        let header_value_result = HeaderValue::from_str(forwarded_values.join(", ").as_str());
+
+        if header_value_result.is_ok() {
+            headers.insert(FORWARDED_HEADER_NAME, header_value_result.unwrap());
+        }
+    }
+
+    // peers connected via Unix domain socket have no address to append...
+    if let Some(ip_address) = remote_address.get_ip_address() {
+        let mut forwarded_for_values: Vec<String> = headers.get_all(X_FORWARDED_FOR_HEADER_NAME)
+            .iter()
+            .map(|header_value| http_utilities::to_latin1_string(header_value.as_bytes()))
+            .collect();
+
+        forwarded_for_values.push(ip_address);
+
+        let header_value_result = HeaderValue::from_str(forwarded_for_values.join(", ").as_str());
+
+        if header_value_result.is_ok() {
+            headers.insert(X_FORWARDED_FOR_HEADER_NAME, header_value_result.unwrap());
+        }
+    }
+
+    // values set by a trusted proxy describe the original request and are kept...
+    if !headers.contains_key(X_FORWARDED_PROTO_HEADER_NAME) {
+        headers.insert(X_FORWARDED_PROTO_HEADER_NAME, HeaderValue::from_str(scheme).unwrap());
+    }
+
+    // HTTP/1.1 carries the host in the header, HTTP/2 and HTTP/3 carry it in the URI...
+    let host_option = request.headers().get("host").cloned()
+        .or(request.uri().authority().and_then(|authority| HeaderValue::from_str(authority.as_str()).ok()));
+
+    if !headers.contains_key(X_FORWARDED_HOST_HEADER_NAME) && host_option.is_some() {
+        headers.insert(X_FORWARDED_HOST_HEADER_NAME, host_option.unwrap());
+    }
+}
+
+fn create_response(status_code: StatusCode) -> Response<Body> {
+    return Response::builder()
+        .status(status_code)
+        .body(Body::from(status_code.canonical_reason().unwrap_or("")))
+        .unwrap();
+}
+
+// appends the path and query of the request to the upstream (e.g. "http://127.0.0.1:8080/api")...
+fn create_upstream_uri(base_uri: &str, path_and_query: &str) -> Option<Uri> {
+    let upstream_uri_result = format!("{}{}", base_uri, path_and_query).parse::<Uri>();
+
+    return upstream_uri_result.ok();
+}
+
+fn create_client(http_connector: &HttpConnector, configuration: &HttpServerConfiguration, is_http2_only: bool) -> Client<UpstreamConnector> {
+    let upstream_connector = UpstreamConnector {
+        http_connector: http_connector.clone(),
+        tls_connector_option: create_tls_connector(configuration.upstream_ca_path.as_str()),
+        upstream_ca_path: configuration.upstream_ca_path.clone(),
+    };
+
+    return Client::builder()
+        .http2_only(is_http2_only)
+        .build(upstream_connector);
+}
+
+impl ReverseProxy {
+    pub fn new(configuration: &HttpServerConfiguration) -> Self {
+        let mut http_connector = HttpConnector::new();
+
+        // https:// URIs are connected via TCP here and via TLS by the upstream connector...
+        http_connector.enforce_http(false);
+        http_connector.set_nodelay(true);
+        http_connector.set_connect_timeout(http_utilities::to_timeout(configuration.upstream_connect_timeout_in_milliseconds));
+
+        return ReverseProxy {
+            client: create_client(&http_connector, configuration, false),
+            http2_client: create_client(&http_connector, configuration, true),
+            response_timeout_option: http_utilities::to_timeout(configuration.upstream_response_timeout_in_milliseconds),
+            retry_count: configuration.upstream_retry_count,
+            health_check_interval_option: http_utilities::to_timeout(configuration.upstream_health_check_interval_in_milliseconds),
+        };
+    }
+
+    pub fn is_health_checking_enabled(&self) -> bool {
+        return self.health_check_interval_option.is_some();
+    }
+
+    fn get_client(&self, upstream_group: &UpstreamGroup, upstream_uri: &Uri) -> &Client<UpstreamConnector> {
+        if upstream_group.state_arc.is_http2_prior_knowledge && upstream_uri.scheme_str() == Some("http") {
+            return &self.http2_client;
+        }
+
+        return &self.client;
+    }
+
+    // sends the request and waits for the head of the response (the body is streamed later)...
+    // a failed health check is reported once the upstream changes state, so the caller decides how loud a failure is...
+    async fn send_async(
+        &self,
+        client: &Client<UpstreamConnector>,
+        request: Request<Body>,
+        failure_log_level: Level,
+    ) -> Result<Response<Body>, StatusCode> {
+        let upstream_uri = request.uri().clone();
+        let response_future = client.request(request);
+        let response_result = if self.response_timeout_option.is_none() {
+            response_future.await
+        } else {
+            let timeout_result = timeout(self.response_timeout_option.unwrap(), response_future).await;
+
+            if timeout_result.is_err() {
+                log!(failure_log_level, "Upstream '{}' did not respond in time.", upstream_uri);
+
+                return Err(StatusCode::GATEWAY_TIMEOUT);
+            }
+
+            timeout_result.unwrap()
+        };
+
+        if response_result.is_err() {
+            let error = response_result.unwrap_err();
+
+            log!(failure_log_level, "An error occurred while sending request to '{}': {}", upstream_uri, error);
+
+            return Err(StatusCode::BAD_GATEWAY);
+        }
+
+        return Ok(response_result.unwrap());
+    }
+
+    pub async fn forward_async(
+        &self,
+        request: &mut Request<Body>,
+        upstream_group: &UpstreamGroup,
+        remote_address: &RemoteAddress,
+        configuration: &HttpServerConfiguration,
+    ) -> Response<Body> {
+        let path_and_query = request.uri()
+            .path_and_query()
+            .map_or("/", PathAndQuery::as_str)
+            .to_string();
+        let scheme = if configuration.is_tls_enabled { "https" } else { "http" };
+        let mut headers = request.headers().clone();
+
+        remove_hop_by_hop_headers(&mut headers);
+        add_forwarding_headers(&mut headers, request, remote_address, scheme,
+            &configuration.trusted_proxies, configuration.trusted_proxy_header);
+
+        // the client sets the host of the upstream unless told otherwise...
+        if !upstream_group.state_arc.is_host_preserved {
+            headers.remove("host");
+        }
+
+        // a request body is streamed to the upstream as it arrives and can't be sent twice.
+        // so only idempotent requests without a body are retried...
+        let is_retriable = IDEMPOTENT_METHODS.contains(request.method()) && request.body().is_end_stream();
+        let attempt_count = if is_retriable { self.retry_count + 1 } else { 1 };
+        let mut tried_target_indices: Vec<usize> = Vec::with_capacity(attempt_count);
+        let mut status_code = StatusCode::BAD_GATEWAY;
+
+        for _ in 0..attempt_count {
+            let target_index = upstream_group.select_target_index(&tried_target_indices);
+            let target = &upstream_group.state_arc.targets[target_index];
+            let upstream_uri_option = create_upstream_uri(target.base_uri.as_str(), path_and_query.as_str());
+
+            tried_target_indices.push(target_index);
+
+            if upstream_uri_option.is_none() {
+                warn!("Unable to create upstream URI of '{}' for '{}'.", target.base_uri, request.uri());
+
+                return create_response(StatusCode::BAD_GATEWAY);
+            }
+
+            let upstream_uri = upstream_uri_option.unwrap();
+            let client = self.get_client(upstream_group, &upstream_uri);
+            let mut upstream_request = Request::builder()
+                .method(request.method().clone())
+                .uri(upstream_uri)
+                // the client may speak any version. HTTP/2 is used if the upstream connection speaks it...
+                .version(Version::HTTP_11)
+                .body(std::mem::take(request.body_mut()))
+                .unwrap();
+
+            *upstream_request.headers_mut() = headers.clone();
+
+            let upstream_response_result = self.send_async(client, upstream_request, Level::Warn).await;
+
+            if upstream_response_result.is_err() {
+                status_code = upstream_response_result.unwrap_err();
+
+                target.mark_failed(self.health_check_interval_option);
+
+                continue;
+            }
+
+            target.mark_succeeded();
+
+            let mut upstream_response = upstream_response_result.unwrap();
+
+            remove_hop_by_hop_headers(upstream_response.headers_mut());
+
+            return upstream_response;
+        }
+
+        return create_response(status_code);
+    }
+
+    async fn check_health_async(&self, upstream_group: &UpstreamGroup, target: &UpstreamTarget) {
+        let upstream_uri_option = create_upstream_uri(target.base_uri.as_str(), upstream_group.state_arc.health_check_path.as_str());
+
+        if upstream_uri_option.is_none() {
+            target.set_healthy(false);
+
+            return;
+        }
+
+        let upstream_uri = upstream_uri_option.unwrap();
+        let client = self.get_client(upstream_group, &upstream_uri);
+        let health_check_request = Request::builder()
+            .method(Method::GET)
+            .uri(upstream_uri)
+            .header("user-agent", "volcano-http-server")
+            .body(Body::empty())
+            .unwrap();
+        let response_result = self.send_async(client, health_check_request, Level::Debug).await;
+
+        // redirects count as healthy as the upstream is evidently up...
+        target.set_healthy(response_result.is_ok_and(|response| response.status().is_success() || response.status().is_redirection()));
+    }
+}
+
+// probes every upstream of the groups that have a health check path...
+pub async fn check_upstream_health_async(reverse_proxy: ReverseProxy, upstream_groups: Vec<UpstreamGroup>) {
+    let health_check_interval = reverse_proxy.health_check_interval_option.unwrap();
+    let upstream_groups: Vec<UpstreamGroup> = upstream_groups.into_iter()
+        .filter(UpstreamGroup::is_health_checked)
+        .collect();
+
+    loop {
+        let health_check_futures = upstream_groups.iter()
+            .flat_map(|upstream_group| upstream_group.state_arc.targets.iter()
+                .map(|target| reverse_proxy.check_health_async(upstream_group, target)));
+
+        join_all(health_check_futures).await;
+        sleep(health_check_interval).await;
+    }
+}
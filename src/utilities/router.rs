@@ -0,0 +1,569 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use hyper::{http::HeaderValue, Body, Method, Request, Response, StatusCode};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, sync::Arc};
+use urlencoding::{decode, encode};
+use log::{error, info, warn};
+
+use crate::{cors_utilities, reverse_proxy::UpstreamGroup};
+
+const HOST_HEADER_NAME: &str = "host";
+const LOCATION_HEADER_NAME: &str = "location";
+const PARAMETER_PREFIX: &str = ":";
+const GLOB_PREFIX: &str = "*";
+// the name of the parameter an unnamed glob (e.g. "/files/*") is stored as...
+const UNNAMED_GLOB_PARAMETER_NAME: &str = "*";
+const DEFAULT_INDEX_FILE_NAME: &str = "index.html";
+
+fn get_default_index_file_name() -> String {
+    return DEFAULT_INDEX_FILE_NAME.to_string();
+}
+
+fn get_default_redirect_status_code() -> u16 {
+    return StatusCode::FOUND.as_u16();
+}
+
+fn get_default_response_status_code() -> u16 {
+    return StatusCode::OK.as_u16();
+}
+
+// what shall be done with a request that matches the route...
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "action", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum RouteAction {
+    // forwarded to the parent process (what happens to requests no route matches as well)...
+    Parent,
+    // files are served from the directory. the path within the directory is taken from
+    // the glob of the route (e.g. "/assets/*") or else from the request path...
+    Static {
+        directory: String,
+        #[serde(default = "get_default_index_file_name")]
+        index_file_name: String,
+    },
+    // path parameters may be used in the location (e.g. "/users/{id}")...
+    Redirect {
+        location: String,
+        #[serde(default = "get_default_redirect_status_code")]
+        status_code: u16,
+        // appends the query string of the request to the location...
+        #[serde(default)]
+        preserve_query: bool,
+    },
+    Respond {
+        #[serde(default = "get_default_response_status_code")]
+        status_code: u16,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        #[serde(default)]
+        body: String,
+    },
+    // forwarded to one of the upstream HTTP servers (e.g. "http://127.0.0.1:8080")...
+    Proxy(UpstreamGroup),
+}
+
+// a route as written in the routes file...
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RouteDefinition {
+    // empty means any method...
+    #[serde(default)]
+    methods: Vec<String>,
+    // may contain * as wildcard (e.g. "*.example.com"). none means any host...
+    #[serde(default)]
+    host: Option<String>,
+    // e.g. "/users/:id" or "/assets/*path"...
+    path: String,
+    #[serde(flatten)]
+    action: RouteAction,
+}
+
+#[derive(Debug)]
+enum PathSegment {
+    Literal(String),
+    Parameter(String),
+    // matches the rest of the path and must be the last segment...
+    Glob(String),
+}
+
+#[derive(Debug)]
+struct Route {
+    methods: Vec<Method>,
+    host_pattern_option: Option<String>,
+    path_segments: Vec<PathSegment>,
+    action: RouteAction,
+}
+
+// path parameters of the route that matched the request...
+#[derive(Clone, Debug)]
+pub struct PathParameters(pub HashMap<String, String>);
+
+pub struct RouteMatch<'a> {
+    pub action: &'a RouteAction,
+    pub path_parameters: HashMap<String, String>,
+    // the (decoded) part of the path matched by the glob of the route, if any...
+    pub glob_path_option: Option<String>,
+    pub glob_name_option: Option<&'a str>,
+}
+
+// routes are evaluated in order and the first one that matches wins...
+#[derive(Clone, Debug)]
+pub struct Router {
+    routes_arc: Arc<Vec<Route>>,
+}
+
+fn split_path(path: &str) -> Vec<&str> {
+    // a trailing slash does not make a difference (e.g. "/users/" matches "/users")...
+    let path = path.strip_suffix('/').unwrap_or(path);
+
+    return path.split('/').skip(1).collect();
+}
+
+fn parse_path_pattern(path_pattern: &str) -> Option<Vec<PathSegment>> {
+    if !path_pattern.starts_with('/') {
+        warn!("Invalid route path '{}'. Paths must start with '/'.", path_pattern);
+
+        return None;
+    }
+
+    let splitted_path_pattern = split_path(path_pattern);
+    let mut path_segments: Vec<PathSegment> = Vec::with_capacity(splitted_path_pattern.len());
+
+    for (index, segment) in splitted_path_pattern.iter().enumerate() {
+        if let Some(parameter_name) = segment.strip_prefix(PARAMETER_PREFIX) {
+            if parameter_name.len() == 0 {
+                warn!("Invalid route path '{}'. Parameters must be named.", path_pattern);
+
+                return None;
+            }
+
+            path_segments.push(PathSegment::Parameter(parameter_name.to_string()));
+        } else if let Some(glob_name) = segment.strip_prefix(GLOB_PREFIX) {
+            if index != splitted_path_pattern.len() - 1 {
+                warn!("Invalid route path '{}'. A glob must be the last segment.", path_pattern);
+
+                return None;
+            }
+
+            let glob_name = if glob_name.len() == 0 { UNNAMED_GLOB_PARAMETER_NAME } else { glob_name };
+
+            path_segments.push(PathSegment::Glob(glob_name.to_string()));
+        } else {
+            path_segments.push(PathSegment::Literal(segment.to_string()));
+        }
+    }
+
+    return Some(path_segments);
+}
+
+// mistakes that would otherwise only show up when a request matches the route...
+fn is_valid_action(action: &RouteAction, path_pattern: &str) -> bool {
+    match action {
+        RouteAction::Static { directory, .. } if !fs::metadata(directory).is_ok_and(|metadata| metadata.is_dir()) => {
+            warn!("Invalid static directory '{}' in route '{}'.", directory, path_pattern);
+
+            return false;
+        },
+        RouteAction::Redirect { status_code, .. } if !(300..=399).contains(status_code) => {
+            warn!("Invalid redirect status code {} in route '{}'. Expected 3xx.", status_code, path_pattern);
+
+            return false;
+        },
+        RouteAction::Respond { status_code, .. } if StatusCode::from_u16(*status_code).is_err() => {
+            warn!("Invalid status code {} in route '{}'.", status_code, path_pattern);
+
+            return false;
+        },
+        RouteAction::Proxy(upstream_group) if upstream_group.get_target_count() == 0 => {
+            warn!("No valid upstream in route '{}'.", path_pattern);
+
+            return false;
+        },
+        _ => return true,
+    }
+}
+
+fn to_route(route_definition: RouteDefinition) -> Option<Route> {
+    let path_segments = parse_path_pattern(route_definition.path.as_str())?;
+
+    if !is_valid_action(&route_definition.action, route_definition.path.as_str()) {
+        return None;
+    }
+
+    let mut methods: Vec<Method> = Vec::with_capacity(route_definition.methods.len());
+
+    for method in route_definition.methods.iter() {
+        let method_result = Method::from_bytes(method.trim().to_uppercase().as_bytes());
+
+        if method_result.is_err() {
+            warn!("Invalid method '{}' in route '{}'.", method, route_definition.path);
+
+            return None;
+        }
+
+        methods.push(method_result.unwrap());
+    }
+
+    return Some(Route {
+        methods,
+        // hosts are compared case insensitively...
+        host_pattern_option: route_definition.host.map(|host| host.trim().to_lowercase()),
+        path_segments,
+        action: route_definition.action,
+    });
+}
+
+// reads the routes from a JSON file containing an array of routes. invalid routes are skipped...
+pub fn load(routes_path: &str) -> Router {
+    let mut routes: Vec<Route> = Vec::new();
+
+    if routes_path.len() == 0 {
+        return Router { routes_arc: Arc::new(routes) };
+    }
+
+    let routes_as_json_result = fs::read_to_string(routes_path);
+
+    if routes_as_json_result.is_err() {
+        let error = routes_as_json_result.unwrap_err();
+
+        error!("An error occurred while reading routes from '{}': {}", routes_path, error);
+
+        return Router { routes_arc: Arc::new(routes) };
+    }
+
+    let route_definitions_result: Result<Vec<RouteDefinition>, serde_json::Error> =
+        serde_json::from_str(routes_as_json_result.unwrap().as_str());
+
+    if route_definitions_result.is_err() {
+        let error = route_definitions_result.unwrap_err();
+
+        error!("An error occurred while parsing routes from '{}': {}", routes_path, error);
+
+        return Router { routes_arc: Arc::new(routes) };
+    }
+
+    for route_definition in route_definitions_result.unwrap() {
+        let route_option = to_route(route_definition);
+
+        if route_option.is_some() {
+            routes.push(route_option.unwrap());
+        }
+    }
+
+    info!("Loaded {} routes from '{}'.", routes.len(), routes_path);
+
+    return Router { routes_arc: Arc::new(routes) };
+}
+
+pub fn decode_path_segment(segment: &str) -> String {
+    return decode(segment).map_or(segment.to_string(), |decoded_segment| decoded_segment.into_owned());
+}
+
+// the decoded value is encoded again, so that it stays a single segment (e.g. "%2F" does not become "/")
+// and cannot inject anything into the location. only a glob may span multiple segments...
+fn encode_path_parameter(parameter_value: &str, is_glob: bool) -> String {
+    if !is_glob {
+        return encode(parameter_value).into_owned();
+    }
+
+    return parameter_value.split('/')
+        .map(|segment| encode(segment).into_owned())
+        .collect::<Vec<String>>()
+        .join("/");
+}
+
+// the host without port (e.g. "example.com" of "example.com:8080")...
+fn get_host(request: &Request<Body>) -> Option<String> {
+    let host = request.headers()
+        .get(HOST_HEADER_NAME)
+        .and_then(|header_value| header_value.to_str().ok())
+        .or(request.uri().host())?;
+    let host = host.trim().to_lowercase();
+
+    // IPv6 addresses are enclosed in brackets and contain colons themselves...
+    if host.starts_with('[') {
+        return Some(host.split(']').next().unwrap_or("").to_string() + "]");
+    }
+
+    return Some(host.split(':').next().unwrap_or("").to_string());
+}
+
+pub fn get_path_parameters(request: &Request<Body>) -> Option<HashMap<String, String>> {
+    return request.extensions()
+        .get::<PathParameters>()
+        .map(|path_parameters| path_parameters.0.clone());
+}
+
+impl Route {
+    fn get_glob_name(&self) -> Option<&str> {
+        return match self.path_segments.last() {
+            Some(PathSegment::Glob(glob_name)) => Some(glob_name.as_str()),
+            _ => None,
+        };
+    }
+
+    fn matches_path(&self, path: &str) -> Option<(HashMap<String, String>, Option<String>)> {
+        let splitted_path = split_path(path);
+        let mut path_parameters: HashMap<String, String> = HashMap::new();
+
+        for (index, path_segment) in self.path_segments.iter().enumerate() {
+            match path_segment {
+                PathSegment::Glob(glob_name) => {
+                    let remaining_path = decode_path_segment(splitted_path.get(index..).unwrap_or(&[]).join("/").as_str());
+
+                    path_parameters.insert(glob_name.clone(), remaining_path.clone());
+
+                    return Some((path_parameters, Some(remaining_path)));
+                },
+                PathSegment::Literal(literal) => {
+                    if splitted_path.get(index) != Some(&literal.as_str()) {
+                        return None;
+                    }
+                },
+                PathSegment::Parameter(parameter_name) => {
+                    let segment_option = splitted_path.get(index);
+
+                    // parameters never match empty segments...
+                    if segment_option.is_none() || segment_option.unwrap().len() == 0 {
+                        return None;
+                    }
+
+                    path_parameters.insert(parameter_name.clone(), decode_path_segment(segment_option.unwrap()));
+                },
+            }
+        }
+
+        if splitted_path.len() != self.path_segments.len() {
+            return None;
+        }
+
+        return Some((path_parameters, None));
+    }
+}
+
+impl Router {
+    pub fn is_enabled(&self) -> bool {
+        return self.routes_arc.len() > 0;
+    }
+
+    // the upstreams of all proxy routes (e.g. to check their health)...
+    pub fn get_upstream_groups(&self) -> Vec<UpstreamGroup> {
+        return self.routes_arc.iter()
+            .filter_map(|route| match &route.action {
+                RouteAction::Proxy(upstream_group) => Some(upstream_group.clone()),
+                _ => None,
+            })
+            .collect();
+    }
+
+    // finds the first route that matches the request...
+    pub fn find(&self, request: &Request<Body>) -> Option<RouteMatch<'_>> {
+        let host_option = get_host(request);
+
+        for route in self.routes_arc.iter() {
+            if route.methods.len() > 0 && !route.methods.contains(request.method()) {
+                continue;
+            }
+
+            if route.host_pattern_option.is_some() && (host_option.is_none()
+                || !cors_utilities::matches_pattern(host_option.as_ref().unwrap(), route.host_pattern_option.as_ref().unwrap())) {
+                continue;
+            }
+
+            let path_match_option = route.matches_path(request.uri().path());
+
+            if path_match_option.is_some() {
+                let (path_parameters, glob_path_option) = path_match_option.unwrap();
+
+                return Some(RouteMatch {
+                    action: &route.action,
+                    path_parameters,
+                    glob_path_option,
+                    glob_name_option: route.get_glob_name(),
+                });
+            }
+        }
+
+        return None;
+    }
+}
+
+pub fn create_redirect_response(
+    request: &Request<Body>,
+    location: &str,
+    status_code: u16,
+    is_query_preserved: bool,
+    path_parameters: &HashMap<String, String>,
+    glob_name_option: Option<&str>,
+) -> Response<Body> {
+    let mut location = location.to_string();
+
+    // e.g. "{id}" is replaced by the (percent-encoded) value of the path parameter "id"...
+    for (parameter_name, parameter_value) in path_parameters.iter() {
+        let is_glob = glob_name_option == Some(parameter_name.as_str());
+
+        location = location.replace(
+            format!("{{{}}}", parameter_name).as_str(), encode_path_parameter(parameter_value, is_glob).as_str());
+    }
+
+    if is_query_preserved && request.uri().query().is_some() {
+        let separator = if location.contains('?') { '&' } else { '?' };
+
+        location = format!("{}{}{}", location, separator, request.uri().query().unwrap());
+    }
+
+    let header_value_result = HeaderValue::from_str(location.as_str());
+
+    // the location may contain characters a header value must not contain...
+    if header_value_result.is_err() {
+        return Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::empty())
+            .unwrap();
+    }
+
+    let status_code = StatusCode::from_u16(status_code).unwrap_or(StatusCode::FOUND);
+
+    return Response::builder()
+        .status(status_code)
+        .header(LOCATION_HEADER_NAME, header_value_result.unwrap())
+        .body(Body::empty())
+        .unwrap();
+}
+
+pub fn create_fixed_response(status_code: u16, headers: &HashMap<String, String>, body: &str) -> Response<Body> {
+    let status_code = StatusCode::from_u16(status_code).unwrap_or(StatusCode::OK);
+    let mut response = Response::builder().status(status_code);
+
+    for (header_name, header_value) in headers.iter() {
+        response = response.header(header_name.as_str(), header_value.as_str());
+    }
+
+    let response_result = response.body(Body::from(body.to_string()));
+
+    if response_result.is_err() {
+        let error = response_result.unwrap_err();
+
+        error!("An error occurred while creating fixed response: {}", error);
+
+        return Response::builder()
+            .status(500)
+            .body(Body::from("ERROR"))
+            .unwrap();
+    }
+
+    return response_result.unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_router(routes_as_json: &str) -> Router {
+        let route_definitions: Vec<RouteDefinition> = serde_json::from_str(routes_as_json).unwrap();
+        let routes: Vec<Route> = route_definitions.into_iter().filter_map(to_route).collect();
+
+        return Router { routes_arc: Arc::new(routes) };
+    }
+
+    fn create_request(method: &str, uri: &str) -> Request<Body> {
+        return Request::builder().method(method).uri(uri).header(HOST_HEADER_NAME, "api.example.com:8080").body(Body::empty()).unwrap();
+    }
+
+    fn get_location(response: &Response<Body>) -> &str {
+        return response.headers().get(LOCATION_HEADER_NAME).unwrap().to_str().unwrap();
+    }
+
+    #[test]
+    fn matches_literals_and_parameters() {
+        let router = create_router(r#"[
+            { "path": "/users/:id", "action": "respond", "body": "user" },
+            { "path": "/users/:id/posts/:postId", "action": "parent" }
+        ]"#);
+        let route_match = router.find(&create_request("GET", "/users/a%20b/")).unwrap();
+
+        assert_eq!(route_match.path_parameters["id"], "a b");
+        assert!(route_match.glob_path_option.is_none());
+
+        let route_match = router.find(&create_request("GET", "/users/7/posts/42")).unwrap();
+
+        assert!(matches!(route_match.action, RouteAction::Parent));
+        assert_eq!(route_match.path_parameters["postId"], "42");
+        // parameters never match empty segments, and literals must match exactly...
+        assert!(router.find(&create_request("GET", "/users//posts/42")).is_none());
+        assert!(router.find(&create_request("GET", "/users")).is_none());
+        assert!(router.find(&create_request("GET", "/Users/7")).is_none());
+        assert!(router.find(&create_request("GET", "/users/7/posts")).is_none());
+    }
+
+    #[test]
+    fn matches_globs() {
+        let router = create_router(r#"[
+            { "path": "/files/*path", "action": "parent" },
+            { "path": "/assets/*", "action": "parent" }
+        ]"#);
+        let route_match = router.find(&create_request("GET", "/files/a/b%20c.txt")).unwrap();
+
+        assert_eq!(route_match.path_parameters["path"], "a/b c.txt");
+        assert_eq!(route_match.glob_path_option, Some(String::from("a/b c.txt")));
+        assert_eq!(route_match.glob_name_option, Some("path"));
+
+        // a glob matches an empty rest as well...
+        let route_match = router.find(&create_request("GET", "/assets")).unwrap();
+
+        assert_eq!(route_match.path_parameters[UNNAMED_GLOB_PARAMETER_NAME], "");
+    }
+
+    #[test]
+    fn evaluates_methods_hosts_and_order() {
+        let router = create_router(r#"[
+            { "path": "/a", "methods": ["post"], "action": "respond", "body": "post" },
+            { "path": "/a", "host": "*.example.com", "action": "respond", "body": "host" },
+            { "path": "/a", "action": "respond", "body": "any" }
+        ]"#);
+        let get_body = |request: Request<Body>| match router.find(&request).unwrap().action {
+            RouteAction::Respond { body, .. } => body.clone(),
+            _ => String::new(),
+        };
+
+        assert_eq!(get_body(create_request("POST", "/a")), "post");
+        assert_eq!(get_body(create_request("GET", "/a")), "host");
+
+        let request = Request::builder().uri("/a").header(HOST_HEADER_NAME, "example.org").body(Body::empty()).unwrap();
+
+        assert_eq!(get_body(request), "any");
+    }
+
+    #[test]
+    fn skips_invalid_routes() {
+        let router = create_router(r#"[
+            { "path": "users", "action": "parent" },
+            { "path": "/users/:", "action": "parent" },
+            { "path": "/files/*path/more", "action": "parent" },
+            { "path": "/old", "action": "redirect", "location": "/new", "statusCode": 200 },
+            { "path": "/teapot", "action": "respond", "statusCode": 1000 },
+            { "path": "/static", "action": "static", "directory": "/nonexistent-directory" },
+            { "path": "/a", "methods": ["NOT A METHOD"], "action": "parent" }
+        ]"#);
+
+        assert!(!router.is_enabled());
+    }
+
+    #[test]
+    fn encodes_path_parameters_in_redirect_location() {
+        let request = create_request("GET", "/old?page=2");
+        let path_parameters = HashMap::from([
+            (String::from("id"), String::from("a/b\r\nSet-Cookie: x=1")),
+            (String::from("path"), String::from("docs/read me.md")),
+        ]);
+        let response = create_redirect_response(
+            &request, "/new/{id}/{path}", 301, true, &path_parameters, Some("path"));
+
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(get_location(&response), "/new/a%2Fb%0D%0ASet-Cookie%3A%20x%3D1/docs/read%20me.md?page=2");
+
+        let response = create_redirect_response(&request, "/new?from=old", 302, true, &HashMap::new(), None);
+
+        assert_eq!(get_location(&response), "/new?from=old&page=2");
+    }
+}
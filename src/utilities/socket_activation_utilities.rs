@@ -0,0 +1,166 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap)]
+
+use std::{env, io, os::unix::io::RawFd, process};
+use log::error;
+
+// systemd passes the listening sockets starting from this file descriptor...
+const SD_LISTEN_FDS_START: RawFd = 3;
+const LISTEN_PID_ENVIRONMENT_VARIABLE_NAME: &str = "LISTEN_PID";
+const LISTEN_FDS_ENVIRONMENT_VARIABLE_NAME: &str = "LISTEN_FDS";
+const LISTEN_FDNAMES_ENVIRONMENT_VARIABLE_NAME: &str = "LISTEN_FDNAMES";
+
+fn parse_u32(name: &str, value: &str) -> Option<u32> {
+    let parse_result = value.parse::<u32>();
+
+    if parse_result.is_err() {
+        let error = parse_result.unwrap_err();
+
+        error!("An error occurred while parsing environment variable {} ('{}') as u32: {}", name, value, error);
+
+        return None;
+    }
+
+    return Some(parse_result.unwrap());
+}
+
+pub struct InheritedFileDescriptor {
+    pub file_descriptor: RawFd,
+    // name assigned via FileDescriptorName= in the socket unit (empty if none)...
+    pub name: String,
+}
+
+fn parse_systemd_file_descriptors(
+    listen_pid_option: Option<&str>,
+    listen_fds_option: Option<&str>,
+    listen_fdnames_option: Option<&str>,
+    process_id: u32,
+) -> Vec<InheritedFileDescriptor> {
+    // if a variable is not set, there is nothing to parse...
+    let listen_pid_option = listen_pid_option
+        .and_then(|listen_pid| parse_u32(LISTEN_PID_ENVIRONMENT_VARIABLE_NAME, listen_pid));
+    let listen_fds_option = listen_fds_option
+        .and_then(|listen_fds| parse_u32(LISTEN_FDS_ENVIRONMENT_VARIABLE_NAME, listen_fds));
+
+    // the variables are meant for us only if the process ID matches ours...
+    if listen_pid_option.is_none() || listen_fds_option.is_none()
+        || listen_pid_option.unwrap() != process_id {
+        return Vec::new();
+    }
+
+    // names are optional. missing names are treated as empty...
+    let file_descriptor_names: Vec<&str> = listen_fdnames_option
+        .unwrap_or_default()
+        .split(':')
+        .collect();
+    let listen_fds = listen_fds_option.unwrap() as RawFd;
+    let file_descriptors = (SD_LISTEN_FDS_START..SD_LISTEN_FDS_START + listen_fds)
+        .enumerate()
+        .map(|(index, file_descriptor)| InheritedFileDescriptor {
+            file_descriptor,
+            name: file_descriptor_names.get(index).copied().unwrap_or_default().to_string(),
+        })
+        .collect();
+
+    return file_descriptors;
+}
+
+fn set_close_on_exec(file_descriptor: RawFd) {
+    let flags = unsafe { libc::fcntl(file_descriptor, libc::F_GETFD) };
+
+    if flags == -1 || unsafe { libc::fcntl(file_descriptor, libc::F_SETFD, flags | libc::FD_CLOEXEC) } == -1 {
+        error!(
+            "An error occurred while setting close-on-exec flag on file descriptor {}: {}",
+            file_descriptor, io::Error::last_os_error(),
+        );
+    }
+}
+
+// modifying the environment is only sound while no other thread is running.
+// therefore, this function must be called before the runtime is started...
+pub fn take_inherited_file_descriptors(listen_file_descriptor: Option<u32>) -> Vec<InheritedFileDescriptor> {
+    let listen_pid = env::var(LISTEN_PID_ENVIRONMENT_VARIABLE_NAME).ok();
+    let listen_fds = env::var(LISTEN_FDS_ENVIRONMENT_VARIABLE_NAME).ok();
+    let listen_fdnames = env::var(LISTEN_FDNAMES_ENVIRONMENT_VARIABLE_NAME).ok();
+
+    // we shall remove the variables so that they are not inherited any further...
+    env::remove_var(LISTEN_PID_ENVIRONMENT_VARIABLE_NAME);
+    env::remove_var(LISTEN_FDS_ENVIRONMENT_VARIABLE_NAME);
+    env::remove_var(LISTEN_FDNAMES_ENVIRONMENT_VARIABLE_NAME);
+
+    let inherited_file_descriptors = if listen_file_descriptor.is_some() {
+        // if a file descriptor is explicitly specified, it shall take precedence...
+        vec![InheritedFileDescriptor {
+            file_descriptor: listen_file_descriptor.unwrap() as RawFd,
+            name: String::from(""),
+        }]
+    } else {
+        parse_systemd_file_descriptors(
+            listen_pid.as_deref(),
+            listen_fds.as_deref(),
+            listen_fdnames.as_deref(),
+            process::id(),
+        )
+    };
+
+    // inherited sockets shall not leak into processes we might spawn...
+    for inherited_file_descriptor in &inherited_file_descriptors {
+        set_close_on_exec(inherited_file_descriptor.file_descriptor);
+    }
+
+    return inherited_file_descriptors;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_names(inherited_file_descriptors: &[InheritedFileDescriptor]) -> Vec<(RawFd, &str)> {
+        return inherited_file_descriptors.iter()
+            .map(|inherited_file_descriptor| (inherited_file_descriptor.file_descriptor, inherited_file_descriptor.name.as_str()))
+            .collect();
+    }
+
+    #[test]
+    fn parses_file_descriptors_with_names() {
+        let inherited_file_descriptors = parse_systemd_file_descriptors(
+            Some("42"), Some("2"), Some("http:https"), 42);
+
+        assert_eq!(get_names(&inherited_file_descriptors), vec![(3, "http"), (4, "https")]);
+    }
+
+    #[test]
+    fn ignores_variables_meant_for_another_process() {
+        let inherited_file_descriptors = parse_systemd_file_descriptors(
+            Some("41"), Some("2"), Some("http:https"), 42);
+
+        assert_eq!(inherited_file_descriptors.len(), 0);
+    }
+
+    #[test]
+    fn ignores_missing_or_invalid_variables() {
+        assert_eq!(parse_systemd_file_descriptors(None, Some("1"), None, 42).len(), 0);
+        assert_eq!(parse_systemd_file_descriptors(Some("42"), None, None, 42).len(), 0);
+        assert_eq!(parse_systemd_file_descriptors(Some("42"), Some("two"), None, 42).len(), 0);
+        assert_eq!(parse_systemd_file_descriptors(Some("-42"), Some("1"), None, 42).len(), 0);
+    }
+
+    #[test]
+    fn treats_missing_names_as_empty() {
+        let inherited_file_descriptors = parse_systemd_file_descriptors(Some("42"), Some("2"), None, 42);
+
+        assert_eq!(get_names(&inherited_file_descriptors), vec![(3, ""), (4, "")]);
+    }
+
+    #[test]
+    fn tolerates_names_not_matching_the_count() {
+        // fewer names than file descriptors...
+        let inherited_file_descriptors = parse_systemd_file_descriptors(Some("42"), Some("3"), Some("http"), 42);
+
+        assert_eq!(get_names(&inherited_file_descriptors), vec![(3, "http"), (4, ""), (5, "")]);
+
+        // more names than file descriptors and empty names in between...
+        let inherited_file_descriptors = parse_systemd_file_descriptors(Some("42"), Some("2"), Some(":https:extra"), 42);
+
+        assert_eq!(get_names(&inherited_file_descriptors), vec![(3, ""), (4, "https")]);
+    }
+}
@@ -0,0 +1,281 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap)]
+
+use bytes::BytesMut;
+use chrono::{DateTime, Utc};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use std::path::{Component, Path, PathBuf};
+use tokio::{fs::File, io::AsyncReadExt};
+use log::{debug, warn};
+
+const BUFFER_LENGTH_IN_BYTES: usize = 65536;        // 64 KB...
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+// e.g. "Mon, 19 Oct 2026 10:00:00 GMT"...
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+const CONTENT_TYPES: [(&str, &str); 24] = [
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("mjs", "text/javascript; charset=utf-8"),
+    ("json", "application/json"),
+    ("map", "application/json"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("xml", "application/xml"),
+    ("svg", "image/svg+xml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("ico", "image/x-icon"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("wasm", "application/wasm"),
+    ("pdf", "application/pdf"),
+    ("mp4", "video/mp4"),
+    ("webm", "video/webm"),
+    ("mp3", "audio/mpeg"),
+];
+
+fn get_content_type(file_path: &Path) -> &'static str {
+    let extension = file_path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    return CONTENT_TYPES.iter()
+        .find(|(known_extension, _)| extension.eq(known_extension))
+        .map_or(DEFAULT_CONTENT_TYPE, |(_, content_type)| content_type);
+}
+
+fn create_response(status_code: StatusCode) -> Response<Body> {
+    return Response::builder()
+        .status(status_code)
+        .body(Body::from(status_code.canonical_reason().unwrap_or("")))
+        .unwrap();
+}
+
+// resolves the (decoded) relative path within the directory. paths that would escape
+// the directory (e.g. "../secret") or that point to hidden files are rejected...
+fn resolve_file_path(directory: &str, relative_path: &str) -> Option<PathBuf> {
+    let mut file_path = PathBuf::from(directory);
+
+    if relative_path.contains('\0') || relative_path.contains('\\') {
+        return None;
+    }
+
+    for component in Path::new(relative_path).components() {
+        match component {
+            Component::Normal(name) if !name.to_string_lossy().starts_with('.') => file_path.push(name),
+            Component::RootDir | Component::CurDir => {},
+            _ => return None,
+        }
+    }
+
+    return Some(file_path);
+}
+
+// symbolic links may still point outside the directory, so the resolved file path
+// must be within the resolved directory. none if either cannot be resolved...
+async fn canonicalize_within_directory_async(directory: &str, file_path: &Path) -> Option<PathBuf> {
+    let canonical_directory_result = tokio::fs::canonicalize(directory).await;
+
+    if canonical_directory_result.is_err() {
+        let error = canonical_directory_result.unwrap_err();
+
+        warn!("An error occurred while resolving static file directory '{}': {}", directory, error);
+
+        return None;
+    }
+
+    let canonical_file_path_result = tokio::fs::canonicalize(file_path).await;
+
+    if canonical_file_path_result.is_err() {
+        return None;
+    }
+
+    let canonical_file_path = canonical_file_path_result.unwrap();
+
+    if !canonical_file_path.starts_with(canonical_directory_result.unwrap()) {
+        return None;
+    }
+
+    return Some(canonical_file_path);
+}
+
+pub async fn serve_file_async(
+    request: &Request<Body>,
+    directory: &str,
+    relative_path: &str,
+    index_file_name: &str,
+) -> Response<Body> {
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        let mut response = create_response(StatusCode::METHOD_NOT_ALLOWED);
+
+        response.headers_mut().insert("allow", "GET, HEAD".parse().unwrap());
+
+        return response;
+    }
+
+    let file_path_option = resolve_file_path(directory, relative_path);
+
+    if file_path_option.is_none() {
+        debug!("Rejected static file path '{}'.", relative_path);
+
+        return create_response(StatusCode::NOT_FOUND);
+    }
+
+    let mut file_path = file_path_option.unwrap();
+    let mut metadata_result = tokio::fs::metadata(&file_path).await;
+
+    // directories are served by their index file...
+    if metadata_result.as_ref().is_ok_and(|metadata| metadata.is_dir()) {
+        file_path.push(index_file_name);
+        metadata_result = tokio::fs::metadata(&file_path).await;
+    }
+
+    if metadata_result.is_err() || !metadata_result.as_ref().unwrap().is_file() {
+        return create_response(StatusCode::NOT_FOUND);
+    }
+
+    let canonical_file_path_option = canonicalize_within_directory_async(directory, &file_path).await;
+
+    if canonical_file_path_option.is_none() {
+        debug!("Rejected static file path '{}' as it resolves outside of '{}'.", relative_path, directory);
+
+        return create_response(StatusCode::NOT_FOUND);
+    }
+
+    let metadata = metadata_result.unwrap();
+    // the resolved path that has just been checked is opened rather than the requested one...
+    let file_result = File::open(canonical_file_path_option.unwrap()).await;
+
+    if file_result.is_err() {
+        let error = file_result.unwrap_err();
+
+        warn!("An error occurred while opening static file '{}': {}", file_path.display(), error);
+
+        return create_response(StatusCode::NOT_FOUND);
+    }
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", get_content_type(&file_path))
+        .header("content-length", metadata.len());
+
+    if let Ok(modified_at) = metadata.modified() {
+        response = response.header("last-modified", DateTime::<Utc>::from(modified_at).format(HTTP_DATE_FORMAT).to_string());
+    }
+
+    // the body of a HEAD response is discarded anyway...
+    if request.method() == Method::HEAD {
+        return response.body(Body::empty()).unwrap();
+    }
+
+    let mut file = file_result.unwrap();
+    let (mut sender, body) = Body::channel();
+
+    // the file is streamed so that large files are never held in memory...
+    tokio::spawn(async move {
+        loop {
+            let mut buffer = BytesMut::with_capacity(BUFFER_LENGTH_IN_BYTES);
+            let read_result = file.read_buf(&mut buffer).await;
+
+            if read_result.is_err() {
+                let error = read_result.unwrap_err();
+
+                warn!("An error occurred while reading static file: {}", error);
+
+                sender.abort();
+
+                return;
+            }
+
+            // end of file...
+            if read_result.unwrap() == 0 {
+                return;
+            }
+
+            // the client has gone away...
+            if sender.send_data(buffer.freeze()).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    return response.body(body).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    // a fresh directory per test, as the tests run in parallel...
+    fn create_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("volcano-static-{}-{}", name, std::process::id()));
+
+        _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("public/assets")).unwrap();
+        std::fs::write(directory.join("public/assets/app.js"), "console.log(1);").unwrap();
+        std::fs::write(directory.join("secret.txt"), "secret").unwrap();
+
+        return directory;
+    }
+
+    #[test]
+    fn resolves_paths_within_directory() {
+        assert_eq!(resolve_file_path("/srv/public", "assets/app.js"), Some(PathBuf::from("/srv/public/assets/app.js")));
+        assert_eq!(resolve_file_path("/srv/public", "/assets/./app.js"), Some(PathBuf::from("/srv/public/assets/app.js")));
+        assert_eq!(resolve_file_path("/srv/public", ""), Some(PathBuf::from("/srv/public")));
+    }
+
+    #[test]
+    fn rejects_parent_directory_segments() {
+        assert_eq!(resolve_file_path("/srv/public", ".."), None);
+        assert_eq!(resolve_file_path("/srv/public", "../secret.txt"), None);
+        assert_eq!(resolve_file_path("/srv/public", "assets/../../secret.txt"), None);
+        // the router decodes the path before it is resolved...
+        assert_eq!(resolve_file_path("/srv/public", router::decode_path_segment("%2e%2e/secret.txt").as_str()), None);
+        assert_eq!(resolve_file_path("/srv/public", router::decode_path_segment("assets/%2E%2E/%2e%2e/secret.txt").as_str()), None);
+    }
+
+    #[test]
+    fn rejects_backslashes_and_nul() {
+        assert_eq!(resolve_file_path("/srv/public", "..\\secret.txt"), None);
+        assert_eq!(resolve_file_path("/srv/public", "assets\\app.js"), None);
+        assert_eq!(resolve_file_path("/srv/public", "app.js\0.png"), None);
+        assert_eq!(resolve_file_path("/srv/public", router::decode_path_segment("app.js%00.png").as_str()), None);
+    }
+
+    #[test]
+    fn rejects_hidden_segments() {
+        assert_eq!(resolve_file_path("/srv/public", ".env"), None);
+        assert_eq!(resolve_file_path("/srv/public", ".git/config"), None);
+        assert_eq!(resolve_file_path("/srv/public", "assets/.hidden/app.js"), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_symbolic_links_leading_outside_directory() {
+        let directory = create_directory("symlink");
+        let public_directory = directory.join("public");
+        let public_directory = public_directory.to_str().unwrap();
+
+        std::os::unix::fs::symlink(directory.join("secret.txt"), directory.join("public/secret.txt")).unwrap();
+        std::os::unix::fs::symlink(directory.join("public/assets/app.js"), directory.join("public/app.js")).unwrap();
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let escaping_response = serve_file_async(&request, public_directory, "secret.txt", "index.html").await;
+        let inner_response = serve_file_async(&request, public_directory, "app.js", "index.html").await;
+        let file_response = serve_file_async(&request, public_directory, "assets/app.js", "index.html").await;
+
+        assert_eq!(escaping_response.status(), StatusCode::NOT_FOUND);
+        // links that stay within the directory are followed...
+        assert_eq!(inner_response.status(), StatusCode::OK);
+        assert_eq!(file_response.status(), StatusCode::OK);
+        assert_eq!(hyper::body::to_bytes(file_response.into_body()).await.unwrap(), "console.log(1);");
+
+        _ = std::fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,599 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use futures::stream::StreamExt;
+use hyper::{
+    body::HttpBody,
+    client::HttpConnector,
+    http::{header::CONTENT_LENGTH, HeaderMap, HeaderValue},
+    Body, Client, Method, Request, Response,
+};
+use serde_json::{json, Value};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    fs::OpenOptions,
+    io::AsyncWriteExt,
+    sync::mpsc::{self, Receiver, Sender},
+    time::{timeout, timeout_at, Instant},
+};
+use log::{debug, error, warn};
+
+pub const TRACEPARENT_HEADER_NAME: &str = "traceparent";
+pub const TRACESTATE_HEADER_NAME: &str = "tracestate";
+const SUPPORTED_TRACEPARENT_VERSION: &str = "00";
+// "00-<trace-id>-<parent-id>-<trace-flags>"...
+const TRACEPARENT_LENGTH: usize = 55;
+const TRACE_ID_LENGTH_IN_BYTES: usize = 16;
+const SPAN_ID_LENGTH_IN_BYTES: usize = 8;
+const SAMPLED_FLAG: u8 = 0x01;
+const MAXIMUM_TRACESTATE_LENGTH: usize = 512;
+const SERVICE_NAME: &str = "volcano-http-server";
+// finished spans are queued until the exporter catches up. if the queue is
+// full, spans are dropped instead of slowing requests down...
+const SPAN_QUEUE_CAPACITY: usize = 4096;
+const MAXIMUM_BATCH_SIZE: usize = 512;
+const EXPORT_INTERVAL_IN_MILLISECONDS: u64 = 1000;
+const EXPORT_TIMEOUT_IN_MILLISECONDS: u64 = 10000;
+// values of the OTLP span kind enumeration...
+const SPAN_KIND_INTERNAL: u8 = 1;
+const SPAN_KIND_SERVER: u8 = 2;
+// values of the OTLP status code enumeration...
+const STATUS_CODE_UNSET: u8 = 0;
+const STATUS_CODE_ERROR: u8 = 2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceExporter {
+    // tracing is disabled...
+    None,
+    // spans are posted to this OTLP/HTTP endpoint as JSON...
+    Otlp(String),
+    // spans are appended to this file in the OTLP JSON format, one batch per line...
+    File(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpanKind {
+    Server,
+    Internal,
+}
+
+// identifies a span within a trace and travels with the request (W3C trace context)...
+#[derive(Clone, Debug)]
+pub struct SpanContext {
+    pub trace_id: String,
+    pub span_id: String,
+    pub is_sampled: bool,
+    pub trace_state: Option<String>,
+}
+
+pub struct Span {
+    pub context: SpanContext,
+    parent_span_id: Option<String>,
+    name: String,
+    kind: SpanKind,
+    started_at: SystemTime,
+    attributes: Vec<(String, Value)>,
+    is_error: bool,
+}
+
+struct FinishedSpan {
+    span: Span,
+    ended_at: SystemTime,
+}
+
+// ends the span when dropped...
+struct SpanGuard {
+    tracer: Tracer,
+    span_option: Option<Span>,
+}
+
+#[derive(Clone)]
+pub struct Tracer {
+    exporter: TraceExporter,
+    // ratio of new traces that are recorded. continued traces follow the decision of the caller...
+    sample_ratio: f64,
+    sender: Sender<FinishedSpan>,
+    // taken by the export task...
+    receiver_arc: Arc<Mutex<Option<Receiver<FinishedSpan>>>>,
+}
+
+pub fn parse_exporter(exporter: &str, otlp_endpoint: &str, file_path: &str) -> TraceExporter {
+    return match exporter.trim().to_lowercase().as_str() {
+        "" | "none" => TraceExporter::None,
+        "otlp" => TraceExporter::Otlp(otlp_endpoint.to_string()),
+        "file" => TraceExporter::File(file_path.to_string()),
+        _ => {
+            eprintln!("Invalid trace exporter '{}'. Expected none, otlp or file. Tracing is disabled.", exporter);
+
+            TraceExporter::None
+        },
+    };
+}
+
+pub fn parse_sample_ratio(sample_ratio: &str) -> f64 {
+    let sample_ratio_result = sample_ratio.trim().parse::<f64>();
+
+    if sample_ratio_result.is_err() || !(0.0..=1.0).contains(sample_ratio_result.as_ref().unwrap()) {
+        eprintln!("Invalid trace sample ratio '{}'. Expected a number between 0 and 1. Falling back to 1.", sample_ratio);
+
+        return 1.0;
+    }
+
+    return sample_ratio_result.unwrap();
+}
+
+fn generate_id(length_in_bytes: usize) -> String {
+    let mut bytes = vec![0u8; length_in_bytes];
+
+    // an ID that is all zeros is invalid, so we shall never produce one...
+    while bytes.iter().all(|byte| *byte == 0) {
+        let random_result = getrandom::getrandom(&mut bytes);
+
+        if random_result.is_err() {
+            let error = random_result.unwrap_err();
+
+            error!("An error occurred while generating random ID: {}", error);
+
+            bytes[length_in_bytes - 1] = 1;
+        }
+    }
+
+    return bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+}
+
+fn is_valid_id(id: &str, length_in_bytes: usize) -> bool {
+    return id.len() == length_in_bytes * 2
+        && id.chars().all(|character| character.is_ascii_digit() || ('a'..='f').contains(&character))
+        && id.chars().any(|character| character != '0');
+}
+
+fn parse_traceparent(traceparent: &str) -> Option<SpanContext> {
+    let traceparent = traceparent.trim();
+    let fields: Vec<&str> = traceparent.split('-').collect();
+
+    if fields.len() < 4 || fields[0].len() != 2 || fields[3].len() != 2 {
+        return None;
+    }
+
+    // version "ff" is forbidden. later versions may append fields, but
+    // the ones we understand are guaranteed to stay where they are...
+    if fields[0].eq("ff")
+        || (fields[0].eq(SUPPORTED_TRACEPARENT_VERSION) && traceparent.len() != TRACEPARENT_LENGTH)
+        || traceparent.len() < TRACEPARENT_LENGTH {
+        return None;
+    }
+
+    let trace_flags_result = u8::from_str_radix(fields[3], 16);
+
+    if !is_valid_id(fields[1], TRACE_ID_LENGTH_IN_BYTES)
+        || !is_valid_id(fields[2], SPAN_ID_LENGTH_IN_BYTES)
+        || trace_flags_result.is_err() {
+        return None;
+    }
+
+    return Some(SpanContext {
+        trace_id: fields[1].to_string(),
+        span_id: fields[2].to_string(),
+        is_sampled: trace_flags_result.unwrap() & SAMPLED_FLAG == SAMPLED_FLAG,
+        trace_state: None,
+    });
+}
+
+fn is_valid_tracestate(tracestate: &str) -> bool {
+    return tracestate.len() > 0
+        && tracestate.len() <= MAXIMUM_TRACESTATE_LENGTH
+        && tracestate.chars().all(|character| character == ' ' || character.is_ascii_graphic());
+}
+
+// continues the trace of the caller, if the request carries valid W3C trace context headers...
+pub fn extract(headers: &HeaderMap) -> Option<SpanContext> {
+    let traceparent = headers.get(TRACEPARENT_HEADER_NAME)
+        .and_then(|header_value| header_value.to_str().ok())?;
+    let span_context_option = parse_traceparent(traceparent);
+
+    if span_context_option.is_none() {
+        debug!("Invalid traceparent '{}' received. Starting a new trace.", traceparent);
+
+        return None;
+    }
+
+    let mut span_context = span_context_option.unwrap();
+    // multiple tracestate headers are combined into one list...
+    let tracestate = headers.get_all(TRACESTATE_HEADER_NAME)
+        .iter()
+        .filter_map(|header_value| header_value.to_str().ok())
+        .collect::<Vec<&str>>()
+        .join(",");
+
+    if is_valid_tracestate(tracestate.as_str()) {
+        span_context.trace_state = Some(tracestate);
+    }
+
+    return Some(span_context);
+}
+
+pub fn get_span_context(request: &Request<Body>) -> Option<SpanContext> {
+    return request.extensions().get::<SpanContext>().cloned();
+}
+
+impl SpanContext {
+    pub fn to_traceparent(&self) -> String {
+        return format!(
+            "{}-{}-{}-{:02x}",
+            SUPPORTED_TRACEPARENT_VERSION,
+            self.trace_id,
+            self.span_id,
+            if self.is_sampled { SAMPLED_FLAG } else { 0 },
+        );
+    }
+}
+
+fn to_unix_time_in_nanoseconds(time: SystemTime) -> String {
+    let unix_time = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    // 64 bit integers are encoded as strings in OTLP JSON...
+    return unix_time.as_nanos().to_string();
+}
+
+fn to_otlp_attribute_value(value: &Value) -> Value {
+    return match value {
+        Value::Bool(value) => json!({ "boolValue": value }),
+        Value::Number(number) if number.is_i64() || number.is_u64() => json!({ "intValue": number.to_string() }),
+        Value::Number(number) => json!({ "doubleValue": number.as_f64() }),
+        Value::String(value) => json!({ "stringValue": value }),
+        value => json!({ "stringValue": value.to_string() }),
+    };
+}
+
+impl Span {
+    pub fn set_attribute(&mut self, key: &str, value: impl Into<Value>) {
+        self.attributes.push((key.to_string(), value.into()));
+    }
+
+    pub fn set_error(&mut self) {
+        self.is_error = true;
+    }
+}
+
+impl FinishedSpan {
+    fn to_otlp_json(&self) -> Value {
+        let span = &self.span;
+        let attributes: Vec<Value> = span.attributes.iter()
+            .map(|(key, value)| json!({ "key": key, "value": to_otlp_attribute_value(value) }))
+            .collect();
+
+        return json!({
+            "traceId": span.context.trace_id,
+            "spanId": span.context.span_id,
+            "parentSpanId": span.parent_span_id.clone().unwrap_or_default(),
+            "traceState": span.context.trace_state.clone().unwrap_or_default(),
+            "name": span.name,
+            "kind": match span.kind {
+                SpanKind::Server => SPAN_KIND_SERVER,
+                SpanKind::Internal => SPAN_KIND_INTERNAL,
+            },
+            "startTimeUnixNano": to_unix_time_in_nanoseconds(span.started_at),
+            "endTimeUnixNano": to_unix_time_in_nanoseconds(self.ended_at),
+            "attributes": attributes,
+            "status": { "code": if span.is_error { STATUS_CODE_ERROR } else { STATUS_CODE_UNSET } },
+        });
+    }
+}
+
+// wraps a batch of spans into an OTLP ExportTraceServiceRequest...
+fn to_otlp_request(batch: &[FinishedSpan]) -> String {
+    let spans: Vec<Value> = batch.iter().map(|finished_span| finished_span.to_otlp_json()).collect();
+
+    return json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [{ "key": "service.name", "value": { "stringValue": SERVICE_NAME } }],
+            },
+            "scopeSpans": [{
+                "scope": { "name": SERVICE_NAME, "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    }).to_string();
+}
+
+impl Tracer {
+    pub fn new(exporter: &TraceExporter, sample_ratio: f64) -> Self {
+        let (sender, receiver) = mpsc::channel(SPAN_QUEUE_CAPACITY);
+
+        return Tracer {
+            exporter: exporter.clone(),
+            sample_ratio,
+            sender,
+            receiver_arc: Arc::new(Mutex::new(Some(receiver))),
+        };
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        return self.exporter != TraceExporter::None;
+    }
+
+    fn should_sample(&self, trace_id: &str) -> bool {
+        // the last 8 bytes of the trace ID are random, hence comparing them against
+        // the ratio gives every process in the trace the same decision...
+        let random_part = u64::from_str_radix(&trace_id[trace_id.len() - 16..], 16).unwrap_or(0);
+
+        return (random_part as f64) < self.sample_ratio * (u64::MAX as f64);
+    }
+
+    // none means tracing is disabled...
+    pub fn start_span(&self, name: &str, kind: SpanKind, parent_option: Option<&SpanContext>) -> Option<Span> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let (trace_id, is_sampled, trace_state) = if parent_option.is_none() {
+            let trace_id = generate_id(TRACE_ID_LENGTH_IN_BYTES);
+            let is_sampled = self.should_sample(trace_id.as_str());
+
+            (trace_id, is_sampled, None)
+        } else {
+            let parent = parent_option.unwrap();
+
+            (parent.trace_id.clone(), parent.is_sampled, parent.trace_state.clone())
+        };
+
+        return Some(Span {
+            context: SpanContext {
+                trace_id,
+                span_id: generate_id(SPAN_ID_LENGTH_IN_BYTES),
+                is_sampled,
+                trace_state,
+            },
+            parent_span_id: parent_option.map(|parent| parent.span_id.clone()),
+            name: name.to_string(),
+            kind,
+            started_at: SystemTime::now(),
+            attributes: Vec::new(),
+            is_error: false,
+        });
+    }
+
+    pub fn end_span(&self, span_option: Option<Span>) {
+        if span_option.is_none() {
+            return;
+        }
+
+        let span = span_option.unwrap();
+
+        // spans of traces that are not sampled are only needed for propagation...
+        if !span.context.is_sampled {
+            return;
+        }
+
+        let send_result = self.sender.try_send(FinishedSpan { span, ended_at: SystemTime::now() });
+
+        if send_result.is_err() {
+            debug!("The span queue is full. Dropping span.");
+        }
+    }
+
+    // hyper writes the body after we return the response. so the span ends once
+    // the body has been written completely or hyper gives up on it (e.g. the client disconnects)...
+    pub fn end_span_after_body(&self, response: Response<Body>, span_option: Option<Span>) -> Response<Body> {
+        if span_option.is_none() {
+            return response;
+        }
+
+        let (mut parts, body) = response.into_parts();
+        let content_length_option = body.size_hint().exact();
+
+        // there is nothing to write...
+        if content_length_option == Some(0) {
+            self.end_span(span_option);
+
+            return Response::from_parts(parts, body);
+        }
+
+        // a wrapped body has no known length, hence we shall keep hyper from switching to chunked encoding...
+        if content_length_option.is_some() && !parts.headers.contains_key(CONTENT_LENGTH) {
+            parts.headers.insert(CONTENT_LENGTH, HeaderValue::from(content_length_option.unwrap()));
+        }
+
+        let span_guard = SpanGuard { tracer: self.clone(), span_option };
+        let body = Body::wrap_stream(body.map(move |chunk_result| {
+            // the guard lives as long as the body...
+            let _span_guard = &span_guard;
+
+            return chunk_result;
+        }));
+
+        return Response::from_parts(parts, body);
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        self.tracer.end_span(self.span_option.take());
+    }
+}
+
+async fn post_to_otlp_endpoint_async(client: &Client<HttpConnector>, endpoint: &str, body: String) {
+    let request_result = Request::builder()
+        .method(Method::POST)
+        .uri(endpoint)
+        .header("content-type", "application/json")
+        .body(Body::from(body));
+
+    if request_result.is_err() {
+        let error = request_result.unwrap_err();
+
+        error!("An error occurred while preparing OTLP request to {}: {}", endpoint, error);
+
+        return;
+    }
+
+    let response_result = timeout(
+        Duration::from_millis(EXPORT_TIMEOUT_IN_MILLISECONDS),
+        client.request(request_result.unwrap()),
+    ).await;
+
+    if response_result.is_err() {
+        warn!("Timed out while exporting spans to {}.", endpoint);
+
+        return;
+    }
+
+    let response_result = response_result.unwrap();
+
+    if response_result.is_err() {
+        let error = response_result.unwrap_err();
+
+        warn!("An error occurred while exporting spans to {}: {}", endpoint, error);
+
+        return;
+    }
+
+    let response = response_result.unwrap();
+
+    if !response.status().is_success() {
+        warn!("The OTLP endpoint {} rejected the exported spans with status {}.", endpoint, response.status());
+    }
+}
+
+// the file is written asynchronously so that exporting never blocks a runtime thread...
+async fn append_to_file_async(file_path: &str, body: String) {
+    let file_result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)
+        .await;
+
+    if file_result.is_err() {
+        let error = file_result.unwrap_err();
+
+        error!("An error occurred while opening trace file '{}': {}", file_path, error);
+
+        return;
+    }
+
+    let mut file = file_result.unwrap();
+    // the write is only complete once flushed. otherwise, the next batch could overtake it...
+    let mut write_result = file.write_all(format!("{}\n", body).as_bytes()).await;
+
+    if write_result.is_ok() {
+        write_result = file.flush().await;
+    }
+
+    if write_result.is_err() {
+        let error = write_result.unwrap_err();
+
+        error!("An error occurred while writing to trace file '{}': {}", file_path, error);
+    }
+}
+
+// collects finished spans and exports them in batches, either once a batch is
+// full or once the export interval has elapsed...
+pub async fn export_spans_async(tracer: Tracer) {
+    let receiver_option = tracer.receiver_arc.lock().unwrap().take();
+
+    // the spans can only be exported once...
+    if receiver_option.is_none() {
+        return;
+    }
+
+    let mut receiver = receiver_option.unwrap();
+    let client = Client::new();
+    let mut batch: Vec<FinishedSpan> = Vec::with_capacity(MAXIMUM_BATCH_SIZE);
+
+    loop {
+        let deadline = Instant::now() + Duration::from_millis(EXPORT_INTERVAL_IN_MILLISECONDS);
+
+        while batch.len() < MAXIMUM_BATCH_SIZE {
+            let receive_result = timeout_at(deadline, receiver.recv()).await;
+
+            // if the export interval has elapsed or all senders are gone...
+            if receive_result.is_err() || receive_result.as_ref().unwrap().is_none() {
+                break;
+            }
+
+            batch.push(receive_result.unwrap().unwrap());
+        }
+
+        if batch.len() == 0 {
+            continue;
+        }
+
+        let body = to_otlp_request(&batch);
+
+        batch.clear();
+
+        match &tracer.exporter {
+            TraceExporter::Otlp(endpoint) => post_to_otlp_endpoint_async(&client, endpoint.as_str(), body).await,
+            TraceExporter::File(file_path) => append_to_file_async(file_path.as_str(), body).await,
+            TraceExporter::None => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn parses_valid_traceparent() {
+        let span_context = parse_traceparent(TRACEPARENT).unwrap();
+
+        assert_eq!(span_context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(span_context.span_id, "00f067aa0ba902b7");
+        assert!(span_context.is_sampled);
+        assert_eq!(span_context.to_traceparent(), TRACEPARENT);
+
+        let span_context = parse_traceparent(" 00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00 ").unwrap();
+
+        assert!(!span_context.is_sampled);
+    }
+
+    #[test]
+    fn accepts_fields_appended_by_later_versions() {
+        let span_context = parse_traceparent("cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-future").unwrap();
+
+        assert_eq!(span_context.span_id, "00f067aa0ba902b7");
+    }
+
+    #[test]
+    fn rejects_invalid_traceparents() {
+        let traceparents = [
+            "",
+            // forbidden version...
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            // version 00 has no additional fields...
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-future",
+            // all-zero IDs...
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            // upper case and non-hexadecimal characters...
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473g-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-0x",
+            // truncated trace ID...
+            "00-4bf92f3577b34da6a3ce929d0e0e47-00f067aa0ba902b7-01",
+        ];
+
+        for traceparent in traceparents {
+            assert!(parse_traceparent(traceparent).is_none(), "{}", traceparent);
+        }
+    }
+
+    #[test]
+    fn extracts_combined_tracestate() {
+        let mut headers = HeaderMap::new();
+
+        headers.insert(TRACEPARENT_HEADER_NAME, HeaderValue::from_static(TRACEPARENT));
+        headers.append(TRACESTATE_HEADER_NAME, HeaderValue::from_static("congo=t61rcWkgMzE"));
+        headers.append(TRACESTATE_HEADER_NAME, HeaderValue::from_static("rojo=00f067aa0ba902b7"));
+
+        let span_context = extract(&headers).unwrap();
+
+        assert_eq!(span_context.trace_state, Some(String::from("congo=t61rcWkgMzE,rojo=00f067aa0ba902b7")));
+    }
+}
@@ -0,0 +1,127 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
+use std::{
+    fs::{self, Permissions},
+    io,
+    os::unix::{
+        fs::{chown, FileTypeExt, PermissionsExt},
+        net::UnixStream,
+    },
+    path::Path,
+};
+use tokio::net::UnixListener;
+use log::error;
+
+use crate::remote_address::PeerCredentials;
+
+pub fn parse_file_mode(file_mode: &str) -> Option<u32> {
+    // file mode is empty means we shall not change the mode...
+    if file_mode.len() == 0 {
+        return None;
+    }
+
+    let parse_result = u32::from_str_radix(file_mode, 8);
+
+    if parse_result.is_err() {
+        let error = parse_result.unwrap_err();
+
+        eprintln!("An error occurred while parsing file mode '{}' as octal: {}", file_mode, error);
+
+        return None;
+    }
+
+    return Some(parse_result.unwrap());
+}
+
+fn remove_stale_socket(socket_path: &str) -> io::Result<()> {
+    let metadata_result = fs::symlink_metadata(socket_path);
+
+    // if nothing exists at the path, there is nothing to clean up...
+    if metadata_result.is_err() {
+        return Ok(());
+    }
+
+    let metadata = metadata_result.unwrap();
+
+    // we shall never remove anything that is not a socket...
+    if !metadata.file_type().is_socket() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("'{}' exists and is not a socket.", socket_path),
+        ));
+    }
+
+    // if we can connect to the socket, another server is still listening on it...
+    if UnixStream::connect(socket_path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("Another process is already listening on '{}'.", socket_path),
+        ));
+    }
+
+    // otherwise, the socket is stale and we shall remove it...
+    return fs::remove_file(socket_path);
+}
+
+pub fn bind(
+    socket_path: &str,
+    file_mode: Option<u32>,
+    owner_id: Option<u32>,
+    group_id: Option<u32>,
+) -> io::Result<UnixListener> {
+    remove_stale_socket(socket_path)?;
+
+    // the socket is created with the permissions allowed by the umask. the requested
+    // ownership and mode are applied on the socket path right after binding...
+    let unix_listener = UnixListener::bind(socket_path)?;
+
+    // if either owner or group is specified...
+    if owner_id.is_some() || group_id.is_some() {
+        // we shall change the ownership of the socket before widening its mode...
+        chown(socket_path, owner_id, group_id)?;
+    }
+
+    // if file mode is specified...
+    if file_mode.is_some() {
+        // we shall apply the file mode on the socket...
+        fs::set_permissions(socket_path, Permissions::from_mode(file_mode.unwrap()))?;
+    }
+
+    return Ok(unix_listener);
+}
+
+pub fn remove(socket_path: &str) {
+    // if the socket no longer exists, there is nothing to remove...
+    if !Path::new(socket_path).exists() {
+        return;
+    }
+
+    let remove_result = fs::remove_file(socket_path);
+
+    if remove_result.is_err() {
+        let error = remove_result.unwrap_err();
+
+        error!("An error occurred while removing Unix domain socket '{}': {}", socket_path, error);
+    }
+}
+
+// none if the operating system could not tell who the peer is...
+pub fn get_peer_credentials(unix_stream: &tokio::net::UnixStream) -> Option<PeerCredentials> {
+    let peer_credentials_result = unix_stream.peer_cred();
+
+    if peer_credentials_result.is_err() {
+        let error = peer_credentials_result.unwrap_err();
+
+        error!("An error occurred while retrieving peer credentials: {}", error);
+
+        return None;
+    }
+
+    let peer_credentials = peer_credentials_result.unwrap();
+
+    return Some(PeerCredentials {
+        process_id: peer_credentials.pid(),
+        user_id: peer_credentials.uid(),
+        group_id: peer_credentials.gid(),
+    });
+}
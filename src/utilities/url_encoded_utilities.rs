@@ -0,0 +1,309 @@
+#![allow(clippy::needless_return, clippy::len_zero, clippy::assign_op_pattern)]
+
+use std::collections::HashMap;
+use serde_json::{Map, Value};
+use log::debug;
+
+// limits protect us from requests crafted to make parsing expensive
+// (the same limits are used by qs, the parser of Express)...
+const MAXIMUM_PARAMETER_COUNT: usize = 1000;
+const MAXIMUM_NESTING_DEPTH: usize = 5;
+// larger indices (e.g. "a[999999]") become object keys instead of huge arrays...
+const MAXIMUM_ARRAY_INDEX: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UrlEncodedParser {
+    // flat map of keys to all of their values (e.g. "a[b]=1" yields "a[b]": ["1"])...
+    Simple,
+    // additionally nested JSON as produced by qs (e.g. "a[b][]=1" yields {"a": {"b": ["1"]}})...
+    Extended,
+}
+
+pub fn parse_parser(parser: &str) -> UrlEncodedParser {
+    return match parser.trim().to_lowercase().as_str() {
+        "" | "simple" => UrlEncodedParser::Simple,
+        "extended" => UrlEncodedParser::Extended,
+        _ => {
+            eprintln!("Invalid URL encoded parser '{}'. Expected simple or extended. Falling back to simple.", parser);
+
+            UrlEncodedParser::Simple
+        },
+    };
+}
+
+fn to_hexadecimal_digit(byte: u8) -> Option<u8> {
+    return (byte as char).to_digit(16).map(|digit| digit as u8);
+}
+
+// decodes as per application/x-www-form-urlencoded. malformed escapes (e.g. "%zz")
+// are kept as they are and invalid UTF-8 is replaced instead of failing...
+pub fn decode_component(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded_bytes: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let byte = bytes[index];
+
+        if byte == b'+' {
+            decoded_bytes.push(b' ');
+        } else if byte == b'%' && index + 2 < bytes.len()
+            && to_hexadecimal_digit(bytes[index + 1]).is_some()
+            && to_hexadecimal_digit(bytes[index + 2]).is_some() {
+            let high_nibble = to_hexadecimal_digit(bytes[index + 1]).unwrap();
+            let low_nibble = to_hexadecimal_digit(bytes[index + 2]).unwrap();
+
+            decoded_bytes.push(high_nibble * 16 + low_nibble);
+            index = index + 2;
+        } else {
+            decoded_bytes.push(byte);
+        }
+
+        index = index + 1;
+    }
+
+    return String::from_utf8_lossy(&decoded_bytes).into_owned();
+}
+
+// splits into decoded key-value pairs. a key without "=" (e.g. "?flag") has an empty value...
+fn parse_pairs(url_encoded_string: &str) -> Vec<(String, String)> {
+    let mut pairs: Vec<(String, String)> = Vec::new();
+
+    for pair in url_encoded_string.split('&') {
+        if pair.len() == 0 {
+            continue;
+        }
+
+        if pairs.len() == MAXIMUM_PARAMETER_COUNT {
+            debug!("Ignoring URL encoded parameters beyond the first {}.", MAXIMUM_PARAMETER_COUNT);
+
+            break;
+        }
+
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = decode_component(key);
+
+        if key.len() == 0 {
+            continue;
+        }
+
+        pairs.push((key, decode_component(value)));
+    }
+
+    return pairs;
+}
+
+pub fn parse(url_encoded_string: &str) -> HashMap<String, Vec<String>> {
+    let mut url_encoded_data_map: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (key, value) in parse_pairs(url_encoded_string) {
+        url_encoded_data_map.entry(key).or_default().push(value);
+    }
+
+    return url_encoded_data_map;
+}
+
+// splits "a[b][]" into "a", "b" and "". anything nested deeper than
+// allowed is kept as a single literal segment...
+fn split_key(key: &str) -> Vec<String> {
+    let index_of_bracket_option = key.find('[');
+
+    if index_of_bracket_option.is_none() {
+        return vec![key.to_string()];
+    }
+
+    let index_of_bracket = index_of_bracket_option.unwrap();
+    let mut segments: Vec<String> = Vec::new();
+    let mut remaining_key = &key[index_of_bracket..];
+
+    // "[a]=1" is treated like "a=1"...
+    if index_of_bracket > 0 {
+        segments.push(key[..index_of_bracket].to_string());
+    }
+
+    while remaining_key.starts_with('[') && segments.len() <= MAXIMUM_NESTING_DEPTH {
+        let index_of_closing_bracket_option = remaining_key.find(']');
+
+        if index_of_closing_bracket_option.is_none() {
+            break;
+        }
+
+        let index_of_closing_bracket = index_of_closing_bracket_option.unwrap();
+        let segment = &remaining_key[1..index_of_closing_bracket];
+
+        // brackets within brackets are not a valid segment...
+        if segment.contains('[') {
+            break;
+        }
+
+        segments.push(segment.to_string());
+        remaining_key = &remaining_key[index_of_closing_bracket + 1..];
+    }
+
+    // if the key does not contain a single valid segment (e.g. "a[b"), it is taken literally...
+    if segments.len() == 0 || (index_of_bracket > 0 && segments.len() == 1) {
+        return vec![key.to_string()];
+    }
+
+    if remaining_key.len() > 0 {
+        segments.push(remaining_key.to_string());
+    }
+
+    return segments;
+}
+
+fn parse_array_index(segment: &str) -> Option<usize> {
+    if segment.len() == 0 || !segment.chars().all(|character| character.is_ascii_digit()) {
+        return None;
+    }
+
+    return segment.parse::<usize>().ok().filter(|index| *index <= MAXIMUM_ARRAY_INDEX);
+}
+
+fn to_object(values: Vec<Value>) -> Map<String, Value> {
+    let mut object = Map::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        object.insert(index.to_string(), value);
+    }
+
+    return object;
+}
+
+fn insert(target: &mut Value, segments: &[String], value: String) {
+    if segments.len() == 0 {
+        match target {
+            Value::Null => *target = Value::String(value),
+            // duplicate keys (e.g. "a=1&a=2") are collected in an array...
+            Value::String(_) => *target = Value::Array(vec![target.take(), Value::String(value)]),
+            Value::Array(values) => values.push(Value::String(value)),
+            _ => debug!("Ignoring URL encoded value '{}' as its key is already used for nested data.", value),
+        }
+
+        return;
+    }
+
+    let segment = segments[0].as_str();
+    let remaining_segments = &segments[1..];
+    let array_index_option = parse_array_index(segment);
+    let is_array_segment = segment.len() == 0 || array_index_option.is_some();
+
+    // the shape of the target is decided by the first segment that reaches it.
+    // if a later one disagrees, the target is turned into an object...
+    match target {
+        Value::Null => *target = if is_array_segment { Value::Array(Vec::new()) } else { Value::Object(Map::new()) },
+        Value::String(_) => {
+            let values = vec![target.take()];
+
+            *target = if is_array_segment { Value::Array(values) } else { Value::Object(to_object(values)) };
+        },
+        Value::Array(values) if !is_array_segment => *target = Value::Object(to_object(std::mem::take(values))),
+        _ => {},
+    }
+
+    match target {
+        Value::Array(values) => {
+            // "[]" appends and indices beyond the end are compacted (as qs does)...
+            if array_index_option.is_none() || array_index_option.unwrap() >= values.len() {
+                values.push(Value::Null);
+            }
+
+            let index = array_index_option.filter(|index| *index < values.len()).unwrap_or(values.len() - 1);
+
+            insert(&mut values[index], remaining_segments, value);
+        },
+        Value::Object(object) => {
+            let key = if segment.len() == 0 { object.len().to_string() } else { segment.to_string() };
+
+            insert(object.entry(key).or_insert(Value::Null), remaining_segments, value);
+        },
+        _ => {},
+    }
+}
+
+pub fn parse_nested(url_encoded_string: &str) -> Value {
+    let mut nested_data = Value::Object(Map::new());
+
+    for (key, value) in parse_pairs(url_encoded_string) {
+        insert(&mut nested_data, &split_key(key.as_str()), value);
+    }
+
+    return nested_data;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn decodes_components_leniently() {
+        assert_eq!(decode_component("a+b%20c%2Bd"), "a b c+d");
+        assert_eq!(decode_component("%E2%9C%93"), "✓");
+        // malformed escapes are kept and invalid UTF-8 is replaced...
+        assert_eq!(decode_component("100%"), "100%");
+        assert_eq!(decode_component("%zz%4"), "%zz%4");
+        assert_eq!(decode_component("%FF"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn parses_flat_pairs() {
+        let url_encoded_data_map = parse("a=1&a=2&flag&=ignored&&b%5B%5D=x+y&c=");
+
+        assert_eq!(url_encoded_data_map.len(), 4);
+        assert_eq!(url_encoded_data_map["a"], vec!["1", "2"]);
+        assert_eq!(url_encoded_data_map["flag"], vec![""]);
+        assert_eq!(url_encoded_data_map["b[]"], vec!["x y"]);
+        assert_eq!(url_encoded_data_map["c"], vec![""]);
+    }
+
+    #[test]
+    fn limits_parameter_count() {
+        let url_encoded_string = (0..MAXIMUM_PARAMETER_COUNT + 10)
+            .map(|index| format!("key{}=value", index))
+            .collect::<Vec<String>>()
+            .join("&");
+
+        assert_eq!(parse(url_encoded_string.as_str()).len(), MAXIMUM_PARAMETER_COUNT);
+    }
+
+    #[test]
+    fn splits_keys_into_segments() {
+        assert_eq!(split_key("a"), vec!["a"]);
+        assert_eq!(split_key("a[b][]"), vec!["a", "b", ""]);
+        assert_eq!(split_key("[a]"), vec!["a"]);
+        assert_eq!(split_key("a[b"), vec!["a[b"]);
+        assert_eq!(split_key("a[b]c"), vec!["a", "b", "c"]);
+        // segments beyond the maximum depth are kept as one literal segment...
+        assert_eq!(split_key("a[1][2][3][4][5][6][7]"), vec!["a", "1", "2", "3", "4", "5", "[6][7]"]);
+    }
+
+    #[test]
+    fn parses_nested_data() {
+        assert_eq!(
+            parse_nested("user[name]=alice&user[roles][]=admin&user[roles][]=dev&tags=a&tags=b"),
+            json!({ "user": { "name": "alice", "roles": ["admin", "dev"] }, "tags": ["a", "b"] }),
+        );
+        // indices beyond the end are compacted...
+        assert_eq!(parse_nested("a[5]=x&a[1]=y"), json!({ "a": ["x", "y"] }));
+        // large indices become object keys...
+        assert_eq!(parse_nested("a[999999]=x"), json!({ "a": { "999999": "x" } }));
+        assert_eq!(parse_nested("items[0][id]=1&items[0][name]=one"), json!({ "items": [{ "id": "1", "name": "one" }] }));
+    }
+
+    #[test]
+    fn resolves_conflicting_shapes() {
+        // an array that receives a named key becomes an object...
+        assert_eq!(parse_nested("a[]=x&a[b]=y"), json!({ "a": { "0": "x", "b": "y" } }));
+        assert_eq!(parse_nested("a=x&a[b]=y"), json!({ "a": { "0": "x", "b": "y" } }));
+        // a plain value cannot replace nested data...
+        assert_eq!(parse_nested("a[b]=x&a=y"), json!({ "a": { "b": "x" } }));
+    }
+
+    #[test]
+    fn parses_parser_names() {
+        assert_eq!(parse_parser(""), UrlEncodedParser::Simple);
+        assert_eq!(parse_parser(" Extended "), UrlEncodedParser::Extended);
+        assert_eq!(parse_parser("qs"), UrlEncodedParser::Simple);
+    }
+}
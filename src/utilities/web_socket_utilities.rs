@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return, clippy::unnecessary_unwrap, clippy::len_zero)]
+
 const CONNECTION_HEADER_NAME: &str = "connection";
 const CONNECTION_HEADER_VALUE_UPGRADE: &str = "upgrade";
 const UPGRADE_HEADER_NAME: &str = "upgrade";
@@ -18,6 +20,7 @@ use tungstenite::{
     handshake,
     error::ProtocolError,
 };
+use log::warn;
 
 use crate::http_utilities;
 
@@ -57,7 +60,7 @@ pub fn upgrade(mut request: impl BorrowMut<Request<Body>>,
     if web_socket_version_parse_result.is_err() {
         let error = web_socket_version_parse_result.unwrap_err();
 
-        eprintln!("An error occurred while parsing web socket version {} as integer: {}", web_socket_version_header_value, error);
+        warn!("An error occurred while parsing web socket version {} as integer: {}", web_socket_version_header_value, error);
 
         // we shall return error...
         return Err(ProtocolError::MissingSecWebSocketVersionHeader);
@@ -103,7 +106,7 @@ pub async fn get_upgraded_connection(on_upgrade: OnUpgrade) -> Option<Upgraded>
     if upgrade_result.is_err() {
         let error = upgrade_result.unwrap_err();
 
-        eprintln!("An error occurred while upgrading connection to WebSocket: {}", error);
+        warn!("An error occurred while upgrading connection to WebSocket: {}", error);
 
         // we shall return none...
         return None;